@@ -0,0 +1,76 @@
+//! Compares cloning and reading a large `Value` with its `SharedValue` form.
+//!
+//! Run with `cargo bench --bench shared_value`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use teo_teon::{teon, Value};
+use teo_teon::shared::SharedValue;
+
+fn records(count: usize) -> Value {
+    Value::Array((0..count).map(|i| teon!({
+        "id": i as i64,
+        "name": format!("user {}", i),
+        "email": format!("user{}@example.com", i),
+        "tags": ["a", "b", "c"],
+        "address": {"city": "Paris", "zip": "75001", "lines": ["1 rue de Rivoli"]},
+    })).collect())
+}
+
+fn measure(name: &str, iterations: u32, mut f: impl FnMut()) {
+    f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iteration = start.elapsed() / iterations;
+    println!("{:<40} {:>12}", name, format_duration(per_iteration));
+}
+
+fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos >= 1_000_000 {
+        format!("{:.2} ms", nanos as f64 / 1_000_000.0)
+    } else if nanos >= 1_000 {
+        format!("{:.2} µs", nanos as f64 / 1_000.0)
+    } else {
+        format!("{} ns", nanos)
+    }
+}
+
+fn main() {
+    for count in [100, 10_000] {
+        let value = records(count);
+        let shared = SharedValue::from(value.clone());
+        println!("{} records", count);
+        measure("Value clone of one record", 50, || {
+            black_box(value[count / 2].clone());
+        });
+        measure("SharedValue clone of one record", 50, || {
+            black_box(shared[count / 2].clone());
+        });
+        measure("Value::clone", 50, || {
+            black_box(value.clone());
+        });
+        measure("SharedValue::clone", 50, || {
+            black_box(shared.clone());
+        });
+        measure("Value clone, then change one record", 50, || {
+            let mut copy = value.clone();
+            copy.as_array_mut().unwrap()[0] = Value::Null;
+            black_box(copy);
+        });
+        measure("SharedValue clone, then change one record", 50, || {
+            let mut copy = shared.clone();
+            copy.as_array_mut().unwrap()[0] = SharedValue::Null;
+            black_box(copy);
+        });
+        measure("SharedValue::from(Value)", 10, || {
+            black_box(SharedValue::from(value.clone()));
+        });
+        measure("Value::from(&SharedValue)", 10, || {
+            black_box(Value::from(&shared));
+        });
+        println!();
+    }
+}
@@ -0,0 +1,231 @@
+//! Deterministic encoding of values for hashing and signing.
+//!
+//! Logically equal values have the same canonical form: dictionary keys are sorted, `Int` is
+//! widened to `Int64`, `Float32` to `Float`, negative zero and NaN floats are normalized,
+//! decimals are normalized and datetimes are truncated to millisecond precision.
+//!
+//! The byte encoding produced by [`Value::canonical_bytes`] is stable across versions of this
+//! crate. Each value is a one byte tag followed by its payload. Lengths and counts are unsigned
+//! 64-bit big-endian integers, and strings are length-prefixed UTF-8.
+//!
+//! | Tag    | Type            | Payload                                                   |
+//! |--------|-----------------|-----------------------------------------------------------|
+//! | `0x00` | `Null`          | none                                                      |
+//! | `0x01` | `Bool`          | `0x00` or `0x01`                                          |
+//! | `0x02` | `Int`, `Int64`  | signed 64-bit big-endian integer                          |
+//! | `0x03` | `Float32`, `Float` | big-endian IEEE 754 double                             |
+//! | `0x04` | `Decimal`       | string of the normalized decimal                          |
+//! | `0x05` | `ObjectId`      | 12 bytes                                                  |
+//! | `0x06` | `String`        | string                                                    |
+//! | `0x07` | `Date`          | string formatted `YYYY-MM-DD`                             |
+//! | `0x08` | `DateTime`      | signed 64-bit big-endian milliseconds since the Unix epoch |
+//! | `0x09` | `Array`         | count, then each value                                    |
+//! | `0x0a` | `Dictionary`    | count, then each key string and value, sorted by key      |
+//! | `0x0b` | `Range`         | `0x00` or `0x01` for closed, then the start and end values |
+//! | `0x0c` | `Tuple`         | count, then each value                                    |
+//! | `0x0d` | `EnumVariant`   | string, then `0x00`, or `0x01` and the arguments as a dictionary |
+//! | `0x0e` | `OptionVariant` | signed 32-bit big-endian integer                          |
+//! | `0x0f` | `Regex`         | pattern string, then flags string                         |
+//! | `0x10` | `File`          | file path, file name, content type and extension strings (`0x00`, or `0x01` and the string, for missing values), then content (`0x00`, or `0x01` and the bytes) |
+//! | `0x11` | `Bytes`         | length, then the bytes                                    |
+
+use std::collections::BTreeMap;
+use chrono::{TimeZone, Utc};
+use indexmap::IndexMap;
+use crate::types::file::File;
+use crate::types::range::Range;
+use crate::utils::sha256::{Sha256, to_hex};
+use crate::value::Value;
+
+impl Value {
+
+    /// The canonical form of this value. See the [module documentation](crate::canonical).
+    pub fn canonicalize(&self) -> Value {
+        match self {
+            Value::Int(i) => Value::Int64(*i as i64),
+            Value::Float32(f) => Value::Float(canonical_float(*f as f64)),
+            Value::Float(f) => Value::Float(canonical_float(*f)),
+            Value::Decimal(d) => Value::Decimal(d.normalized()),
+            Value::DateTime(d) => Value::DateTime(Utc.timestamp_millis_opt(d.timestamp_millis()).unwrap()),
+            Value::Array(a) => Value::Array(a.iter().map(Value::canonicalize).collect()),
+            Value::Tuple(t) => Value::Tuple(t.iter().map(Value::canonicalize).collect()),
+            Value::Dictionary(m) => {
+                let sorted: BTreeMap<String, Value> = m.iter().map(|(k, v)| (k.clone(), v.canonicalize())).collect();
+                Value::Dictionary(sorted.into_iter().collect::<IndexMap<_, _>>())
+            }
+            Value::Range(r) => Value::Range(Range {
+                closed: r.closed,
+                start: Box::new(r.start.canonicalize()),
+                end: Box::new(r.end.canonicalize()),
+            }),
+            Value::EnumVariant(e) => {
+                let mut e = e.clone();
+                if let Some(args) = e.args.as_mut() {
+                    args.values_mut().for_each(|v| *v = v.canonicalize());
+                }
+                Value::EnumVariant(e)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// The stable byte encoding of the canonical form of this value. See the
+    /// [module documentation](crate::canonical) for the format.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut output = vec![];
+        encode(&self.canonicalize(), &mut output);
+        output
+    }
+
+    /// The hex encoded SHA-256 digest of [`Value::canonical_bytes`]. Logically equal values have
+    /// the same digest, which is stable across versions of this crate.
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.canonical_bytes());
+        to_hex(&hasher.finalize())
+    }
+}
+
+fn canonical_float(f: f64) -> f64 {
+    if f.is_nan() {
+        f64::NAN
+    } else if f == 0.0 {
+        0.0
+    } else {
+        f
+    }
+}
+
+fn encode_len(len: usize, output: &mut Vec<u8>) {
+    output.extend_from_slice(&(len as u64).to_be_bytes());
+}
+
+fn encode_str(s: &str, output: &mut Vec<u8>) {
+    encode_len(s.len(), output);
+    output.extend_from_slice(s.as_bytes());
+}
+
+fn encode_optional_str(s: Option<&String>, output: &mut Vec<u8>) {
+    match s {
+        Some(s) => {
+            output.push(1);
+            encode_str(s, output);
+        }
+        None => output.push(0),
+    }
+}
+
+fn encode_file(file: &File, output: &mut Vec<u8>) {
+    encode_str(&file.filepath, output);
+    encode_str(&file.filename, output);
+    encode_optional_str(file.content_type.as_ref(), output);
+    encode_optional_str(file.filename_ext.as_ref(), output);
+    match &file.content {
+        Some(content) => {
+            output.push(1);
+            encode_len(content.len(), output);
+            output.extend_from_slice(content);
+        }
+        None => output.push(0),
+    }
+}
+
+// Encodes a canonicalized value.
+fn encode(value: &Value, output: &mut Vec<u8>) {
+    match value {
+        Value::Null => output.push(0x00),
+        Value::Bool(b) => output.extend_from_slice(&[0x01, *b as u8]),
+        Value::Int(i) => {
+            output.push(0x02);
+            output.extend_from_slice(&(*i as i64).to_be_bytes());
+        }
+        Value::Int64(i) => {
+            output.push(0x02);
+            output.extend_from_slice(&i.to_be_bytes());
+        }
+        Value::Float32(f) => {
+            output.push(0x03);
+            output.extend_from_slice(&(*f as f64).to_be_bytes());
+        }
+        Value::Float(f) => {
+            output.push(0x03);
+            output.extend_from_slice(&f.to_be_bytes());
+        }
+        Value::Decimal(d) => {
+            output.push(0x04);
+            encode_str(&d.to_string(), output);
+        }
+        Value::ObjectId(o) => {
+            output.push(0x05);
+            output.extend_from_slice(&o.bytes());
+        }
+        Value::String(s) => {
+            output.push(0x06);
+            encode_str(s, output);
+        }
+        Value::Date(d) => {
+            output.push(0x07);
+            encode_str(&d.format("%Y-%m-%d").to_string(), output);
+        }
+        Value::DateTime(d) => {
+            output.push(0x08);
+            output.extend_from_slice(&d.timestamp_millis().to_be_bytes());
+        }
+        Value::Array(a) => {
+            output.push(0x09);
+            encode_len(a.len(), output);
+            a.iter().for_each(|v| encode(v, output));
+        }
+        Value::Dictionary(m) => {
+            output.push(0x0a);
+            encode_len(m.len(), output);
+            for (k, v) in m {
+                encode_str(k, output);
+                encode(v, output);
+            }
+        }
+        Value::Range(r) => {
+            output.extend_from_slice(&[0x0b, r.closed as u8]);
+            encode(&r.start, output);
+            encode(&r.end, output);
+        }
+        Value::Tuple(t) => {
+            output.push(0x0c);
+            encode_len(t.len(), output);
+            t.iter().for_each(|v| encode(v, output));
+        }
+        Value::EnumVariant(e) => {
+            output.push(0x0d);
+            encode_str(&e.value, output);
+            match &e.args {
+                Some(args) => {
+                    output.extend_from_slice(&[0x01, 0x0a]);
+                    encode_len(args.len(), output);
+                    for (k, v) in args {
+                        encode_str(k, output);
+                        encode(v, output);
+                    }
+                }
+                None => output.push(0x00),
+            }
+        }
+        Value::OptionVariant(o) => {
+            output.push(0x0e);
+            output.extend_from_slice(&o.value.to_be_bytes());
+        }
+        Value::Regex(r) => {
+            output.push(0x0f);
+            encode_str(r.pattern(), output);
+            encode_str(r.flags(), output);
+        }
+        Value::File(f) => {
+            output.push(0x10);
+            encode_file(f, output);
+        }
+        Value::Bytes(b) => {
+            output.push(0x11);
+            encode_len(b.len(), output);
+            output.extend_from_slice(b);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::Bson;
+use teo_result::Error;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+impl TryFrom<&Bson> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: &Bson) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Bson::Null | Bson::Undefined => Value::Null,
+            Bson::Boolean(b) => Value::Bool(*b),
+            Bson::Int32(i) => Value::Int(*i),
+            Bson::Int64(i) => Value::Int64(*i),
+            Bson::Double(f) => Value::Float(*f),
+            Bson::Decimal128(d) => Value::Decimal(BigDecimal::from_str(&d.to_string()).map_err(|_| Error::new(format!("Cannot convert bson value `{}` into Decimal", d)))?),
+            Bson::ObjectId(o) => Value::ObjectId(*o),
+            Bson::String(s) => Value::String(s.clone()),
+            Bson::DateTime(d) => Value::DateTime(d.to_chrono()),
+            Bson::Binary(b) => Value::Bytes(b.bytes.clone()),
+            Bson::RegularExpression(r) => {
+                // `u` and `l` have no effect on rust regexes, which are always unicode aware
+                let flags: String = r.options.chars().filter(|c| *c != 'u' && *c != 'l').collect();
+                Value::Regex(TeonRegex::new(r.pattern.as_str(), &flags)?)
+            }
+            Bson::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                Value::Array(vec)
+            }
+            Bson::Document(d) => {
+                let mut map = indexmap::IndexMap::new();
+                for (k, v) in d {
+                    map.insert(k.clone(), v.try_into()?);
+                }
+                Value::Dictionary(map)
+            }
+            _ => Err(Error::new(format!("Cannot convert bson value `{}` into Teon", value)))?,
+        })
+    }
+}
+
+impl TryFrom<Bson> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: Bson) -> Result<Self, Self::Error> {
+        Value::try_from(&value)
+    }
+}
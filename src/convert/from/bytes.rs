@@ -0,0 +1,49 @@
+use crate::value::Value;
+
+impl From<Vec<u8>> for Value {
+
+    fn from(v: Vec<u8>) -> Self {
+        Value::Bytes(v)
+    }
+}
+
+impl From<&Vec<u8>> for Value {
+
+    fn from(v: &Vec<u8>) -> Self {
+        Value::Bytes(v.clone())
+    }
+}
+
+impl From<&[u8]> for Value {
+
+    fn from(v: &[u8]) -> Self {
+        Value::Bytes(v.to_vec())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Value {
+
+    fn from(v: &[u8; N]) -> Self {
+        Value::Bytes(v.to_vec())
+    }
+}
+
+impl From<Option<Vec<u8>>> for Value {
+
+    fn from(v: Option<Vec<u8>>) -> Self {
+        match v {
+            Some(b) => Value::Bytes(b),
+            None => Value::Null,
+        }
+    }
+}
+
+impl From<Option<&[u8]>> for Value {
+
+    fn from(v: Option<&[u8]>) -> Self {
+        match v {
+            Some(b) => Value::Bytes(b.to_vec()),
+            None => Value::Null,
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use bigdecimal::BigDecimal;
+use bigdecimal::num_bigint::{BigInt, Sign};
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use ciborium::value::{Value as CborValue};
+use indexmap::IndexMap;
+use teo_result::Error;
+use crate::convert::into::cbor::{TAG_DATE, TAG_DATETIME, TAG_DECIMAL, TAG_ENUM_VARIANT, TAG_EPOCH_DATETIME, TAG_FILE, TAG_NEGATIVE_BIGNUM, TAG_OBJECT_ID, TAG_OPTION_VARIANT, TAG_POSITIVE_BIGNUM, TAG_RANGE, TAG_REGEX, TAG_TUPLE};
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::option_variant::OptionVariant;
+use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+// The largest decimal exponent accepted, which bounds the length of the decimal's string form.
+const MAX_DECIMAL_EXPONENT: u64 = 1 << 16;
+
+impl Value {
+
+    /// Decode CBOR written by [`Value::to_cbor`]. Integers decode as `Int64`, bignums as
+    /// `Decimal`, and both RFC 3339 and epoch datetimes as `DateTime`.
+    pub fn from_cbor(bytes: &[u8]) -> teo_result::Result<Value> {
+        let mut reader = bytes;
+        let value: CborValue = ciborium::de::from_reader(&mut reader).map_err(|e| Error::new(format!("Cannot decode cbor: {}", e)))?;
+        if !reader.is_empty() {
+            Err(Error::new(format!("Cannot decode cbor: {} trailing bytes", reader.len())))?
+        }
+        Value::try_from(&value)
+    }
+}
+
+fn bigint(value: &CborValue) -> Option<BigInt> {
+    match value {
+        CborValue::Integer(i) => Some(BigInt::from(i128::from(*i))),
+        CborValue::Tag(TAG_POSITIVE_BIGNUM, bytes) => Some(BigInt::from_bytes_be(Sign::Plus, bytes.as_bytes()?)),
+        CborValue::Tag(TAG_NEGATIVE_BIGNUM, bytes) => Some(-BigInt::from_bytes_be(Sign::Plus, bytes.as_bytes()?) - BigInt::from(1)),
+        _ => None,
+    }
+}
+
+fn string(value: &CborValue) -> Option<String> {
+    value.as_text().map(ToOwned::to_owned)
+}
+
+fn optional_string(value: Option<&CborValue>) -> Option<Option<String>> {
+    match value {
+        None | Some(CborValue::Null) => Some(None),
+        Some(value) => string(value).map(Some),
+    }
+}
+
+fn file(value: &CborValue) -> Option<File> {
+    let map = value.as_map()?;
+    let get = |key: &str| map.iter().find(|(k, _)| k.as_text() == Some(key)).map(|(_, v)| v);
+    Some(File {
+        filepath: string(get("filepath")?)?,
+        content_type: optional_string(get("contentType"))?,
+        filename: string(get("filename")?)?,
+        filename_ext: optional_string(get("filenameExt"))?,
+        content: match get("content") {
+            None | Some(CborValue::Null) => None,
+            Some(CborValue::Bytes(content)) => Some(content.clone()),
+            Some(_) => None?,
+        },
+    })
+}
+
+fn from_tag(tag: u64, content: &CborValue) -> Result<Value, Error> {
+    let invalid = || Error::new(format!("Cannot convert cbor tag {} into teon", tag));
+    let items = || content.as_array().ok_or_else(invalid);
+    Ok(match tag {
+        TAG_DATETIME => Value::DateTime(DateTime::parse_from_rfc3339(content.as_text().ok_or_else(invalid)?).map_err(|_| invalid())?.with_timezone(&Utc)),
+        TAG_EPOCH_DATETIME => {
+            let millis = match content {
+                CborValue::Integer(i) => i64::try_from(*i).ok().and_then(|i| i.checked_mul(1000)),
+                CborValue::Float(f) => Some((f * 1000.0).round() as i64),
+                _ => None,
+            };
+            Value::DateTime(millis.and_then(DateTime::from_timestamp_millis).ok_or_else(invalid)?)
+        }
+        TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM => Value::Decimal(BigDecimal::from(bigint(&CborValue::Tag(tag, Box::new(content.clone()))).ok_or_else(invalid)?)),
+        TAG_DECIMAL => match items()?.as_slice() {
+            [exponent, mantissa] => {
+                let exponent = exponent.as_integer().and_then(|e| i64::try_from(e).ok()).filter(|e| e.unsigned_abs() <= MAX_DECIMAL_EXPONENT).ok_or_else(invalid)?;
+                Value::Decimal(BigDecimal::new(bigint(mantissa).ok_or_else(invalid)?, -exponent))
+            }
+            _ => Err(invalid())?,
+        },
+        TAG_DATE => Value::Date(NaiveDate::parse_from_str(content.as_text().ok_or_else(invalid)?, "%Y-%m-%d").map_err(|_| invalid())?),
+        TAG_OBJECT_ID => Value::ObjectId(ObjectId::from_bytes(content.as_bytes().and_then(|b| b.as_slice().try_into().ok()).ok_or_else(invalid)?)),
+        TAG_RANGE => match items()?.as_slice() {
+            [closed, start, end] => Value::Range(Range {
+                closed: closed.as_bool().ok_or_else(invalid)?,
+                start: Box::new(Value::try_from(start)?),
+                end: Box::new(Value::try_from(end)?),
+            }),
+            _ => Err(invalid())?,
+        },
+        TAG_TUPLE => match Value::try_from(content)? {
+            Value::Array(items) => Value::Tuple(items),
+            _ => Err(invalid())?,
+        },
+        TAG_ENUM_VARIANT => match items()?.as_slice() {
+            [value, args] => Value::EnumVariant(EnumVariant {
+                value: string(value).ok_or_else(invalid)?,
+                args: match Value::try_from(args)? {
+                    Value::Null => None,
+                    Value::Dictionary(args) => Some(args.into_iter().collect::<BTreeMap<_, _>>()),
+                    _ => Err(invalid())?,
+                },
+            }),
+            _ => Err(invalid())?,
+        },
+        TAG_OPTION_VARIANT => match items()?.as_slice() {
+            [value, display] => Value::OptionVariant(OptionVariant {
+                value: value.as_integer().and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?,
+                display: string(display).ok_or_else(invalid)?,
+            }),
+            _ => Err(invalid())?,
+        },
+        TAG_REGEX => match items()?.as_slice() {
+            [pattern, flags] => Value::Regex(TeonRegex::new(string(pattern).ok_or_else(invalid)?, &string(flags).ok_or_else(invalid)?)?),
+            _ => Err(invalid())?,
+        },
+        TAG_FILE => Value::File(file(content).ok_or_else(invalid)?),
+        _ => Err(Error::new(format!("Cannot convert unknown cbor tag {} into teon", tag)))?,
+    })
+}
+
+impl TryFrom<&CborValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: &CborValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            CborValue::Null => Value::Null,
+            CborValue::Bool(b) => Value::Bool(*b),
+            CborValue::Integer(i) => match i64::try_from(*i) {
+                Ok(i) => Value::Int64(i),
+                Err(_) => Value::Float(i128::from(*i) as f64),
+            },
+            CborValue::Float(f) => Value::Float(*f),
+            CborValue::Text(s) => Value::String(s.clone()),
+            CborValue::Bytes(b) => Value::Bytes(b.clone()),
+            CborValue::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                Value::Array(vec)
+            }
+            CborValue::Map(m) => {
+                let mut map = IndexMap::new();
+                for (k, v) in m {
+                    let key = k.as_text().ok_or_else(|| Error::new(format!("Cannot convert cbor map key {:?} into string", k)))?;
+                    map.insert(key.to_owned(), v.try_into()?);
+                }
+                Value::Dictionary(map)
+            }
+            CborValue::Tag(tag, content) => from_tag(*tag, content)?,
+            _ => Err(Error::new("Cannot convert unknown cbor value into teon"))?,
+        })
+    }
+}
+
+impl TryFrom<CborValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: CborValue) -> Result<Self, Self::Error> {
+        Value::try_from(&value)
+    }
+}
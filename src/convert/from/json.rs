@@ -1,3 +1,5 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use serde_json::{Value as JsonValue};
 use crate::value::Value;
 
@@ -20,9 +22,17 @@ impl From<&JsonValue> for Value {
             JsonValue::Array(vec) => Self::Array(vec.iter().map(|v| {
                 Self::from(v)
             }).collect()),
-            JsonValue::Object(obj) => Self::Dictionary(obj.iter().map(|(k, v)| {
-                (k.to_owned(), Self::from(v))
-            }).collect()),
+            JsonValue::Object(obj) => {
+                // `{"$bytes": "<base64>"}` is how bytes are converted into json
+                if obj.len() == 1 {
+                    if let Some(bytes) = obj.get("$bytes").and_then(JsonValue::as_str).and_then(|s| BASE64.decode(s).ok()) {
+                        return Self::Bytes(bytes);
+                    }
+                }
+                Self::Dictionary(obj.iter().map(|(k, v)| {
+                    (k.to_owned(), Self::from(v))
+                }).collect())
+            }
         }
     }
 }
@@ -16,5 +16,16 @@ pub mod vec;
 pub mod value;
 pub mod enum_variant;
 pub mod option_variant;
+pub mod regex;
 pub mod file;
+pub mod bytes;
+pub mod bson;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "cbor")]
+pub mod cbor;
 pub mod error;
\ No newline at end of file
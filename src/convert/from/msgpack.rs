@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate};
+use indexmap::IndexMap;
+use rmpv::{Value as MsgpackValue};
+use teo_result::Error;
+use crate::convert::into::msgpack::{EXT_DATE, EXT_DECIMAL, EXT_ENUM_VARIANT, EXT_FILE, EXT_OBJECT_ID, EXT_OPTION_VARIANT, EXT_RANGE, EXT_REGEX, EXT_TIMESTAMP, EXT_TUPLE};
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::option_variant::OptionVariant;
+use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+impl Value {
+
+    /// Decode MessagePack written by [`Value::to_msgpack`]. Integers decode as `Int64`.
+    pub fn from_msgpack(bytes: &[u8]) -> teo_result::Result<Value> {
+        let mut reader = bytes;
+        let value = rmpv::decode::read_value(&mut reader).map_err(|e| Error::new(format!("Cannot decode msgpack: {}", e)))?;
+        if !reader.is_empty() {
+            Err(Error::new(format!("Cannot decode msgpack: {} trailing bytes", reader.len())))?
+        }
+        Value::try_from(&value)
+    }
+}
+
+fn decode_ext(data: &[u8]) -> Result<MsgpackValue, Error> {
+    let mut reader = data;
+    rmpv::decode::read_value(&mut reader).map_err(|e| Error::new(format!("Cannot decode msgpack extension: {}", e)))
+}
+
+fn string(value: &MsgpackValue) -> Option<String> {
+    value.as_str().map(ToOwned::to_owned)
+}
+
+fn optional_string(value: Option<&MsgpackValue>) -> Option<Option<String>> {
+    match value {
+        None | Some(MsgpackValue::Nil) => Some(None),
+        Some(value) => string(value).map(Some),
+    }
+}
+
+fn file(value: &MsgpackValue) -> Option<File> {
+    let map = value.as_map()?;
+    let get = |key: &str| map.iter().find(|(k, _)| k.as_str() == Some(key)).map(|(_, v)| v);
+    Some(File {
+        filepath: string(get("filepath")?)?,
+        content_type: optional_string(get("contentType"))?,
+        filename: string(get("filename")?)?,
+        filename_ext: optional_string(get("filenameExt"))?,
+        content: match get("content") {
+            None | Some(MsgpackValue::Nil) => None,
+            Some(MsgpackValue::Binary(content)) => Some(content.clone()),
+            Some(_) => None?,
+        },
+    })
+}
+
+fn from_ext(code: i8, data: &[u8]) -> Result<Value, Error> {
+    let invalid = || Error::new(format!("Cannot convert msgpack extension type {} into teon", code));
+    let text = || std::str::from_utf8(data).map_err(|_| invalid());
+    Ok(match code {
+        EXT_TIMESTAMP => {
+            let (seconds, nanos) = match data.len() {
+                4 => (u32::from_be_bytes(data.try_into().unwrap()) as i64, 0),
+                8 => {
+                    let raw = u64::from_be_bytes(data.try_into().unwrap());
+                    ((raw & 0x3_ffff_ffff) as i64, (raw >> 34) as u32)
+                }
+                12 => (i64::from_be_bytes(data[4..].try_into().unwrap()), u32::from_be_bytes(data[..4].try_into().unwrap())),
+                _ => Err(invalid())?,
+            };
+            Value::DateTime(DateTime::from_timestamp(seconds, nanos).ok_or_else(invalid)?)
+        }
+        EXT_DECIMAL => Value::Decimal(BigDecimal::from_str(text()?).map_err(|_| invalid())?),
+        EXT_OBJECT_ID => Value::ObjectId(ObjectId::from_bytes(data.try_into().map_err(|_| invalid())?)),
+        EXT_DATE => Value::Date(NaiveDate::parse_from_str(text()?, "%Y-%m-%d").map_err(|_| invalid())?),
+        EXT_RANGE => match decode_ext(data)? {
+            MsgpackValue::Array(items) if items.len() == 3 => Value::Range(Range {
+                closed: items[0].as_bool().ok_or_else(invalid)?,
+                start: Box::new(Value::try_from(&items[1])?),
+                end: Box::new(Value::try_from(&items[2])?),
+            }),
+            _ => Err(invalid())?,
+        },
+        EXT_TUPLE => match Value::try_from(&decode_ext(data)?)? {
+            Value::Array(items) => Value::Tuple(items),
+            _ => Err(invalid())?,
+        },
+        EXT_ENUM_VARIANT => match decode_ext(data)? {
+            MsgpackValue::Array(items) if items.len() == 2 => Value::EnumVariant(EnumVariant {
+                value: string(&items[0]).ok_or_else(invalid)?,
+                args: match Value::try_from(&items[1])? {
+                    Value::Null => None,
+                    Value::Dictionary(args) => Some(args.into_iter().collect::<BTreeMap<_, _>>()),
+                    _ => Err(invalid())?,
+                },
+            }),
+            _ => Err(invalid())?,
+        },
+        EXT_OPTION_VARIANT => match decode_ext(data)? {
+            MsgpackValue::Array(items) if items.len() == 2 => Value::OptionVariant(OptionVariant {
+                value: items[0].as_i64().and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?,
+                display: string(&items[1]).ok_or_else(invalid)?,
+            }),
+            _ => Err(invalid())?,
+        },
+        EXT_REGEX => match decode_ext(data)? {
+            MsgpackValue::Array(items) if items.len() == 2 => {
+                Value::Regex(TeonRegex::new(string(&items[0]).ok_or_else(invalid)?, &string(&items[1]).ok_or_else(invalid)?)?)
+            }
+            _ => Err(invalid())?,
+        },
+        EXT_FILE => Value::File(file(&decode_ext(data)?).ok_or_else(invalid)?),
+        _ => Err(Error::new(format!("Cannot convert unknown msgpack extension type {} into teon", code)))?,
+    })
+}
+
+impl TryFrom<&MsgpackValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: &MsgpackValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            MsgpackValue::Nil => Value::Null,
+            MsgpackValue::Boolean(b) => Value::Bool(*b),
+            MsgpackValue::Integer(i) => match i.as_i64() {
+                Some(i) => Value::Int64(i),
+                None => Value::Float(i.as_f64().unwrap()),
+            },
+            MsgpackValue::F32(f) => Value::Float32(*f),
+            MsgpackValue::F64(f) => Value::Float(*f),
+            MsgpackValue::String(s) => Value::String(s.as_str().ok_or_else(|| Error::new("Cannot convert msgpack string with invalid utf-8 into teon"))?.to_owned()),
+            MsgpackValue::Binary(b) => Value::Bytes(b.clone()),
+            MsgpackValue::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                Value::Array(vec)
+            }
+            MsgpackValue::Map(m) => {
+                let mut map = IndexMap::new();
+                for (k, v) in m {
+                    let key = k.as_str().ok_or_else(|| Error::new(format!("Cannot convert msgpack map key {} into string", k)))?;
+                    map.insert(key.to_owned(), v.try_into()?);
+                }
+                Value::Dictionary(map)
+            }
+            MsgpackValue::Ext(code, data) => from_ext(*code, data)?,
+        })
+    }
+}
+
+impl TryFrom<MsgpackValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: MsgpackValue) -> Result<Self, Self::Error> {
+        Value::try_from(&value)
+    }
+}
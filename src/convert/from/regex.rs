@@ -0,0 +1,31 @@
+use regex::Regex;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+impl From<Regex> for Value {
+
+    fn from(v: Regex) -> Self {
+        Value::Regex(TeonRegex::from(v))
+    }
+}
+
+impl From<&Regex> for Value {
+
+    fn from(v: &Regex) -> Self {
+        Value::Regex(TeonRegex::from(v.clone()))
+    }
+}
+
+impl From<TeonRegex> for Value {
+
+    fn from(v: TeonRegex) -> Self {
+        Value::Regex(v)
+    }
+}
+
+impl From<&TeonRegex> for Value {
+
+    fn from(v: &TeonRegex) -> Self {
+        Value::Regex(v.clone())
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use toml::{Value as TomlValue};
+use teo_result::Error;
+use crate::value::Value;
+
+impl Value {
+
+    /// Parse a TOML document into a dictionary. Offset date-times become `DateTime`, local
+    /// date-times are read as UTC, and local dates become `Date`.
+    pub fn from_toml_str(s: &str) -> teo_result::Result<Value> {
+        let table: toml::Table = toml::from_str(s).map_err(|e| Error::new(format!("Cannot parse toml: {}", e)))?;
+        Value::try_from(&TomlValue::Table(table))
+    }
+}
+
+impl TryFrom<&TomlValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: &TomlValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            TomlValue::String(s) => Value::String(s.clone()),
+            TomlValue::Integer(i) => Value::Int64(*i),
+            TomlValue::Float(f) => Value::Float(*f),
+            TomlValue::Boolean(b) => Value::Bool(*b),
+            TomlValue::Datetime(d) => {
+                let text = d.to_string();
+                match (d.date.is_some(), d.time.is_some(), d.offset.is_some()) {
+                    (true, true, true) => Value::DateTime(DateTime::parse_from_rfc3339(&text).map_err(|_| Error::new(format!("Cannot convert toml datetime {} into teon", text)))?.with_timezone(&Utc)),
+                    (true, true, false) => Value::DateTime(NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f").map_err(|_| Error::new(format!("Cannot convert toml datetime {} into teon", text)))?.and_utc()),
+                    (true, false, _) => Value::Date(NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| Error::new(format!("Cannot convert toml date {} into teon", text)))?),
+                    _ => Err(Error::new(format!("Cannot convert toml local time {} into teon", text)))?,
+                }
+            }
+            TomlValue::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                Value::Array(vec)
+            }
+            TomlValue::Table(t) => {
+                let mut map = indexmap::IndexMap::new();
+                for (k, v) in t {
+                    map.insert(k.clone(), v.try_into()?);
+                }
+                Value::Dictionary(map)
+            }
+        })
+    }
+}
+
+impl TryFrom<TomlValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: TomlValue) -> Result<Self, Self::Error> {
+        Value::try_from(&value)
+    }
+}
@@ -0,0 +1,104 @@
+use std::str::FromStr;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
+use serde_yaml::{Value as YamlValue};
+use serde_yaml::value::TaggedValue;
+use teo_result::Error;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+impl Value {
+
+    /// Parse a YAML document. The tags `!int`, `!float32`, `!decimal`, `!objectId`, `!date`,
+    /// `!datetime`, `!tuple`, `!regex` and `!bytes` produce the matching Teon variants.
+    pub fn from_yaml_str(s: &str) -> teo_result::Result<Value> {
+        let yaml: YamlValue = serde_yaml::from_str(s).map_err(|e| Error::new(format!("Cannot parse yaml: {}", e)))?;
+        Value::try_from(&yaml)
+    }
+}
+
+impl TryFrom<&YamlValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: &YamlValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            YamlValue::Null => Value::Null,
+            YamlValue::Bool(b) => Value::Bool(*b),
+            YamlValue::Number(n) => if let Some(i) = n.as_i64() {
+                Value::Int64(i)
+            } else {
+                Value::Float(n.as_f64().unwrap())
+            },
+            YamlValue::String(s) => Value::String(s.clone()),
+            YamlValue::Sequence(seq) => {
+                let mut vec = vec![];
+                for v in seq {
+                    vec.push(v.try_into()?);
+                }
+                Value::Array(vec)
+            }
+            YamlValue::Mapping(mapping) => {
+                let mut map = IndexMap::new();
+                for (k, v) in mapping {
+                    let key = match k {
+                        YamlValue::String(s) => s.clone(),
+                        YamlValue::Number(n) => n.to_string(),
+                        YamlValue::Bool(b) => b.to_string(),
+                        _ => Err(Error::new(format!("Cannot convert yaml mapping key {:?} into string", k)))?,
+                    };
+                    map.insert(key, v.try_into()?);
+                }
+                Value::Dictionary(map)
+            }
+            YamlValue::Tagged(tagged) => from_tagged(tagged)?,
+        })
+    }
+}
+
+impl TryFrom<YamlValue> for Value {
+
+    type Error = Error;
+
+    fn try_from(value: YamlValue) -> Result<Self, Self::Error> {
+        Value::try_from(&value)
+    }
+}
+
+fn from_tagged(tagged: &TaggedValue) -> Result<Value, Error> {
+    let tag = &tagged.tag;
+    let invalid = || Error::new(format!("Cannot convert yaml value {:?} tagged {} into teon", tagged.value, tag));
+    let string = || match &tagged.value {
+        YamlValue::String(s) => Ok(s.clone()),
+        YamlValue::Number(n) => Ok(n.to_string()),
+        _ => Err(invalid()),
+    };
+    Ok(if *tag == "int" {
+        Value::Int(tagged.value.as_i64().and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?)
+    } else if *tag == "float32" {
+        Value::Float32(tagged.value.as_f64().ok_or_else(invalid)? as f32)
+    } else if *tag == "decimal" {
+        Value::Decimal(BigDecimal::from_str(&string()?).map_err(|_| invalid())?)
+    } else if *tag == "objectId" {
+        Value::ObjectId(ObjectId::parse_str(string()?).map_err(|_| invalid())?)
+    } else if *tag == "date" {
+        Value::Date(NaiveDate::parse_from_str(&string()?, "%Y-%m-%d").map_err(|_| invalid())?)
+    } else if *tag == "datetime" {
+        Value::DateTime(DateTime::parse_from_rfc3339(&string()?).map_err(|_| invalid())?.with_timezone(&Utc))
+    } else if *tag == "tuple" {
+        match Value::try_from(&tagged.value)? {
+            Value::Array(items) => Value::Tuple(items),
+            _ => Err(invalid())?,
+        }
+    } else if *tag == "regex" {
+        Value::Regex(TeonRegex::from_str(&string()?)?)
+    } else if *tag == "bytes" {
+        Value::Bytes(BASE64.decode(string()?).map_err(|_| invalid())?)
+    } else {
+        Err(Error::new(format!("Cannot convert yaml value with unknown tag {}", tag)))?
+    })
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+use bson::{Binary, Bson, DateTime as BsonDateTime, Document, Regex as BsonRegex};
+use bson::spec::BinarySubtype;
+use bson::Decimal128;
+use chrono::{DateTime, Utc};
+use teo_result::Error;
+use crate::value::Value;
+
+impl TryFrom<&Value> for Bson {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Bson, Self::Error> {
+        Ok(match value {
+            Value::Null => Bson::Null,
+            Value::Bool(b) => Bson::Boolean(*b),
+            Value::Int(i) => Bson::Int32(*i),
+            Value::Int64(i) => Bson::Int64(*i),
+            Value::Float32(f) => Bson::Double(*f as f64),
+            Value::Float(f) => Bson::Double(*f),
+            Value::Decimal(d) => Bson::Decimal128(Decimal128::from_str(&d.normalized().to_string()).map_err(|_| Error::new(format!("Cannot convert Decimal `{}` into bson", d)))?),
+            Value::ObjectId(o) => Bson::ObjectId(*o),
+            Value::String(s) => Bson::String(s.clone()),
+            Value::Date(d) => Bson::DateTime(BsonDateTime::from_chrono(DateTime::<Utc>::from_naive_utc_and_offset(d.and_hms_opt(0, 0, 0).unwrap(), Utc))),
+            Value::DateTime(d) => Bson::DateTime(BsonDateTime::from_chrono(*d)),
+            Value::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                Bson::Array(vec)
+            }
+            Value::Dictionary(d) => {
+                let mut document = Document::new();
+                for (k, v) in d {
+                    document.insert(k.clone(), Bson::try_from(v)?);
+                }
+                Bson::Document(document)
+            }
+            Value::EnumVariant(e) => Bson::String(e.value.clone()),
+            Value::OptionVariant(o) => Bson::Int32(o.value),
+            Value::Regex(r) => Bson::RegularExpression(BsonRegex {
+                pattern: r.pattern().to_owned(),
+                options: r.flags().to_owned(),
+            }),
+            Value::Bytes(b) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: b.clone(),
+            }),
+            _ => Err(Error::new(format!("Cannot convert {} into bson", value.type_hint())))?,
+        })
+    }
+}
+
+impl TryFrom<Value> for Bson {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Bson, Self::Error> {
+        (&value).try_into()
+    }
+}
@@ -0,0 +1,64 @@
+use teo_result::Error;
+use crate::value::Value;
+
+impl TryFrom<Value> for Vec<u8> {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(Error::new(format!("Cannot convert {} into Bytes", value.type_hint()))),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Vec<u8> {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(b) => Ok(b.clone()),
+            _ => Err(Error::new(format!("Cannot convert {} into Bytes", value.type_hint()))),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a [u8] {
+
+    type Error = Error;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(Error::new(format!("Cannot convert {} into &Bytes", value.type_hint()))),
+        }
+    }
+}
+
+impl TryFrom<Value> for Option<Vec<u8>> {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(None),
+            Value::Bytes(b) => Ok(Some(b)),
+            _ => Err(Error::new(format!("Cannot convert {} into Option<Bytes>", value.type_hint()))),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for Option<&'a [u8]> {
+
+    type Error = Error;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(None),
+            Value::Bytes(b) => Ok(Some(b)),
+            _ => Err(Error::new(format!("Cannot convert {} into Option<&Bytes>", value.type_hint()))),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::SecondsFormat;
+use bigdecimal::num_bigint::{BigInt, Sign};
+use ciborium::value::{Integer, Value as CborValue};
+use teo_result::Error;
+use crate::value::Value;
+
+// Standard CBOR tags.
+pub(crate) const TAG_DATETIME: u64 = 0;
+pub(crate) const TAG_EPOCH_DATETIME: u64 = 1;
+pub(crate) const TAG_POSITIVE_BIGNUM: u64 = 2;
+pub(crate) const TAG_NEGATIVE_BIGNUM: u64 = 3;
+pub(crate) const TAG_DECIMAL: u64 = 4;
+pub(crate) const TAG_DATE: u64 = 1004;
+
+// CBOR tags of Teon values without a standard tag.
+pub(crate) const TAG_OBJECT_ID: u64 = 60000;
+pub(crate) const TAG_RANGE: u64 = 60001;
+pub(crate) const TAG_TUPLE: u64 = 60002;
+pub(crate) const TAG_ENUM_VARIANT: u64 = 60003;
+pub(crate) const TAG_OPTION_VARIANT: u64 = 60004;
+pub(crate) const TAG_REGEX: u64 = 60005;
+pub(crate) const TAG_FILE: u64 = 60006;
+
+impl Value {
+
+    /// Encode the value as CBOR. Teon types without a native CBOR type are tagged:
+    ///
+    /// | Type            | Tag   | Content                                             |
+    /// |-----------------|-------|-----------------------------------------------------|
+    /// | `DateTime`      | 0     | RFC 3339 string                                     |
+    /// | `Decimal`       | 4     | `[exponent, mantissa]` decimal fraction             |
+    /// | `Date`          | 1004  | `YYYY-MM-DD` string                                 |
+    /// | `ObjectId`      | 60000 | 12 bytes                                            |
+    /// | `Range`         | 60001 | `[closed, start, end]`                              |
+    /// | `Tuple`         | 60002 | array                                               |
+    /// | `EnumVariant`   | 60003 | `[value, args]`, `args` a map or null               |
+    /// | `OptionVariant` | 60004 | `[value, display]`                                  |
+    /// | `Regex`         | 60005 | `[pattern, flags]`                                  |
+    /// | `File`          | 60006 | map of the file fields                              |
+    ///
+    /// CBOR has one integer type and doesn't distinguish float widths, so `Int` decodes as
+    /// `Int64` and `Float32` as `Float`.
+    pub fn to_cbor(&self) -> teo_result::Result<Vec<u8>> {
+        let mut output = vec![];
+        ciborium::ser::into_writer(&CborValue::try_from(self)?, &mut output).map_err(|e| Error::new(format!("Cannot encode cbor: {}", e)))?;
+        Ok(output)
+    }
+}
+
+fn tagged(tag: u64, value: CborValue) -> CborValue {
+    CborValue::Tag(tag, Box::new(value))
+}
+
+fn bigint_to_cbor(value: &BigInt) -> CborValue {
+    match i64::try_from(value) {
+        Ok(i) => CborValue::Integer(i.into()),
+        Err(_) => match value.sign() {
+            Sign::Minus => tagged(TAG_NEGATIVE_BIGNUM, CborValue::Bytes((-value - BigInt::from(1)).to_bytes_be().1)),
+            _ => tagged(TAG_POSITIVE_BIGNUM, CborValue::Bytes(value.to_bytes_be().1)),
+        },
+    }
+}
+
+fn optional_string(value: &Option<String>) -> CborValue {
+    value.as_ref().map_or(CborValue::Null, |s| CborValue::Text(s.clone()))
+}
+
+fn array(values: &[Value]) -> Result<CborValue, Error> {
+    let mut vec = vec![];
+    for v in values {
+        vec.push(v.try_into()?);
+    }
+    Ok(CborValue::Array(vec))
+}
+
+impl TryFrom<&Value> for CborValue {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<CborValue, Self::Error> {
+        Ok(match value {
+            Value::Null => CborValue::Null,
+            Value::Bool(b) => CborValue::Bool(*b),
+            Value::Int(i) => CborValue::Integer((*i).into()),
+            Value::Int64(i) => CborValue::Integer((*i).into()),
+            Value::Float32(f) => CborValue::Float(*f as f64),
+            Value::Float(f) => CborValue::Float(*f),
+            Value::Decimal(d) => {
+                let (mantissa, scale) = d.normalized().as_bigint_and_exponent();
+                tagged(TAG_DECIMAL, CborValue::Array(vec![CborValue::Integer(Integer::from(-scale)), bigint_to_cbor(&mantissa)]))
+            }
+            Value::ObjectId(o) => tagged(TAG_OBJECT_ID, CborValue::Bytes(o.bytes().to_vec())),
+            Value::String(s) => CborValue::Text(s.clone()),
+            Value::Date(d) => tagged(TAG_DATE, CborValue::Text(d.format("%Y-%m-%d").to_string())),
+            Value::DateTime(d) => tagged(TAG_DATETIME, CborValue::Text(d.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+            Value::Array(a) => array(a)?,
+            Value::Dictionary(d) => {
+                let mut vec = vec![];
+                for (k, v) in d {
+                    vec.push((CborValue::Text(k.clone()), v.try_into()?));
+                }
+                CborValue::Map(vec)
+            }
+            Value::Range(r) => tagged(TAG_RANGE, CborValue::Array(vec![
+                CborValue::Bool(r.closed),
+                r.start.as_ref().try_into()?,
+                r.end.as_ref().try_into()?,
+            ])),
+            Value::Tuple(t) => tagged(TAG_TUPLE, array(t)?),
+            Value::EnumVariant(e) => tagged(TAG_ENUM_VARIANT, CborValue::Array(vec![
+                CborValue::Text(e.value.clone()),
+                match &e.args {
+                    Some(args) => {
+                        let mut vec = vec![];
+                        for (k, v) in args {
+                            vec.push((CborValue::Text(k.clone()), v.try_into()?));
+                        }
+                        CborValue::Map(vec)
+                    }
+                    None => CborValue::Null,
+                },
+            ])),
+            Value::OptionVariant(o) => tagged(TAG_OPTION_VARIANT, CborValue::Array(vec![
+                CborValue::Integer(o.value.into()),
+                CborValue::Text(o.display.clone()),
+            ])),
+            Value::Regex(r) => tagged(TAG_REGEX, CborValue::Array(vec![
+                CborValue::Text(r.pattern().to_owned()),
+                CborValue::Text(r.flags().to_owned()),
+            ])),
+            Value::File(f) => tagged(TAG_FILE, CborValue::Map(vec![
+                (CborValue::Text("filepath".to_owned()), CborValue::Text(f.filepath.clone())),
+                (CborValue::Text("contentType".to_owned()), optional_string(&f.content_type)),
+                (CborValue::Text("filename".to_owned()), CborValue::Text(f.filename.clone())),
+                (CborValue::Text("filenameExt".to_owned()), optional_string(&f.filename_ext)),
+                (CborValue::Text("content".to_owned()), f.content.as_ref().map_or(CborValue::Null, |c| CborValue::Bytes(c.clone()))),
+            ])),
+            Value::Bytes(b) => CborValue::Bytes(b.clone()),
+        })
+    }
+}
+
+impl TryFrom<Value> for CborValue {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<CborValue, Self::Error> {
+        (&value).try_into()
+    }
+}
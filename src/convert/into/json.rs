@@ -1,5 +1,7 @@
 use serde_json::{Value as JsonValue, Number as JsonNumber, Map as JsonMap, json};
 use chrono::SecondsFormat;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use teo_result::Error;
 use crate::value::Value;
 
@@ -37,6 +39,12 @@ impl TryFrom<&Value> for JsonValue {
             Value::EnumVariant(val) => {
                 JsonValue::String(val.value.clone())
             }
+            Value::Bytes(val) => json!({"$bytes": BASE64.encode(val)}),
+            Value::Regex(val) => if val.flags().is_empty() {
+                json!({"$regex": val.pattern()})
+            } else {
+                json!({"$regex": val.pattern(), "$options": val.flags()})
+            },
             _ => {
                 Err(Error::new(format!("Cannot convert {} into json", value.type_hint())))?
             }
@@ -19,4 +19,14 @@ pub mod enum_variant;
 pub mod option_variant;
 pub mod tuple;
 pub mod value;
-pub mod file;
\ No newline at end of file
+pub mod file;
+pub mod bytes;
+pub mod bson;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "cbor")]
+pub mod cbor;
\ No newline at end of file
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use rmpv::{Value as MsgpackValue};
+use teo_result::Error;
+use crate::types::file::File;
+use crate::value::Value;
+
+// MessagePack extension types of Teon values. `DateTime` uses the standard timestamp type -1.
+pub(crate) const EXT_TIMESTAMP: i8 = -1;
+pub(crate) const EXT_DECIMAL: i8 = 1;
+pub(crate) const EXT_OBJECT_ID: i8 = 2;
+pub(crate) const EXT_DATE: i8 = 3;
+pub(crate) const EXT_RANGE: i8 = 4;
+pub(crate) const EXT_TUPLE: i8 = 5;
+pub(crate) const EXT_ENUM_VARIANT: i8 = 6;
+pub(crate) const EXT_OPTION_VARIANT: i8 = 7;
+pub(crate) const EXT_REGEX: i8 = 8;
+pub(crate) const EXT_FILE: i8 = 9;
+
+impl Value {
+
+    /// Encode the value as MessagePack. Teon types without a native MessagePack type are
+    /// written as extension types:
+    ///
+    /// | Type            | Extension type | Data                                              |
+    /// |-----------------|----------------|---------------------------------------------------|
+    /// | `DateTime`      | -1             | timestamp 96                                      |
+    /// | `Decimal`       | 1              | normalized decimal string                         |
+    /// | `ObjectId`      | 2              | 12 bytes                                          |
+    /// | `Date`          | 3              | `YYYY-MM-DD` string                               |
+    /// | `Range`         | 4              | encoded `[closed, start, end]`                    |
+    /// | `Tuple`         | 5              | encoded array                                     |
+    /// | `EnumVariant`   | 6              | encoded `[value, args]`, `args` a map or nil      |
+    /// | `OptionVariant` | 7              | encoded `[value, display]`                        |
+    /// | `Regex`         | 8              | encoded `[pattern, flags]`                        |
+    /// | `File`          | 9              | encoded map of the file fields                    |
+    pub fn to_msgpack(&self) -> teo_result::Result<Vec<u8>> {
+        let mut output = vec![];
+        write(&MsgpackValue::try_from(self)?, &mut output);
+        Ok(output)
+    }
+}
+
+fn write(value: &MsgpackValue, output: &mut Vec<u8>) {
+    rmpv::encode::write_value(output, value).unwrap();
+}
+
+fn ext(code: i8, value: &MsgpackValue) -> MsgpackValue {
+    let mut data = vec![];
+    write(value, &mut data);
+    MsgpackValue::Ext(code, data)
+}
+
+fn optional_string(value: &Option<String>) -> MsgpackValue {
+    value.as_ref().map_or(MsgpackValue::Nil, |s| MsgpackValue::from(s.as_str()))
+}
+
+fn file_map(file: &File) -> MsgpackValue {
+    MsgpackValue::Map(vec![
+        (MsgpackValue::from("filepath"), MsgpackValue::from(file.filepath.as_str())),
+        (MsgpackValue::from("contentType"), optional_string(&file.content_type)),
+        (MsgpackValue::from("filename"), MsgpackValue::from(file.filename.as_str())),
+        (MsgpackValue::from("filenameExt"), optional_string(&file.filename_ext)),
+        (MsgpackValue::from("content"), file.content.as_ref().map_or(MsgpackValue::Nil, |c| MsgpackValue::Binary(c.clone()))),
+    ])
+}
+
+fn map(entries: &BTreeMap<String, Value>) -> Result<MsgpackValue, Error> {
+    let mut vec = vec![];
+    for (k, v) in entries {
+        vec.push((MsgpackValue::from(k.as_str()), v.try_into()?));
+    }
+    Ok(MsgpackValue::Map(vec))
+}
+
+impl TryFrom<&Value> for MsgpackValue {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<MsgpackValue, Self::Error> {
+        Ok(match value {
+            Value::Null => MsgpackValue::Nil,
+            Value::Bool(b) => MsgpackValue::Boolean(*b),
+            Value::Int(i) => MsgpackValue::from(*i),
+            Value::Int64(i) => MsgpackValue::from(*i),
+            Value::Float32(f) => MsgpackValue::F32(*f),
+            Value::Float(f) => MsgpackValue::F64(*f),
+            Value::Decimal(d) => MsgpackValue::Ext(EXT_DECIMAL, d.normalized().to_string().into_bytes()),
+            Value::ObjectId(o) => MsgpackValue::Ext(EXT_OBJECT_ID, o.bytes().to_vec()),
+            Value::String(s) => MsgpackValue::from(s.as_str()),
+            Value::Date(d) => MsgpackValue::Ext(EXT_DATE, d.format("%Y-%m-%d").to_string().into_bytes()),
+            Value::DateTime(d) => {
+                let mut data = d.timestamp_subsec_nanos().to_be_bytes().to_vec();
+                data.extend_from_slice(&d.timestamp().to_be_bytes());
+                MsgpackValue::Ext(EXT_TIMESTAMP, data)
+            }
+            Value::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                MsgpackValue::Array(vec)
+            }
+            Value::Dictionary(d) => {
+                let mut vec = vec![];
+                for (k, v) in d {
+                    vec.push((MsgpackValue::from(k.as_str()), v.try_into()?));
+                }
+                MsgpackValue::Map(vec)
+            }
+            Value::Range(r) => ext(EXT_RANGE, &MsgpackValue::Array(vec![
+                MsgpackValue::Boolean(r.closed),
+                r.start.as_ref().try_into()?,
+                r.end.as_ref().try_into()?,
+            ])),
+            Value::Tuple(t) => {
+                let mut vec = vec![];
+                for v in t {
+                    vec.push(v.try_into()?);
+                }
+                ext(EXT_TUPLE, &MsgpackValue::Array(vec))
+            }
+            Value::EnumVariant(e) => ext(EXT_ENUM_VARIANT, &MsgpackValue::Array(vec![
+                MsgpackValue::from(e.value.as_str()),
+                match &e.args {
+                    Some(args) => map(args)?,
+                    None => MsgpackValue::Nil,
+                },
+            ])),
+            Value::OptionVariant(o) => ext(EXT_OPTION_VARIANT, &MsgpackValue::Array(vec![
+                MsgpackValue::from(o.value),
+                MsgpackValue::from(o.display.as_str()),
+            ])),
+            Value::Regex(r) => ext(EXT_REGEX, &MsgpackValue::Array(vec![
+                MsgpackValue::from(r.pattern()),
+                MsgpackValue::from(r.flags()),
+            ])),
+            Value::File(f) => ext(EXT_FILE, &file_map(f)),
+            Value::Bytes(b) => MsgpackValue::Binary(b.clone()),
+        })
+    }
+}
+
+impl TryFrom<Value> for MsgpackValue {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<MsgpackValue, Self::Error> {
+        (&value).try_into()
+    }
+}
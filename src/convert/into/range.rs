@@ -19,7 +19,10 @@ impl TryInto<Range> for &Value {
     type Error = Error;
 
     fn try_into(self) -> Result<Range, Self::Error> {
-        self.clone().try_into()
+        match self {
+            Value::Range(s) => Ok(s.clone()),
+            _ => Err(Error::new(format!("Cannot convert {} into Range", self.type_hint()))),
+        }
     }
 }
 
@@ -53,7 +56,11 @@ impl TryInto<Option<Range>> for &Value {
     type Error = Error;
 
     fn try_into(self) -> Result<Option<Range>, Self::Error> {
-        self.clone().try_into()
+        match self {
+            Value::Null => Ok(None),
+            Value::Range(s) => Ok(Some(s.clone())),
+            _ => Err(Error::new(format!("Cannot convert {} into Option<Range>", self.type_hint()))),
+        }
     }
 }
 
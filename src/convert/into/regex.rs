@@ -1,5 +1,6 @@
 use regex::Regex;
 use teo_result::Error;
+use crate::types::regex::TeonRegex;
 use crate::value::Value;
 
 impl TryInto<Regex> for Value {
@@ -8,7 +9,7 @@ impl TryInto<Regex> for Value {
 
     fn try_into(self) -> Result<Regex, Self::Error> {
         match self {
-            Value::Regex(s) => Ok(s),
+            Value::Regex(s) => Ok(s.into_regex()),
             _ => Err(Error::new(format!("Cannot convert {} into Regex", self.type_hint()))),
         }
     }
@@ -19,7 +20,10 @@ impl TryInto<Regex> for &Value {
     type Error = Error;
 
     fn try_into(self) -> Result<Regex, Self::Error> {
-        self.clone().try_into()
+        match self {
+            Value::Regex(s) => Ok(s.as_regex().clone()),
+            _ => Err(Error::new(format!("Cannot convert {} into Regex", self.type_hint()))),
+        }
     }
 }
 
@@ -29,7 +33,7 @@ impl<'a> TryInto<&'a Regex> for &'a Value {
 
     fn try_into(self) -> Result<&'a Regex, Self::Error> {
         match self {
-            Value::Regex(s) => Ok(s),
+            Value::Regex(s) => Ok(s.as_regex()),
             _ => Err(Error::new(format!("Cannot convert {} into &Regex", self.type_hint()))),
         }
     }
@@ -42,7 +46,7 @@ impl TryInto<Option<Regex>> for Value {
     fn try_into(self) -> Result<Option<Regex>, Self::Error> {
         match self {
             Value::Null => Ok(None),
-            Value::Regex(s) => Ok(Some(s)),
+            Value::Regex(s) => Ok(Some(s.into_regex())),
             _ => Err(Error::new(format!("Cannot convert {} into Option<Regex>", self.type_hint()))),
         }
     }
@@ -53,7 +57,11 @@ impl TryInto<Option<Regex>> for &Value {
     type Error = Error;
 
     fn try_into(self) -> Result<Option<Regex>, Self::Error> {
-        self.clone().try_into()
+        match self {
+            Value::Null => Ok(None),
+            Value::Regex(s) => Ok(Some(s.as_regex().clone())),
+            _ => Err(Error::new(format!("Cannot convert {} into Option<Regex>", self.type_hint()))),
+        }
     }
 }
 
@@ -64,8 +72,44 @@ impl<'a> TryInto<Option<&'a Regex>> for &'a Value {
     fn try_into(self) -> Result<Option<&'a Regex>, Self::Error> {
         match self {
             Value::Null => Ok(None),
-            Value::Regex(s) => Ok(Some(s)),
+            Value::Regex(s) => Ok(Some(s.as_regex())),
             _ => Err(Error::new(format!("Cannot convert {} into Option<&Regex>", self.type_hint()))),
         }
     }
-}
\ No newline at end of file
+}
+
+impl TryInto<TeonRegex> for Value {
+
+    type Error = Error;
+
+    fn try_into(self) -> Result<TeonRegex, Self::Error> {
+        match self {
+            Value::Regex(s) => Ok(s),
+            _ => Err(Error::new(format!("Cannot convert {} into Regex", self.type_hint()))),
+        }
+    }
+}
+
+impl TryInto<TeonRegex> for &Value {
+
+    type Error = Error;
+
+    fn try_into(self) -> Result<TeonRegex, Self::Error> {
+        match self {
+            Value::Regex(s) => Ok(s.clone()),
+            _ => Err(Error::new(format!("Cannot convert {} into Regex", self.type_hint()))),
+        }
+    }
+}
+
+impl<'a> TryInto<&'a TeonRegex> for &'a Value {
+
+    type Error = Error;
+
+    fn try_into(self) -> Result<&'a TeonRegex, Self::Error> {
+        match self {
+            Value::Regex(s) => Ok(s),
+            _ => Err(Error::new(format!("Cannot convert {} into &Regex", self.type_hint()))),
+        }
+    }
+}
@@ -43,7 +43,11 @@ impl<'a> TryFrom<&'a Value> for Option<String> {
     type Error = Error;
 
     fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
-        value.clone().try_into()
+        match value {
+            Value::Null => Ok(None),
+            Value::String(s) => Ok(Some(s.to_owned())),
+            _ => Err(Error::new(format!("Cannot convert {} into Option<String>", value.type_hint()))),
+        }
     }
 }
 
@@ -0,0 +1,74 @@
+use std::str::FromStr;
+use chrono::SecondsFormat;
+use toml::{Table, Value as TomlValue};
+use toml::value::Datetime;
+use teo_result::Error;
+use crate::value::Value;
+
+impl Value {
+
+    /// Write a dictionary as a TOML document. Values TOML can't represent, like `null`,
+    /// decimals and tuples, are rejected with the path where they occur.
+    pub fn to_toml_string(&self) -> teo_result::Result<String> {
+        if !self.is_dictionary() {
+            Err(Error::new(format!("Cannot convert {} into toml document, expect Dictionary", self.type_hint())))?
+        }
+        let toml = to_toml(self, "")?;
+        toml::to_string(&toml).map_err(|e| Error::new(format!("Cannot write toml: {}", e)))
+    }
+}
+
+fn to_toml(value: &Value, path: &str) -> Result<TomlValue, Error> {
+    let unsupported = || if path.is_empty() {
+        Error::new(format!("Cannot convert {} into toml", value.type_hint()))
+    } else {
+        Error::new(format!("Cannot convert {} at `{}` into toml", value.type_hint(), path))
+    };
+    Ok(match value {
+        Value::Bool(b) => TomlValue::Boolean(*b),
+        Value::Int(i) => TomlValue::Integer(*i as i64),
+        Value::Int64(i) => TomlValue::Integer(*i),
+        Value::Float32(f) => TomlValue::Float(*f as f64),
+        Value::Float(f) => TomlValue::Float(*f),
+        Value::ObjectId(o) => TomlValue::String(o.to_hex()),
+        Value::String(s) => TomlValue::String(s.clone()),
+        // TOML only has four digit years
+        Value::Date(d) => TomlValue::Datetime(Datetime::from_str(&d.format("%Y-%m-%d").to_string()).map_err(|_| unsupported())?),
+        Value::DateTime(d) => TomlValue::Datetime(Datetime::from_str(&d.to_rfc3339_opts(SecondsFormat::Millis, true)).map_err(|_| unsupported())?),
+        Value::Array(a) => {
+            let mut vec = vec![];
+            for (i, v) in a.iter().enumerate() {
+                vec.push(to_toml(v, &format!("{path}[{i}]"))?);
+            }
+            TomlValue::Array(vec)
+        }
+        Value::Dictionary(d) => {
+            let mut table = Table::new();
+            for (k, v) in d {
+                let path = if path.is_empty() { k.clone() } else { format!("{path}.{k}") };
+                table.insert(k.clone(), to_toml(v, &path)?);
+            }
+            TomlValue::Table(table)
+        }
+        Value::EnumVariant(e) => TomlValue::String(e.value.clone()),
+        _ => Err(unsupported())?,
+    })
+}
+
+impl TryFrom<&Value> for TomlValue {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<TomlValue, Self::Error> {
+        to_toml(value, "")
+    }
+}
+
+impl TryFrom<Value> for TomlValue {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<TomlValue, Self::Error> {
+        (&value).try_into()
+    }
+}
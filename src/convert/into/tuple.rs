@@ -5,15 +5,13 @@ impl<T0, T1> TryFrom<Value> for (T0, T1) where T0: TryFrom<Value, Error = Error>
 
     type Error = Error;
 
-    fn try_from(ref value: Value) -> Result<Self, Self::Error> {
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Tuple(values) => {
-                if values.len() != 2 {
-                    Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint())))?
-                }
+            Value::Tuple(values) if values.len() == 2 => {
+                let mut values = values.into_iter();
                 Ok((
-                    values.get(0).unwrap().clone().try_into()?,
-                    values.get(1).unwrap().clone().try_into()?,
+                    values.next().unwrap().try_into()?,
+                    values.next().unwrap().try_into()?,
                 ))
             }
             _ => Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint()))),
@@ -25,13 +23,16 @@ impl<T0, T1, T2> TryFrom<Value> for (T0, T1, T2) where T0: TryFrom<Value, Error
 
     type Error = Error;
 
-    fn try_from(ref value: Value) -> Result<Self, Self::Error> {
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Tuple(values) => Ok((
-                values.get(0).map(Clone::clone).ok_or(Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint())))?).unwrap().try_into()?,
-                values.get(1).map(Clone::clone).ok_or(Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint())))?).unwrap().try_into()?,
-                values.get(2).map(Clone::clone).ok_or(Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint())))?).unwrap().try_into()?,
-            )),
+            Value::Tuple(values) if values.len() == 3 => {
+                let mut values = values.into_iter();
+                Ok((
+                    values.next().unwrap().try_into()?,
+                    values.next().unwrap().try_into()?,
+                    values.next().unwrap().try_into()?,
+                ))
+            }
             _ => Err(Error::new(format!("Cannot convert {} into Tuple", value.type_hint()))),
         }
     }
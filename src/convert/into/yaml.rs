@@ -0,0 +1,76 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::SecondsFormat;
+use serde_yaml::{Mapping, Value as YamlValue};
+use serde_yaml::value::{Tag, TaggedValue};
+use teo_result::Error;
+use crate::value::Value;
+
+impl Value {
+
+    /// Write the value as a YAML document, using tags for variants YAML has no type for.
+    /// Ranges, option variants and files can't be converted.
+    pub fn to_yaml_string(&self) -> teo_result::Result<String> {
+        let yaml = YamlValue::try_from(self)?;
+        serde_yaml::to_string(&yaml).map_err(|e| Error::new(format!("Cannot write yaml: {}", e)))
+    }
+}
+
+fn tagged(tag: &str, value: YamlValue) -> YamlValue {
+    YamlValue::Tagged(Box::new(TaggedValue { tag: Tag::new(tag), value }))
+}
+
+impl TryFrom<&Value> for YamlValue {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<YamlValue, Self::Error> {
+        Ok(match value {
+            Value::Null => YamlValue::Null,
+            Value::Bool(b) => YamlValue::Bool(*b),
+            Value::Int(i) => YamlValue::Number((*i).into()),
+            Value::Int64(i) => YamlValue::Number((*i).into()),
+            Value::Float32(f) => tagged("float32", YamlValue::Number((*f as f64).into())),
+            Value::Float(f) => YamlValue::Number((*f).into()),
+            Value::Decimal(d) => tagged("decimal", YamlValue::String(d.normalized().to_string())),
+            Value::ObjectId(o) => tagged("objectId", YamlValue::String(o.to_hex())),
+            Value::String(s) => YamlValue::String(s.clone()),
+            Value::Date(d) => tagged("date", YamlValue::String(d.format("%Y-%m-%d").to_string())),
+            Value::DateTime(d) => tagged("datetime", YamlValue::String(d.to_rfc3339_opts(SecondsFormat::Millis, true))),
+            Value::Array(a) => {
+                let mut vec = vec![];
+                for v in a {
+                    vec.push(v.try_into()?);
+                }
+                YamlValue::Sequence(vec)
+            }
+            Value::Dictionary(d) => {
+                let mut mapping = Mapping::new();
+                for (k, v) in d {
+                    mapping.insert(YamlValue::String(k.clone()), v.try_into()?);
+                }
+                YamlValue::Mapping(mapping)
+            }
+            Value::Tuple(t) => {
+                let mut vec = vec![];
+                for v in t {
+                    vec.push(v.try_into()?);
+                }
+                tagged("tuple", YamlValue::Sequence(vec))
+            }
+            Value::EnumVariant(e) => YamlValue::String(e.value.clone()),
+            Value::Regex(r) => tagged("regex", YamlValue::String(r.to_string())),
+            Value::Bytes(b) => tagged("bytes", YamlValue::String(BASE64.encode(b))),
+            _ => Err(Error::new(format!("Cannot convert {} into yaml", value.type_hint())))?,
+        })
+    }
+}
+
+impl TryFrom<Value> for YamlValue {
+
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<YamlValue, Self::Error> {
+        (&value).try_into()
+    }
+}
@@ -0,0 +1,350 @@
+//! CSV import and export for arrays of dictionaries.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::csv::{from_csv, to_csv, CsvReadOptions, CsvWriteOptions};
+//! #
+//! let records = vec![teon!({"id": 1, "profile": {"name": "Alice"}}), teon!({"id": 2, "active": true})];
+//! let text = to_csv(&records, &CsvWriteOptions::default()).unwrap();
+//! assert_eq!(text, "id,profile.name,active\n1,Alice,\n2,,true\n");
+//! let read = from_csv(&text, &CsvReadOptions::default()).unwrap();
+//! assert_eq!(read[0], teon!({"id": 1, "profile": {"name": "Alice"}, "active": null}));
+//! ```
+
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use indexmap::IndexMap;
+use serde_json::{Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::value::Value;
+
+/// When the writer quotes fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quoting {
+    /// Quote fields containing the delimiter, quotes or line breaks.
+    #[default]
+    Necessary,
+    /// Quote every field.
+    Always,
+    /// Quote every field which is not a number or a boolean.
+    NonNumeric,
+}
+
+/// Options for [`to_csv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvWriteOptions {
+    pub delimiter: u8,
+    pub quoting: Quoting,
+    /// Whether to write the header row.
+    pub header: bool,
+    /// Whether nested dictionaries become columns named by dotted paths. Otherwise they are
+    /// written as JSON like arrays are.
+    pub flatten: bool,
+}
+
+impl Default for CsvWriteOptions {
+
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quoting: Quoting::Necessary,
+            header: true,
+            flatten: true,
+        }
+    }
+}
+
+/// The type of a CSV column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvType {
+    Bool,
+    Int64,
+    Float,
+    Decimal,
+    Date,
+    DateTime,
+    String,
+}
+
+/// Options for [`from_csv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvReadOptions {
+    pub delimiter: u8,
+    /// Column types by header name. Other columns have their types inferred, or are read as
+    /// strings when `infer` is false.
+    pub types: IndexMap<String, CsvType>,
+    /// Whether to infer column types from their values. A column is read as the first of
+    /// `Bool`, `Int64`, `Float`, `Date` and `DateTime` that all its non-empty fields parse as,
+    /// and as `String` otherwise.
+    pub infer: bool,
+    /// Whether dotted header names become nested dictionaries.
+    pub unflatten: bool,
+}
+
+impl Default for CsvReadOptions {
+
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            types: IndexMap::new(),
+            infer: true,
+            unflatten: true,
+        }
+    }
+}
+
+/// Write dictionaries as CSV. The columns are the union of the keys of all records, in the
+/// order they first appear. Missing values and nulls are written as empty fields, and empty
+/// strings as quoted empty fields.
+///
+/// Records which [`from_csv`] couldn't read back are rejected, like a key `a.b` next to a nested
+/// `{"a": {"b": ..}}`, or `a` in one record and `a.b` in another when flattening.
+pub fn to_csv(records: &[Value], options: &CsvWriteOptions) -> Result<String> {
+    let mut rows = vec![];
+    let mut columns: IndexMap<String, ()> = IndexMap::new();
+    for record in records {
+        let record = record.as_dictionary().ok_or_else(|| Error::new(format!("cannot write {} as csv row, expect Dictionary", record.type_hint())))?;
+        let mut row = IndexMap::new();
+        flatten_into(record, "", options.flatten, &mut row)?;
+        for key in row.keys() {
+            columns.entry(key.clone()).or_default();
+        }
+        rows.push(row);
+    }
+    let columns: Vec<String> = columns.into_keys().collect();
+    if let Some((a, b)) = conflicting_columns(&columns, options.flatten) {
+        return Err(Error::new(format!("cannot write csv: conflicting columns `{}` and `{}`", a, b)));
+    }
+    let mut output = String::new();
+    if options.header {
+        let header: Vec<FormattedField> = columns.iter().map(|k| (k.clone(), false, false)).collect();
+        write_row(&header, options, &mut output);
+    }
+    for row in rows {
+        let mut fields = vec![];
+        for column in &columns {
+            fields.push(match row.get(column) {
+                Some(value) => format_field(value)?,
+                None => (String::new(), false, false),
+            });
+        }
+        write_row(&fields, options, &mut output);
+    }
+    Ok(output)
+}
+
+fn flatten_into<'a>(record: &'a IndexMap<String, Value>, prefix: &str, flatten: bool, row: &mut IndexMap<String, &'a Value>) -> Result<()> {
+    for (key, value) in record {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+        match value {
+            Value::Dictionary(nested) if flatten => flatten_into(nested, &path, flatten, row)?,
+            _ => if row.insert(path.clone(), value).is_some() {
+                return Err(Error::new(format!("cannot write csv: more than one column named `{}`", path)));
+            },
+        }
+    }
+    Ok(())
+}
+
+// The text of a field, whether it's numeric, and whether it's an empty string, which is always
+// quoted so that it's not read back as null.
+type FormattedField = (String, bool, bool);
+
+fn format_field(value: &Value) -> Result<FormattedField> {
+    let (text, numeric) = match value {
+        Value::Null => (String::new(), false),
+        Value::Bool(b) => (b.to_string(), true),
+        Value::Int(i) => (i.to_string(), true),
+        Value::Int64(i) => (i.to_string(), true),
+        Value::Float32(f) => (f.to_string(), true),
+        Value::Float(f) => (f.to_string(), true),
+        Value::Decimal(d) => (d.normalized().to_string(), true),
+        Value::ObjectId(o) => (o.to_hex(), false),
+        Value::String(s) if s.is_empty() => return Ok((String::new(), false, true)),
+        Value::String(s) => (s.clone(), false),
+        Value::Date(d) => (d.format("%Y-%m-%d").to_string(), false),
+        Value::DateTime(d) => (d.to_rfc3339_opts(SecondsFormat::Millis, true), false),
+        Value::EnumVariant(e) => (e.value.clone(), false),
+        _ => (serde_json::to_string(&JsonValue::try_from(value)?).unwrap(), false),
+    };
+    Ok((text, numeric, false))
+}
+
+fn write_row(fields: &[FormattedField], options: &CsvWriteOptions, output: &mut String) {
+    let delimiter = options.delimiter as char;
+    for (i, (field, numeric, empty_string)) in fields.iter().enumerate() {
+        if i != 0 {
+            output.push(delimiter);
+        }
+        let quote = *empty_string || match options.quoting {
+            Quoting::Always => true,
+            Quoting::NonNumeric => !numeric,
+            Quoting::Necessary => field.contains([delimiter, '"', '\n', '\r']),
+        };
+        if quote {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push('\n');
+}
+
+// A parsed field, and whether it was quoted.
+type Field = (String, bool);
+
+/// Read CSV with a header row into dictionaries. Empty unquoted fields are read as `null`, and
+/// quoted empty fields as empty strings.
+pub fn from_csv(text: &str, options: &CsvReadOptions) -> Result<Vec<Value>> {
+    let mut rows = parse_rows(text, options.delimiter as char)?;
+    if rows.is_empty() {
+        return Ok(vec![]);
+    }
+    let (header_line, header) = rows.remove(0);
+    let header: Vec<String> = header.into_iter().map(|(name, _)| name).collect();
+    check_header(&header, header_line, options.unflatten)?;
+    for (line, row) in &rows {
+        if row.len() != header.len() {
+            return Err(Error::new(format!("csv error at line {}: expect {} fields, found {}", line, header.len(), row.len())));
+        }
+    }
+    let types: Vec<CsvType> = header.iter().enumerate().map(|(i, name)| match options.types.get(name) {
+        Some(t) => *t,
+        None if options.infer => infer_type(rows.iter().map(|(_, row)| &row[i])),
+        None => CsvType::String,
+    }).collect();
+    let mut records = vec![];
+    for (line, row) in rows {
+        let mut record = Value::Dictionary(IndexMap::new());
+        for (i, (field, quoted)) in row.into_iter().enumerate() {
+            let value = if field.is_empty() && !quoted {
+                Value::Null
+            } else {
+                parse_field(&field, types[i]).ok_or_else(|| Error::new(format!("csv error at line {}: cannot read `{}` as {:?} in column `{}`", line, field, types[i], header[i])))?
+            };
+            insert(&mut record, &header[i], value, options.unflatten);
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn check_header(header: &[String], line: usize, unflatten: bool) -> Result<()> {
+    match conflicting_columns(header, unflatten) {
+        Some((a, b)) => Err(Error::new(format!("csv error at line {}: conflicting columns `{}` and `{}`", line, a, b))),
+        None => Ok(()),
+    }
+}
+
+// Columns can't repeat, and when unflattening, `a` can't be read along with `a.b`.
+fn conflicting_columns(columns: &[String], unflatten: bool) -> Option<(&str, &str)> {
+    let nests = |parent: &str, child: &str| unflatten && child.strip_prefix(parent).is_some_and(|rest| rest.starts_with('.'));
+    columns.iter().enumerate().find_map(|(i, name)| {
+        columns[..i].iter().find(|other| *other == name || nests(other, name) || nests(name, other)).map(|other| (other.as_str(), name.as_str()))
+    })
+}
+
+fn insert(record: &mut Value, name: &str, value: Value, unflatten: bool) {
+    let map = record.as_dictionary_mut().unwrap();
+    match name.split_once('.') {
+        Some((head, rest)) if unflatten => {
+            let nested = map.entry(head.to_owned()).or_insert_with(|| Value::Dictionary(IndexMap::new()));
+            if !nested.is_dictionary() {
+                *nested = Value::Dictionary(IndexMap::new());
+            }
+            insert(nested, rest, value, unflatten);
+        }
+        _ => {
+            map.insert(name.to_owned(), value);
+        }
+    }
+}
+
+fn infer_type<'a>(fields: impl Iterator<Item = &'a Field> + Clone) -> CsvType {
+    let candidates = [CsvType::Bool, CsvType::Int64, CsvType::Float, CsvType::Date, CsvType::DateTime];
+    let mut non_empty = fields.filter(|(field, _)| !field.is_empty()).peekable();
+    if non_empty.peek().is_none() {
+        return CsvType::String;
+    }
+    candidates.into_iter().find(|t| non_empty.clone().all(|(field, _)| parse_field(field, *t).is_some())).unwrap_or(CsvType::String)
+}
+
+fn parse_field(field: &str, t: CsvType) -> Option<Value> {
+    match t {
+        CsvType::Bool => match field.to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        CsvType::Int64 => field.parse().ok().map(Value::Int64),
+        CsvType::Float => field.parse().ok().filter(|f: &f64| f.is_finite()).map(Value::Float),
+        CsvType::Decimal => BigDecimal::from_str(field).ok().map(Value::Decimal),
+        CsvType::Date => NaiveDate::parse_from_str(field, "%Y-%m-%d").ok().map(Value::Date),
+        CsvType::DateTime => DateTime::parse_from_rfc3339(field).ok().map(|d| Value::DateTime(d.with_timezone(&Utc))),
+        CsvType::String => Some(Value::String(field.to_owned())),
+    }
+}
+
+// Splits CSV text into rows of fields, each row with the line it starts on. Blank lines are
+// skipped.
+fn parse_rows(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<Field>)>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(Error::new(format!("csv error at line {}: unterminated quoted field", start))),
+                    }
+                }
+                match chars.peek() {
+                    None | Some('\n' | '\r') => (),
+                    Some(c) if *c == delimiter => (),
+                    Some(_) => return Err(Error::new(format!("csv error at line {}: unexpected character after quoted field", line))),
+                }
+            }
+            c if c == delimiter => {
+                row.push((std::mem::take(&mut field), quoted));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' | '\r' => {
+                if !row.is_empty() || !field.is_empty() || quoted {
+                    row.push((std::mem::take(&mut field), quoted));
+                    quoted = false;
+                    rows.push((row_line, std::mem::take(&mut row)));
+                }
+                line += 1;
+                row_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || quoted || !row.is_empty() {
+        row.push((field, quoted));
+        rows.push((row_line, row));
+    }
+    Ok(rows)
+}
@@ -0,0 +1,53 @@
+use crate::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOperator {
+    /// `!`, logical not
+    Not,
+    /// `-`
+    Neg,
+    /// `~`, bitwise not
+    BitNeg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Literal(Value),
+    Array(Vec<Expression>),
+    Tuple(Vec<Expression>),
+    Dictionary(Vec<(String, Expression)>),
+    /// A top level name, resolved against the context value.
+    Variable(String),
+    /// `object.name`
+    Member(Box<Expression>, String),
+    /// `object[index]`
+    Subscript(Box<Expression>, Box<Expression>),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+    /// `condition ? then : otherwise`
+    Ternary(Box<Expression>, Box<Expression>, Box<Expression>),
+    /// `name(arguments...)`
+    Call(String, Vec<Expression>),
+}
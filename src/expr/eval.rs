@@ -0,0 +1,195 @@
+use bigdecimal::Zero;
+use indexmap::IndexMap;
+use teo_result::{Error, Result};
+use crate::value::{operands_error_message, Value};
+use super::ast::{BinaryOperator, Expression, UnaryOperator};
+use super::functions::FunctionRegistry;
+use super::parser::parse;
+
+/// Parse and evaluate an expression against a context value.
+pub fn evaluate(source: &str, context: &Value, functions: &FunctionRegistry) -> Result<Value> {
+    parse(source)?.evaluate(context, functions)
+}
+
+impl Expression {
+
+    /// Evaluate the expression. Variables are looked up as keys of `context`, which is usually
+    /// a dictionary. Referring to a missing variable is an error, while accessing a missing key
+    /// or index of a value gives `null`.
+    pub fn evaluate(&self, context: &Value, functions: &FunctionRegistry) -> Result<Value> {
+        Ok(match self {
+            Expression::Literal(value) => value.clone(),
+            Expression::Array(items) => Value::Array(evaluate_all(items, context, functions)?),
+            Expression::Tuple(items) => Value::Tuple(evaluate_all(items, context, functions)?),
+            Expression::Dictionary(entries) => {
+                let mut map = IndexMap::new();
+                for (key, expression) in entries {
+                    map.insert(key.clone(), expression.evaluate(context, functions)?);
+                }
+                Value::Dictionary(map)
+            }
+            Expression::Variable(name) => match context.get(name.as_str()) {
+                Some(value) => value.clone(),
+                None => Err(Error::new(format!("undefined variable `{name}`")))?,
+            },
+            Expression::Member(object, name) => {
+                let object = object.evaluate(context, functions)?;
+                object.get(name.as_str()).cloned().unwrap_or(Value::Null)
+            }
+            Expression::Subscript(object, index) => {
+                let object = object.evaluate(context, functions)?;
+                let index = index.evaluate(context, functions)?;
+                match (&object, &index) {
+                    (Value::Array(_) | Value::Tuple(_), _) if index.is_any_int() => {
+                        let i = index.to_int64().unwrap();
+                        if i < 0 { Value::Null } else { object.get(i as usize).cloned().unwrap_or(Value::Null) }
+                    }
+                    (Value::Dictionary(_), Value::String(key)) => object.get(key.as_str()).cloned().unwrap_or(Value::Null),
+                    (Value::Null, _) => Value::Null,
+                    _ => Err(operands_error_message(&object, &index, "subscript"))?,
+                }
+            }
+            Expression::Unary(operator, operand) => {
+                let operand = operand.evaluate(context, functions)?;
+                match operator {
+                    UnaryOperator::Not => operand.normal_not(),
+                    UnaryOperator::Neg => match operand {
+                        Value::Int(i) => i.checked_neg().map_or(Value::Int64(-(i as i64)), Value::Int),
+                        Value::Int64(i) => Value::Int64(i.checked_neg().ok_or_else(|| overflow("neg"))?),
+                        _ => (-&operand)?,
+                    },
+                    UnaryOperator::BitNeg => (!&operand)?,
+                }
+            }
+            Expression::Binary(BinaryOperator::And, lhs, rhs) => {
+                let lhs = lhs.evaluate(context, functions)?;
+                if lhs.normal_not().is_true() { lhs } else { rhs.evaluate(context, functions)? }
+            }
+            Expression::Binary(BinaryOperator::Or, lhs, rhs) => {
+                let lhs = lhs.evaluate(context, functions)?;
+                if lhs.normal_not().is_false() { lhs } else { rhs.evaluate(context, functions)? }
+            }
+            Expression::Binary(operator, lhs, rhs) => {
+                let lhs = lhs.evaluate(context, functions)?;
+                let rhs = rhs.evaluate(context, functions)?;
+                binary(*operator, &lhs, &rhs)?
+            }
+            Expression::Ternary(condition, then, otherwise) => {
+                if condition.evaluate(context, functions)?.normal_not().is_false() {
+                    then.evaluate(context, functions)?
+                } else {
+                    otherwise.evaluate(context, functions)?
+                }
+            }
+            Expression::Call(name, arguments) => {
+                let arguments = evaluate_all(arguments, context, functions)?;
+                functions.call(name, &arguments)?
+            }
+        })
+    }
+}
+
+fn evaluate_all(expressions: &[Expression], context: &Value, functions: &FunctionRegistry) -> Result<Vec<Value>> {
+    expressions.iter().map(|e| e.evaluate(context, functions)).collect()
+}
+
+fn overflow(name: &str) -> Error {
+    Error::new(format!("integer overflow in {name}"))
+}
+
+// Integer arithmetic which can't panic. `Int` operands give an `Int` result when both operands
+// are `Int`, and an `Int64` one otherwise.
+fn integer(operator: BinaryOperator, lhs: &Value, rhs: &Value) -> Option<Result<Value>> {
+    let (Some(a), Some(b)) = (lhs.to_int64(), rhs.to_int64()) else { return None };
+    let name = match operator {
+        BinaryOperator::Add => "add",
+        BinaryOperator::Sub => "sub",
+        BinaryOperator::Mul => "mul",
+        BinaryOperator::Div => "div",
+        BinaryOperator::Rem => "rem",
+        BinaryOperator::BitAnd => "bitand",
+        BinaryOperator::BitOr => "bitor",
+        BinaryOperator::BitXor => "bitxor",
+        BinaryOperator::Shl => "shl",
+        BinaryOperator::Shr => "shr",
+        _ => return None,
+    };
+    let narrow = lhs.is_int() && rhs.is_int();
+    if matches!(operator, BinaryOperator::Shl | BinaryOperator::Shr) {
+        let bits = if lhs.is_int() { 32 } else { 64 };
+        if !(0..bits).contains(&b) {
+            return Some(Err(Error::new(format!("cannot {name} by {b}, the shift amount must be at least 0 and less than {bits}"))));
+        }
+        let b = b as u32;
+        return Some(Ok(match (lhs, operator) {
+            (Value::Int(a), BinaryOperator::Shl) => Value::Int(a << b),
+            (Value::Int(a), _) => Value::Int(a >> b),
+            (_, BinaryOperator::Shl) => Value::Int64(a << b),
+            _ => Value::Int64(a >> b),
+        }));
+    }
+    if matches!(operator, BinaryOperator::Div | BinaryOperator::Rem) && b == 0 {
+        return Some(Err(Error::new(format!("cannot {name} {lhs} by zero"))));
+    }
+    let result = match operator {
+        BinaryOperator::Add => a.checked_add(b),
+        BinaryOperator::Sub => a.checked_sub(b),
+        BinaryOperator::Mul => a.checked_mul(b),
+        BinaryOperator::Div => a.checked_div(b),
+        BinaryOperator::Rem => a.checked_rem(b),
+        BinaryOperator::BitAnd => Some(a & b),
+        BinaryOperator::BitOr => Some(a | b),
+        _ => Some(a ^ b),
+    };
+    Some(match result {
+        Some(result) => Ok(match i32::try_from(result) {
+            Ok(result) if narrow => Value::Int(result),
+            _ => Value::Int64(result),
+        }),
+        None => Err(overflow(name)),
+    })
+}
+
+fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Int(i) => *i == 0,
+        Value::Int64(i) => *i == 0,
+        Value::Decimal(d) => d.is_zero(),
+        _ => false,
+    }
+}
+
+fn binary(operator: BinaryOperator, lhs: &Value, rhs: &Value) -> Result<Value> {
+    if lhs.is_any_int() && rhs.is_any_int() {
+        if let Some(result) = integer(operator, lhs, rhs) {
+            return result;
+        }
+    }
+    if matches!(operator, BinaryOperator::Div | BinaryOperator::Rem) && (lhs.is_decimal() || rhs.is_decimal()) && is_zero(rhs) {
+        Err(Error::new(format!("cannot {} {lhs} by zero", if operator == BinaryOperator::Div { "div" } else { "rem" })))?
+    }
+    Ok(match operator {
+        BinaryOperator::Add => (lhs + rhs)?,
+        BinaryOperator::Sub => (lhs - rhs)?,
+        BinaryOperator::Mul => (lhs * rhs)?,
+        BinaryOperator::Div => (lhs / rhs)?,
+        BinaryOperator::Rem => (lhs % rhs)?,
+        BinaryOperator::BitAnd => (lhs & rhs)?,
+        BinaryOperator::BitOr => (lhs | rhs)?,
+        BinaryOperator::BitXor => (lhs ^ rhs)?,
+        BinaryOperator::Shl => (lhs << rhs)?,
+        BinaryOperator::Shr => (lhs >> rhs)?,
+        BinaryOperator::Eq => Value::Bool(lhs == rhs),
+        BinaryOperator::Neq => Value::Bool(lhs != rhs),
+        BinaryOperator::Lt | BinaryOperator::Lte | BinaryOperator::Gt | BinaryOperator::Gte => {
+            let ordering = lhs.partial_cmp(rhs).ok_or_else(|| operands_error_message(lhs, rhs, "compare"))?;
+            Value::Bool(match operator {
+                BinaryOperator::Lt => ordering.is_lt(),
+                BinaryOperator::Lte => ordering.is_le(),
+                BinaryOperator::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+        BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+    })
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use teo_result::{Error, Result};
+use crate::ops::aggregate::AggregateOptions;
+use crate::value::Value;
+
+type Function = Arc<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>;
+
+/// Named functions which expressions can call.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: BTreeMap<String, Function>,
+}
+
+impl FunctionRegistry {
+
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in functions: `len`, `upper`, `lower`, `trim`, `contains`,
+    /// `startsWith`, `endsWith`, `split`, `join`, `replace`, `sum`, `avg`, `min`, `max`,
+    /// `count`, `unique` and `flatten`.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register("len", |args| one(args, "len").and_then(|v| match v {
+            Value::Array(a) | Value::Tuple(a) => Ok(Value::Int64(a.len() as i64)),
+            Value::Dictionary(d) => Ok(Value::Int64(d.len() as i64)),
+            Value::Bytes(b) => Ok(Value::Int64(b.len() as i64)),
+            _ => v.str_ops().len(),
+        }));
+        registry.register("upper", |args| one(args, "upper")?.str_ops().upper());
+        registry.register("lower", |args| one(args, "lower")?.str_ops().lower());
+        registry.register("trim", |args| one(args, "trim")?.str_ops().trim());
+        registry.register("contains", |args| {
+            let (target, item) = two(args, "contains")?;
+            match target {
+                Value::String(_) => target.str_ops().contains(item),
+                _ => target.contains(item),
+            }
+        });
+        registry.register("startsWith", |args| two(args, "startsWith").and_then(|(s, p)| s.str_ops().starts_with(p)));
+        registry.register("endsWith", |args| two(args, "endsWith").and_then(|(s, p)| s.str_ops().ends_with(p)));
+        registry.register("split", |args| two(args, "split").and_then(|(s, p)| s.str_ops().split(p)));
+        registry.register("join", |args| two(args, "join").and_then(|(s, p)| s.str_ops().join(p)));
+        registry.register("replace", |args| match args {
+            [s, pattern, replacement] => s.str_ops().replace(pattern, replacement),
+            _ => Err(arity_error("replace", 3, args.len())),
+        });
+        registry.register("sum", |args| one(args, "sum")?.sum(&AggregateOptions::default()));
+        registry.register("avg", |args| one(args, "avg")?.avg(&AggregateOptions::default()));
+        registry.register("min", |args| one(args, "min")?.min(&AggregateOptions::default()));
+        registry.register("max", |args| one(args, "max")?.max(&AggregateOptions::default()));
+        registry.register("count", |args| one(args, "count")?.count(&AggregateOptions::default()));
+        registry.register("unique", |args| one(args, "unique")?.unique());
+        registry.register("flatten", |args| one(args, "flatten")?.flatten());
+        registry
+    }
+
+    /// Register a function, replacing any function with the same name.
+    pub fn register<F>(&mut self, name: impl Into<String>, function: F) where F: Fn(&[Value]) -> Result<Value> + Send + Sync + 'static {
+        self.functions.insert(name.into(), Arc::new(function));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    pub fn call(&self, name: &str, arguments: &[Value]) -> Result<Value> {
+        match self.functions.get(name) {
+            Some(function) => function(arguments),
+            None => Err(Error::new(format!("undefined function `{name}`"))),
+        }
+    }
+}
+
+impl Debug for FunctionRegistry {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.functions.keys()).finish()
+    }
+}
+
+fn arity_error(name: &str, expected: usize, found: usize) -> Error {
+    Error::new(format!("function `{name}` expects {expected} argument(s), found {found}"))
+}
+
+fn one<'a>(args: &'a [Value], name: &str) -> Result<&'a Value> {
+    match args {
+        [a] => Ok(a),
+        _ => Err(arity_error(name, 1, args.len())),
+    }
+}
+
+fn two<'a>(args: &'a [Value], name: &str) -> Result<(&'a Value, &'a Value)> {
+    match args {
+        [a, b] => Ok((a, b)),
+        _ => Err(arity_error(name, 2, args.len())),
+    }
+}
@@ -0,0 +1,26 @@
+//! A small expression language over Teon values.
+//!
+//! Expressions use Teon literal syntax (`null`, `true`, `12`, `1.5`, `"text"`, `.enumVariant`,
+//! `[1, 2]`, `{"key": 1}`, `(1, 2)`, `Decimal("1.5")`, `Date("2023-01-01")`,
+//! `DateTime("2023-01-01T00:00:00.000Z")`, `ObjectId("...")`), variable and path references
+//! into a context value (`user.age`, `items[0]`), the arithmetic, bitwise, comparison and
+//! logical operators of `Value`, ternaries (`a ? b : c`) and calls to named functions.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::expr::{evaluate, FunctionRegistry};
+//! #
+//! let context = teon!({"user": {"age": 20, "name": "teo"}});
+//! let result = evaluate("user.age >= 18 ? upper(user.name) : null", &context, &FunctionRegistry::with_builtins()).unwrap();
+//! assert_eq!(result, teon!("TEO"));
+//! ```
+
+pub mod ast;
+pub mod parser;
+pub mod eval;
+pub mod functions;
+
+pub use ast::{BinaryOperator, Expression, UnaryOperator};
+pub use eval::evaluate;
+pub use functions::FunctionRegistry;
+pub use parser::parse;
@@ -0,0 +1,437 @@
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use teo_result::{Error, Result};
+use crate::types::enum_variant::EnumVariant;
+use crate::value::Value;
+use super::ast::{BinaryOperator, Expression, UnaryOperator};
+
+/// The maximum nesting depth of a parsed expression. Deeper expressions are errors, so that
+/// parsing, evaluating and dropping an expression can't overflow the stack.
+pub const MAX_DEPTH: usize = 64;
+
+/// Parse an expression from its source text.
+pub fn parse(source: &str) -> Result<Expression> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, position: 0, last: 0, level: 0 };
+    let (expression, _) = parser.expression(0)?;
+    match parser.peek() {
+        Token::End => Ok(expression),
+        _ => Err(parser.unexpected()),
+    }
+}
+
+impl FromStr for Expression {
+
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(String),
+    Float(String),
+    String(String),
+    Identifier(String),
+    Punctuation(&'static str),
+    End,
+}
+
+const PUNCTUATIONS: [&str; 30] = [
+    "<<", ">>", "<=", ">=", "==", "!=", "&&", "||",
+    "+", "-", "*", "/", "%", "&", "|", "^", "~", "!", "<", ">",
+    "?", ":", ".", ",", "(", ")", "[", "]", "{", "}",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                i += 1;
+            }
+            let mut float = false;
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+                float = true;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                    i += 1;
+                }
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    float = true;
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            tokens.push((if float { Token::Float(text) } else { Token::Int(text) }, start));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            tokens.push((Token::Identifier(chars[start..i].iter().collect()), start));
+        } else if c == '"' {
+            i += 1;
+            let mut string = String::new();
+            loop {
+                match chars.get(i) {
+                    None => Err(Error::new(format!("expression error at {start}: unterminated string")))?,
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some('r') => string.push('\r'),
+                            Some('0') => string.push('\0'),
+                            Some('\\') => string.push('\\'),
+                            Some('"') => string.push('"'),
+                            Some('\'') => string.push('\''),
+                            Some('/') => string.push('/'),
+                            Some('u') if chars.get(i + 1) == Some(&'{') => {
+                                let close = chars[i..].iter().position(|c| *c == '}').map(|p| p + i);
+                                let code = close
+                                    .and_then(|close| u32::from_str_radix(&chars[i + 2..close].iter().collect::<String>(), 16).ok())
+                                    .and_then(char::from_u32);
+                                match (code, close) {
+                                    (Some(code), Some(close)) => {
+                                        string.push(code);
+                                        i = close;
+                                    }
+                                    _ => Err(Error::new(format!("expression error at {}: invalid unicode escape", i - 1)))?,
+                                }
+                            }
+                            _ => Err(Error::new(format!("expression error at {}: invalid escape", i - 1)))?,
+                        }
+                        i += 1;
+                    }
+                    Some(c) => {
+                        string.push(*c);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((Token::String(string), start));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let punctuation = PUNCTUATIONS.iter().find(|p| rest.starts_with(**p));
+            match punctuation {
+                Some(p) => {
+                    i += p.chars().count();
+                    tokens.push((Token::Punctuation(p), start));
+                }
+                None => Err(Error::new(format!("expression error at {start}: unexpected character `{c}`")))?,
+            }
+        }
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    last: usize,
+    // the number of nested `expression` calls
+    level: usize,
+}
+
+// An expression with the depth of its tree.
+type Parsed = (Expression, usize);
+
+fn binary_operator(token: &Token) -> Option<(BinaryOperator, u8)> {
+    let Token::Punctuation(p) = token else { return None };
+    Some(match *p {
+        "||" => (BinaryOperator::Or, 2),
+        "&&" => (BinaryOperator::And, 3),
+        "==" => (BinaryOperator::Eq, 4),
+        "!=" => (BinaryOperator::Neq, 4),
+        "<" => (BinaryOperator::Lt, 5),
+        "<=" => (BinaryOperator::Lte, 5),
+        ">" => (BinaryOperator::Gt, 5),
+        ">=" => (BinaryOperator::Gte, 5),
+        "|" => (BinaryOperator::BitOr, 6),
+        "^" => (BinaryOperator::BitXor, 7),
+        "&" => (BinaryOperator::BitAnd, 8),
+        "<<" => (BinaryOperator::Shl, 9),
+        ">>" => (BinaryOperator::Shr, 9),
+        "+" => (BinaryOperator::Add, 10),
+        "-" => (BinaryOperator::Sub, 10),
+        "*" => (BinaryOperator::Mul, 11),
+        "/" => (BinaryOperator::Div, 11),
+        "%" => (BinaryOperator::Rem, 11),
+        _ => return None,
+    })
+}
+
+const TERNARY_POWER: u8 = 1;
+const UNARY_POWER: u8 = 12;
+
+impl Parser {
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn next(&mut self) -> Token {
+        self.last = self.position;
+        let token = self.tokens[self.position].0.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// The error for a token which was just consumed by `next`.
+    fn unexpected_previous(&self) -> Error {
+        self.unexpected_at(self.last)
+    }
+
+    fn unexpected(&self) -> Error {
+        self.unexpected_at(self.position)
+    }
+
+    fn unexpected_at(&self, index: usize) -> Error {
+        let (token, position) = &self.tokens[index];
+        match token {
+            Token::End => Error::new(format!("expression error at {position}: unexpected end of input")),
+            _ => Error::new(format!("expression error at {position}: unexpected token")),
+        }
+    }
+
+    fn expect(&mut self, punctuation: &str) -> Result<()> {
+        match self.peek() {
+            Token::Punctuation(p) if *p == punctuation => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn eat(&mut self, punctuation: &str) -> bool {
+        match self.peek() {
+            Token::Punctuation(p) if *p == punctuation => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The depth of a node whose deepest child has `depth`.
+    fn parent_depth(&self, depth: usize) -> Result<usize> {
+        if depth >= MAX_DEPTH {
+            Err(Error::new(format!("expression error at {}: expression is nested deeper than {MAX_DEPTH} levels", self.tokens[self.position].1)))?
+        }
+        Ok(depth + 1)
+    }
+
+    fn expression(&mut self, min_power: u8) -> Result<Parsed> {
+        if self.level >= MAX_DEPTH {
+            return Err(Error::new(format!("expression error at {}: expression is nested deeper than {MAX_DEPTH} levels", self.tokens[self.position].1)));
+        }
+        self.level += 1;
+        let result = self.operators(min_power);
+        self.level -= 1;
+        result
+    }
+
+    fn operators(&mut self, min_power: u8) -> Result<Parsed> {
+        let (mut lhs, mut depth) = self.prefix()?;
+        loop {
+            if min_power <= TERNARY_POWER && self.eat("?") {
+                let (then, then_depth) = self.expression(0)?;
+                self.expect(":")?;
+                let (otherwise, otherwise_depth) = self.expression(TERNARY_POWER)?;
+                depth = self.parent_depth(depth.max(then_depth).max(otherwise_depth))?;
+                lhs = Expression::Ternary(Box::new(lhs), Box::new(then), Box::new(otherwise));
+                continue;
+            }
+            let Some((operator, power)) = binary_operator(self.peek()) else { break };
+            if power <= min_power {
+                break;
+            }
+            self.next();
+            let (rhs, rhs_depth) = self.expression(power)?;
+            depth = self.parent_depth(depth.max(rhs_depth))?;
+            lhs = Expression::Binary(operator, Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, depth))
+    }
+
+    fn prefix(&mut self) -> Result<Parsed> {
+        let operator = match self.peek() {
+            Token::Punctuation("!") => Some(UnaryOperator::Not),
+            Token::Punctuation("-") => Some(UnaryOperator::Neg),
+            Token::Punctuation("~") => Some(UnaryOperator::BitNeg),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            self.next();
+            let (operand, depth) = self.expression(UNARY_POWER)?;
+            return Ok((Expression::Unary(operator, Box::new(operand)), self.parent_depth(depth)?));
+        }
+        let primary = self.primary()?;
+        self.postfix(primary)
+    }
+
+    fn postfix(&mut self, (mut expression, mut depth): Parsed) -> Result<Parsed> {
+        loop {
+            if self.eat(".") {
+                match self.next() {
+                    Token::Identifier(name) => expression = Expression::Member(Box::new(expression), name),
+                    Token::Int(index) => expression = Expression::Subscript(Box::new(expression), Box::new(Expression::Literal(int_value(&index)?))),
+                    _ => return Err(self.unexpected_previous()),
+                }
+                depth = self.parent_depth(depth)?;
+            } else if self.eat("[") {
+                let (index, index_depth) = self.expression(0)?;
+                self.expect("]")?;
+                depth = self.parent_depth(depth.max(index_depth))?;
+                expression = Expression::Subscript(Box::new(expression), Box::new(index));
+            } else {
+                return Ok((expression, depth));
+            }
+        }
+    }
+
+    /// The items of a list and the depth of the deepest one.
+    fn list(&mut self, close: &str) -> Result<(Vec<Expression>, usize, bool)> {
+        let mut items = vec![];
+        let mut depth = 0;
+        let mut trailing_comma = false;
+        while !self.eat(close) {
+            let (item, item_depth) = self.expression(0)?;
+            items.push(item);
+            depth = depth.max(item_depth);
+            trailing_comma = self.eat(",");
+            if !trailing_comma {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok((items, depth, trailing_comma))
+    }
+
+    fn primary(&mut self) -> Result<Parsed> {
+        let position = self.tokens[self.position].1;
+        match self.next() {
+            Token::Punctuation("(") => {
+                let (mut items, depth, trailing_comma) = self.list(")")?;
+                if items.len() == 1 && !trailing_comma {
+                    Ok((items.remove(0), depth))
+                } else {
+                    Ok((Expression::Tuple(items), self.parent_depth(depth)?))
+                }
+            }
+            Token::Punctuation("[") => {
+                let (items, depth, _) = self.list("]")?;
+                Ok((Expression::Array(items), self.parent_depth(depth)?))
+            }
+            Token::Punctuation("{") => self.dictionary(),
+            Token::Identifier(name) => self.identifier(name, position),
+            token => Ok((Expression::Literal(self.literal(token, position)?), 1)),
+        }
+    }
+
+    // The rest of a dictionary after `{`.
+    fn dictionary(&mut self) -> Result<Parsed> {
+        let mut entries = vec![];
+        let mut depth = 0;
+        while !self.eat("}") {
+            let key = match self.next() {
+                Token::String(key) | Token::Identifier(key) => key,
+                _ => Err(self.unexpected_previous())?,
+            };
+            self.expect(":")?;
+            let (value, value_depth) = self.expression(0)?;
+            entries.push((key, value));
+            depth = depth.max(value_depth);
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok((Expression::Dictionary(entries), self.parent_depth(depth)?))
+    }
+
+    // A keyword, variable or call.
+    fn identifier(&mut self, name: String, position: usize) -> Result<Parsed> {
+        Ok(match name.as_str() {
+            "null" => (Expression::Literal(Value::Null), 1),
+            "true" => (Expression::Literal(Value::Bool(true)), 1),
+            "false" => (Expression::Literal(Value::Bool(false)), 1),
+            _ => if self.eat("(") {
+                let (arguments, depth, _) = self.list(")")?;
+                match constructor_literal(&name, &arguments, position)? {
+                    Some(value) => (Expression::Literal(value), 1),
+                    None => (Expression::Call(name, arguments), self.parent_depth(depth)?),
+                }
+            } else {
+                (Expression::Variable(name), 1)
+            }
+        })
+    }
+
+    // A number, string or enum variant literal.
+    fn literal(&mut self, token: Token, position: usize) -> Result<Value> {
+        Ok(match token {
+            Token::Int(text) => int_value(&text)?,
+            Token::Float(text) => Value::Float(f64::from_str(&text).map_err(|_| Error::new(format!("expression error at {position}: invalid number")))?),
+            Token::String(s) => Value::String(s),
+            Token::Punctuation(".") => match self.next() {
+                Token::Identifier(name) => Value::EnumVariant(EnumVariant { value: name, args: None }),
+                _ => Err(self.unexpected_previous())?,
+            },
+            _ => Err(self.unexpected_previous())?,
+        })
+    }
+}
+
+fn int_value(text: &str) -> Result<Value> {
+    if let Ok(i) = i32::from_str(text) {
+        Ok(Value::Int(i))
+    } else if let Ok(i) = i64::from_str(text) {
+        Ok(Value::Int64(i))
+    } else {
+        Err(Error::new(format!("expression error: integer `{text}` is out of range")))
+    }
+}
+
+/// Literals which are written like calls in Teon syntax, such as `Decimal("1.5")`.
+fn constructor_literal(name: &str, arguments: &[Expression], position: usize) -> Result<Option<Value>> {
+    let argument = match arguments {
+        [Expression::Literal(Value::String(s))] => s.as_str(),
+        _ => return Ok(None),
+    };
+    let invalid = || Error::new(format!("expression error at {position}: invalid {name} literal `{argument}`"));
+    Ok(Some(match name {
+        "Decimal" => Value::Decimal(BigDecimal::from_str(argument).map_err(|_| invalid())?),
+        "Date" => Value::Date(NaiveDate::parse_from_str(argument, "%Y-%m-%d").map_err(|_| invalid())?),
+        "DateTime" => Value::DateTime(DateTime::parse_from_rfc3339(argument).map_err(|_| invalid())?.with_timezone(&Utc)),
+        "ObjectId" => Value::ObjectId(ObjectId::parse_str(argument).map_err(|_| invalid())?),
+        _ => return Ok(None),
+    }))
+}
@@ -0,0 +1,226 @@
+//! Matching Teon documents against Teo's `where` filter dictionaries in memory.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::filter::matches;
+//! #
+//! let user = teon!({"name": "Alice", "age": 20});
+//! let filter = teon!({"age": {"gte": 18}, "name": {"startsWith": "al", "mode": "insensitive"}});
+//! assert!(matches(&user, &filter).unwrap());
+//! ```
+//!
+//! A filter is a dictionary of field names to conditions, combined with `AND`. A condition is
+//! either a dictionary of operators (`equals`, `not`, `in`, `notIn`, `lt`, `lte`, `gt`, `gte`,
+//! `contains`, `startsWith`, `endsWith`, with `mode: "insensitive"` for case-insensitive string
+//! matching), a nested filter for dictionary fields (optionally wrapped in `is` or `isNot`),
+//! `some`, `every` or `none` for arrays of dictionaries, a range as shorthand for its bounds, or
+//! any other value as shorthand for `equals`. `AND`, `OR` and `NOT` combine nested filters.
+
+use std::cmp::Ordering;
+use indexmap::IndexMap;
+use teo_result::{Error, Result};
+use crate::types::range::Range;
+use crate::value::{operands_error_message, Value};
+
+const OPERATORS: [&str; 12] = ["equals", "not", "in", "notIn", "lt", "lte", "gt", "gte", "contains", "startsWith", "endsWith", "mode"];
+const RELATION_OPERATORS: [&str; 5] = ["is", "isNot", "some", "every", "none"];
+
+/// Whether `document` matches the `where` filter. A `null` filter matches everything.
+pub fn matches(document: &Value, filter: &Value) -> Result<bool> {
+    match filter {
+        Value::Null => Ok(true),
+        Value::Dictionary(filter) => matches_dictionary(document, filter),
+        _ => Err(Error::new(format!("cannot filter with {}, expect Dictionary", filter.type_hint()))),
+    }
+}
+
+fn matches_dictionary(document: &Value, filter: &IndexMap<String, Value>) -> Result<bool> {
+    for (key, condition) in filter {
+        let matched = match key.as_str() {
+            "AND" => {
+                let mut matched = true;
+                for filter in filter_list(condition, "AND")? {
+                    if !matches(document, filter)? {
+                        matched = false;
+                        break;
+                    }
+                }
+                matched
+            }
+            "OR" => {
+                let mut matched = false;
+                for filter in filter_list(condition, "OR")? {
+                    if matches(document, filter)? {
+                        matched = true;
+                        break;
+                    }
+                }
+                matched
+            }
+            "NOT" => {
+                let mut matched = true;
+                for filter in filter_list(condition, "NOT")? {
+                    if matches(document, filter)? {
+                        matched = false;
+                        break;
+                    }
+                }
+                matched
+            }
+            _ => matches_field(document.get(key.as_str()).unwrap_or(&Value::Null), condition)?,
+        };
+        if !matched {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn filter_list<'a>(condition: &'a Value, name: &str) -> Result<Vec<&'a Value>> {
+    match condition {
+        Value::Array(filters) => Ok(filters.iter().collect()),
+        Value::Dictionary(_) => Ok(vec![condition]),
+        _ => Err(Error::new(format!("cannot {name} with {}, expect Array or Dictionary", condition.type_hint()))),
+    }
+}
+
+fn matches_field(field: &Value, condition: &Value) -> Result<bool> {
+    match condition {
+        Value::Dictionary(map) if map.keys().any(|k| OPERATORS.contains(&k.as_str())) => matches_operators(field, map),
+        Value::Dictionary(map) if map.keys().any(|k| RELATION_OPERATORS.contains(&k.as_str())) => matches_relation(field, map),
+        Value::Dictionary(_) => match field {
+            Value::Dictionary(_) => matches(field, condition),
+            Value::Null => Ok(false),
+            Value::Array(_) => Err(Error::new("cannot filter Array with a nested filter, use `some`, `every` or `none`")),
+            _ => Err(Error::new(format!("cannot filter {} with a nested filter", field.type_hint()))),
+        },
+        Value::Range(range) => matches_range(field, range),
+        _ => Ok(equals(field, condition)),
+    }
+}
+
+fn matches_relation(field: &Value, map: &IndexMap<String, Value>) -> Result<bool> {
+    for (key, filter) in map {
+        let matched = match key.as_str() {
+            "is" => if filter.is_null() { field.is_null() } else { !field.is_null() && matches(field, filter)? },
+            "isNot" => if filter.is_null() { !field.is_null() } else { field.is_null() || !matches(field, filter)? },
+            "some" | "every" | "none" => {
+                let items = match field {
+                    Value::Array(items) => items,
+                    Value::Null => &vec![],
+                    _ => Err(Error::new(format!("cannot filter {} with `{}`, expect Array", field.type_hint(), key)))?,
+                };
+                let mut count = 0;
+                for item in items {
+                    if matches(item, filter)? {
+                        count += 1;
+                    }
+                }
+                match key.as_str() {
+                    "some" => count > 0,
+                    "every" => count == items.len(),
+                    _ => count == 0,
+                }
+            }
+            _ => Err(Error::new(format!("unknown relation filter `{}`", key)))?,
+        };
+        if !matched {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn matches_range(field: &Value, range: &Range) -> Result<bool> {
+    if field.is_null() {
+        return Ok(false);
+    }
+    let lower = compare(field, &range.start)?;
+    let upper = compare(field, &range.end)?;
+    Ok(lower != Ordering::Less && (upper == Ordering::Less || (range.closed && upper == Ordering::Equal)))
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Result<Ordering> {
+    crate::ops::aggregate::compare(lhs, rhs).ok_or_else(|| operands_error_message(lhs, rhs, "compare"))
+}
+
+// Equality which, like `compare`, treats a decimal and another number of the same value as equal.
+fn equals(lhs: &Value, rhs: &Value) -> bool {
+    if lhs.is_any_number() && rhs.is_any_number() {
+        crate::ops::aggregate::compare(lhs, rhs) == Some(Ordering::Equal)
+    } else {
+        lhs == rhs
+    }
+}
+
+fn is_insensitive(map: &IndexMap<String, Value>) -> Result<bool> {
+    match map.get("mode") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::String(mode)) if mode == "insensitive" => Ok(true),
+        Some(Value::String(mode)) if mode == "default" => Ok(false),
+        Some(Value::EnumVariant(mode)) if mode.value == "insensitive" => Ok(true),
+        Some(Value::EnumVariant(mode)) if mode.value == "default" => Ok(false),
+        Some(mode) => Err(Error::new(format!("invalid filter mode {}", mode))),
+    }
+}
+
+fn fold_case(value: &Value, insensitive: bool) -> Value {
+    match value {
+        Value::String(s) if insensitive => Value::String(s.to_lowercase()),
+        _ => value.clone(),
+    }
+}
+
+fn matches_operators(field: &Value, map: &IndexMap<String, Value>) -> Result<bool> {
+    let insensitive = is_insensitive(map)?;
+    let folded = fold_case(field, insensitive);
+    for (key, argument) in map {
+        let matched = match key.as_str() {
+            "mode" => true,
+            "equals" => equals(&folded, &fold_case(argument, insensitive)),
+            "not" => match argument {
+                Value::Dictionary(not) => {
+                    let mut not = not.clone();
+                    if insensitive && !not.contains_key("mode") {
+                        not.insert("mode".to_owned(), Value::String("insensitive".to_owned()));
+                    }
+                    !matches_field(field, &Value::Dictionary(not))?
+                }
+                _ => !equals(&folded, &fold_case(argument, insensitive)),
+            },
+            "in" | "notIn" => {
+                let list = argument.as_array().ok_or_else(|| Error::new(format!("cannot filter with `{}` {}, expect Array", key, argument.type_hint())))?;
+                let found = list.iter().any(|item| equals(&folded, &fold_case(item, insensitive)));
+                if key == "in" { found } else { !found }
+            }
+            "lt" | "lte" | "gt" | "gte" => {
+                if field.is_null() || argument.is_null() {
+                    false
+                } else {
+                    let ordering = compare(&folded, &fold_case(argument, insensitive))?;
+                    match key.as_str() {
+                        "lt" => ordering.is_lt(),
+                        "lte" => ordering.is_le(),
+                        "gt" => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    }
+                }
+            }
+            "contains" | "startsWith" | "endsWith" => match (&folded, fold_case(argument, insensitive)) {
+                (Value::Null, _) => false,
+                (Value::String(s), Value::String(pattern)) => match key.as_str() {
+                    "contains" => s.contains(pattern.as_str()),
+                    "startsWith" => s.starts_with(pattern.as_str()),
+                    _ => s.ends_with(pattern.as_str()),
+                },
+                (Value::Array(items), argument) if key == "contains" => items.iter().any(|item| fold_case(item, insensitive) == argument),
+                (_, argument) => Err(operands_error_message(field, &argument, key))?,
+            },
+            _ => Err(Error::new(format!("unknown filter operator `{}`", key)))?,
+        };
+        if !matched {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,332 @@
+//! Schema inference from sample values.
+//!
+//! [`infer_type`] unifies the types of sample values into a [`Type`], and reports what was
+//! observed at every path: how often a key is present or null, the number of distinct values,
+//! a few examples, and candidate types for strings which always parse as something more
+//! specific.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::infer::infer_type;
+//! # use teo_teon::schema::json_schema::validate;
+//! #
+//! let report = infer_type(&[
+//!     teon!({"id": 1, "born": "1990-01-02"}),
+//!     teon!({"id": 2, "born": "1985-12-31", "nickname": "Bo"}),
+//! ]);
+//! assert_eq!(report.ty.to_string(), "{ id: Int, born: String, nickname: String? }");
+//! let born = report.field("$.born").unwrap();
+//! assert_eq!(born.candidates[0].to_string(), "Date");
+//! assert_eq!(report.field("$.nickname").unwrap().missing, 1);
+//! assert!(validate(&teon!({"id": 3, "born": "2000-01-01"}), &report.json_schema()).is_ok());
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::mem;
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate};
+use indexmap::IndexMap;
+use serde_json::{Value as JsonValue};
+use crate::schema::json_schema::to_json_schema;
+use crate::schema::Type;
+use crate::value::Value;
+use crate::visit::PathSegment;
+
+/// Options for [`infer_type_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferOptions {
+    /// The number of distinct examples kept for each path.
+    pub max_examples: usize,
+    /// The number of distinct values counted for each path. Paths with more are reported as
+    /// having at least this many.
+    pub max_distinct: usize,
+    /// Whether strings use their first candidate type in the inferred type.
+    pub promote_candidates: bool,
+}
+
+impl Default for InferOptions {
+
+    fn default() -> Self {
+        Self {
+            max_examples: 3,
+            max_distinct: 1000,
+            promote_candidates: false,
+        }
+    }
+}
+
+/// What was observed at one path of the samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldReport {
+    /// The path, `$` for the samples themselves, `$.key` for dictionary values and `$.key[]`
+    /// for array elements.
+    pub path: String,
+    /// The unified type of the values at this path. It's optional when the key is sometimes
+    /// missing.
+    pub ty: Type,
+    /// The number of values at this path, including nulls.
+    pub count: usize,
+    /// The number of dictionaries at the parent path without this key.
+    pub missing: usize,
+    /// The number of null values.
+    pub nulls: usize,
+    /// The number of distinct values other than null, dictionaries and arrays, at most
+    /// [`InferOptions::max_distinct`].
+    pub distinct: usize,
+    /// Whether there are more distinct values than counted.
+    pub distinct_capped: bool,
+    /// Distinct examples of the values other than null, dictionaries and arrays.
+    pub examples: Vec<Value>,
+    /// For strings, the more specific types which all of them parse as, in order of preference
+    /// from `Bool`, `Int64`, `Decimal`, `ObjectId`, `Date` and `DateTime`.
+    pub candidates: Vec<Type>,
+}
+
+/// The result of [`infer_type`]. Its `Display` implementation prints a table of the fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferenceReport {
+    /// The number of samples.
+    pub samples: usize,
+    /// The inferred type of the samples.
+    pub ty: Type,
+    /// What was observed at each path, parents before children.
+    pub fields: Vec<FieldReport>,
+}
+
+impl InferenceReport {
+
+    /// The report of a path.
+    pub fn field(&self, path: &str) -> Option<&FieldReport> {
+        self.fields.iter().find(|f| f.path == path)
+    }
+
+    /// The JSON Schema of the inferred type, for validating later values.
+    pub fn json_schema(&self) -> JsonValue {
+        to_json_schema(&self.ty)
+    }
+}
+
+/// Infer the type of sample values with the default options.
+pub fn infer_type(values: &[Value]) -> InferenceReport {
+    infer_type_with(values, &InferOptions::default())
+}
+
+/// Infer the type of sample values.
+pub fn infer_type_with(values: &[Value], options: &InferOptions) -> InferenceReport {
+    let mut collector = Collector { options, stats: IndexMap::new() };
+    for value in values {
+        collector.visit(value, "$", None);
+    }
+    let types = types(&collector.stats);
+    let mut fields = vec![];
+    for ((path, stats), ty) in collector.stats.iter().zip(types) {
+        let missing = match &stats.parent {
+            Some((parent, _)) => collector.stats[parent].dictionaries - stats.count,
+            None => 0,
+        };
+        let ty = if missing > 0 { ty.or_missing() } else { ty };
+        let candidates = match &ty {
+            Type::String => stats.candidates.clone().unwrap_or_default(),
+            Type::Optional(inner) if **inner == Type::String => stats.candidates.clone().unwrap_or_default(),
+            _ => vec![],
+        };
+        fields.push(FieldReport {
+            path: path.clone(),
+            ty,
+            count: stats.count,
+            missing,
+            nulls: stats.nulls,
+            distinct: stats.distinct.len(),
+            distinct_capped: stats.distinct_capped,
+            examples: stats.examples.clone(),
+            candidates,
+        });
+    }
+    let ty = fields.first().map_or(Type::Any, |field| field.ty.clone());
+    let ty = if options.promote_candidates { promote(ty, "$", &fields) } else { ty };
+    InferenceReport { samples: values.len(), ty, fields }
+}
+
+#[derive(Default)]
+struct Stats {
+    // the parent path and the key when this is a dictionary value
+    parent: Option<(String, String)>,
+    // the distinct types of the values in order of appearance, with an empty shape for
+    // dictionaries and an array of `Never` for arrays
+    kinds: Vec<Type>,
+    count: usize,
+    nulls: usize,
+    dictionaries: usize,
+    distinct: HashSet<String>,
+    distinct_capped: bool,
+    examples: Vec<Value>,
+    // None until a string is seen
+    candidates: Option<Vec<Type>>,
+}
+
+struct Collector<'a> {
+    options: &'a InferOptions,
+    stats: IndexMap<String, Stats>,
+}
+
+fn key_path(parent: &str, key: &str) -> String {
+    format!("{}{}", parent, PathSegment::Key(key.to_owned()))
+}
+
+fn parses_as(s: &str, t: &Type) -> bool {
+    match t {
+        Type::Bool => s == "true" || s == "false",
+        Type::Int64 => s.parse::<i64>().is_ok(),
+        Type::Decimal => s.bytes().any(|b| b.is_ascii_digit()) && BigDecimal::from_str(s).is_ok(),
+        Type::ObjectId => ObjectId::parse_str(s).is_ok(),
+        Type::Date => NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        Type::DateTime => DateTime::parse_from_rfc3339(s).is_ok(),
+        _ => false,
+    }
+}
+
+impl<'a> Collector<'a> {
+
+    fn visit(&mut self, value: &Value, path: &str, parent: Option<(&str, &str)>) {
+        let options = self.options;
+        let stats = self.stats.entry(path.to_owned()).or_insert_with(|| Stats {
+            parent: parent.map(|(parent, key)| (parent.to_owned(), key.to_owned())),
+            ..Default::default()
+        });
+        stats.count += 1;
+        let kind = match value {
+            Value::Dictionary(_) => Type::Shape(IndexMap::new()),
+            Value::Array(_) => Type::Array(Box::new(Type::Union(vec![]))),
+            value => Type::of(value),
+        };
+        if !stats.kinds.contains(&kind) {
+            stats.kinds.push(kind);
+        }
+        match value {
+            Value::Null => stats.nulls += 1,
+            Value::Dictionary(map) => {
+                stats.dictionaries += 1;
+                for (key, item) in map {
+                    self.visit(item, &key_path(path, key), Some((path, key)));
+                }
+            }
+            Value::Array(items) => {
+                let path = format!("{}[]", path);
+                for item in items {
+                    self.visit(item, &path, None);
+                }
+            }
+            value => {
+                let key = value.to_string();
+                if !stats.distinct.contains(&key) {
+                    if stats.distinct.len() < options.max_distinct {
+                        stats.distinct.insert(key);
+                        if stats.examples.len() < options.max_examples {
+                            stats.examples.push(value.clone());
+                        }
+                    } else {
+                        stats.distinct_capped = true;
+                    }
+                }
+                let candidates = stats.candidates.get_or_insert_with(|| vec![Type::Bool, Type::Int64, Type::Decimal, Type::ObjectId, Type::Date, Type::DateTime]);
+                match value {
+                    Value::String(s) => candidates.retain(|t| parses_as(s, t)),
+                    _ => candidates.clear(),
+                }
+            }
+        }
+    }
+}
+
+// The type of the values at each path, in the order of `stats`. Parents are inserted before their
+// children, so in reverse order the types of the children are known when their parent is typed.
+fn types(stats: &IndexMap<String, Stats>) -> Vec<Type> {
+    let mut shapes: Vec<IndexMap<String, Type>> = (0..stats.len()).map(|_| IndexMap::new()).collect();
+    let mut elements: Vec<Option<Type>> = vec![None; stats.len()];
+    let mut types = vec![Type::Any; stats.len()];
+    for (index, (path, path_stats)) in stats.iter().enumerate().rev() {
+        let ty = path_stats.kinds.iter().fold(Type::Union(vec![]), |ty, kind| ty.unify(match kind {
+            Type::Shape(_) => {
+                let mut fields = mem::take(&mut shapes[index]);
+                fields.reverse();
+                Type::Shape(fields)
+            }
+            Type::Array(_) => Type::Array(Box::new(elements[index].take().unwrap_or(Type::Union(vec![])))),
+            kind => kind.clone(),
+        }));
+        match &path_stats.parent {
+            Some((parent, key)) => {
+                let parent_index = stats.get_index_of(parent).unwrap();
+                let missing = stats[parent_index].dictionaries > path_stats.count;
+                shapes[parent_index].insert(key.clone(), if missing { ty.clone().or_missing() } else { ty.clone() });
+            }
+            None => if let Some(parent) = path.strip_suffix("[]") {
+                elements[stats.get_index_of(parent).unwrap()] = Some(ty.clone());
+            },
+        }
+        types[index] = ty;
+    }
+    types
+}
+
+// Replaces strings by their first candidate type.
+fn promote(t: Type, path: &str, fields: &[FieldReport]) -> Type {
+    match t {
+        Type::Optional(inner) => promote(*inner, path, fields).optional(),
+        Type::String => fields.iter().find(|f| f.path == path).and_then(|f| f.candidates.first().cloned()).unwrap_or(Type::String),
+        Type::Array(inner) => Type::Array(Box::new(promote(*inner, &format!("{}[]", path), fields))),
+        Type::Shape(shape) => Type::Shape(shape.into_iter().map(|(k, t)| {
+            let t = promote(t, &key_path(path, &k), fields);
+            (k, t)
+        }).collect()),
+        t => t,
+    }
+}
+
+// The type column of the report, without the fields of nested shapes.
+fn brief(t: &Type) -> String {
+    match t {
+        Type::Shape(_) => "{ .. }".to_owned(),
+        Type::Optional(inner) => format!("{}?", brief(inner)),
+        Type::Array(inner) => format!("{}[]", brief(inner)),
+        Type::Union(members) => members.iter().map(brief).collect::<Vec<_>>().join(" | "),
+        t => t.to_string(),
+    }
+}
+
+impl Display for FieldReport {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "present {}/{}", self.count - self.nulls, self.count + self.missing)?;
+        if self.nulls > 0 {
+            write!(f, ", null {}", self.nulls)?;
+        }
+        if self.distinct > 0 {
+            write!(f, ", distinct {}{}", self.distinct, if self.distinct_capped { "+" } else { "" })?;
+        }
+        if !self.examples.is_empty() {
+            write!(f, ", e.g. {}", self.examples.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))?;
+        }
+        if !self.candidates.is_empty() {
+            write!(f, ", candidates {}", self.candidates.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InferenceReport {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} samples", self.samples)?;
+        let types: Vec<String> = self.fields.iter().map(|field| brief(&field.ty)).collect();
+        let path_width = self.fields.iter().map(|field| field.path.chars().count()).max().unwrap_or(0);
+        let type_width = types.iter().map(|t| t.chars().count()).max().unwrap_or(0);
+        for (field, t) in self.fields.iter().zip(&types) {
+            writeln!(f, "{:path_width$}  {:type_width$}  {}", field.path, t, field)?;
+        }
+        Ok(())
+    }
+}
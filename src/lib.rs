@@ -5,6 +5,22 @@ pub mod value;
 pub mod convert;
 pub mod index;
 pub mod serde;
+pub mod ops;
+pub mod expr;
+pub mod filter;
+pub mod query;
+pub mod pretty;
+pub mod canonical;
+pub mod stream;
+pub mod csv;
+pub mod query_string;
+pub mod multipart;
+pub mod schema;
+pub mod infer;
+pub mod visit;
+pub mod redact;
+pub mod shared;
+mod utils;
 
 #[macro_use]
 mod macros;
@@ -0,0 +1,362 @@
+//! `multipart/form-data` decoding into Teon values.
+//!
+//! Text parts become strings and file parts become [`File`] values whose content is written to
+//! a temporary directory. Part names use the same bracket notation as query strings, so
+//! `user[name]` and `photos[]` build nested dictionaries and arrays.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::multipart::{from_multipart, MultipartOptions};
+//! #
+//! let body = "--XyZ\r\n\
+//!     Content-Disposition: form-data; name=\"user[name]\"\r\n\r\n\
+//!     Ann\r\n\
+//!     --XyZ\r\n\
+//!     Content-Disposition: form-data; name=\"avatar\"; filename=\"me.png\"\r\n\
+//!     Content-Type: image/png\r\n\r\n\
+//!     PNG\r\n\
+//!     --XyZ--\r\n";
+//! let value = from_multipart(body.as_bytes(), "XyZ", &MultipartOptions::default()).unwrap();
+//! assert_eq!(value.get("user"), Some(&teon!({"name": "Ann"})));
+//! let avatar = value.get("avatar").unwrap().as_file().unwrap();
+//! assert_eq!(avatar.filename, "me.png");
+//! assert_eq!(avatar.content_type.as_deref(), Some("image/png"));
+//! # std::fs::remove_file(&avatar.filepath).unwrap();
+//! ```
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use teo_result::{Error, Result};
+use crate::query_string::{Builder, QueryStringOptions};
+use crate::types::file::File;
+use crate::utils::mime::mime_from_extension;
+use crate::utils::percent::percent_decode;
+use crate::utils::sha256::to_hex;
+use crate::value::Value;
+
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Options for [`from_multipart`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartOptions {
+    /// The directory uploaded files are written to. The files are not removed by this crate;
+    /// move them with [`File::persist_to`] or remove them once the request is handled.
+    pub temp_dir: PathBuf,
+    /// The maximum size in bytes of each uploaded file.
+    pub max_file_size: u64,
+    /// The maximum size in bytes of each text field.
+    pub max_field_size: usize,
+    /// The maximum size in bytes of the whole body.
+    pub max_body_size: u64,
+    /// The maximum number of parts.
+    pub max_parts: usize,
+    /// The maximum number of bracket segments read from a part name. See
+    /// [`QueryStringOptions::depth`].
+    pub depth: usize,
+    /// The largest index read as an array index. See [`QueryStringOptions::array_limit`].
+    pub array_limit: usize,
+}
+
+impl Default for MultipartOptions {
+
+    fn default() -> Self {
+        Self {
+            temp_dir: std::env::temp_dir(),
+            max_file_size: 64 * 1024 * 1024,
+            max_field_size: 1024 * 1024,
+            max_body_size: 128 * 1024 * 1024,
+            max_parts: 1000,
+            depth: 5,
+            array_limit: 20,
+        }
+    }
+}
+
+/// The boundary parameter of a `multipart/form-data` content type header value.
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+    let (mime, params) = parse_header_value(content_type);
+    if !mime.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params.into_iter().find(|(k, _)| k.eq_ignore_ascii_case("boundary")).map(|(_, v)| v).filter(|b| !b.is_empty())
+}
+
+/// Decode a `multipart/form-data` body read from `reader` into a dictionary.
+///
+/// The body is streamed: file content is written to `options.temp_dir` as it is read, and only
+/// text fields are held in memory. Parts with an empty filename, which browsers send for file
+/// inputs left empty, are skipped. When decoding fails, the files written so far are removed.
+pub fn from_multipart<R: Read>(reader: R, boundary: &str, options: &MultipartOptions) -> Result<Value> {
+    let mut parser = Parser {
+        reader,
+        // the first delimiter may not be preceded by a line break
+        buffer: b"\r\n".to_vec(),
+        delimiter: format!("\r\n--{}", boundary).into_bytes(),
+        read: 0,
+        eof: false,
+        options,
+        files: vec![],
+    };
+    let result = parser.parse();
+    if result.is_err() {
+        for path in &parser.files {
+            let _ = fs::remove_file(path);
+        }
+    }
+    result
+}
+
+struct Parser<'a, R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    delimiter: Vec<u8>,
+    read: u64,
+    eof: bool,
+    options: &'a MultipartOptions,
+    files: Vec<PathBuf>,
+}
+
+enum Sink {
+    Field(Vec<u8>),
+    File(fs::File, u64),
+}
+
+fn multipart_error(message: impl AsRef<str>) -> Error {
+    Error::new(format!("multipart error: {}", message.as_ref()))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+impl<'a, R: Read> Parser<'a, R> {
+
+    // Reads another chunk into the buffer, returning false at the end of the body.
+    fn fill(&mut self) -> Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let mut chunk = [0u8; CHUNK_SIZE];
+        let len = self.reader.read(&mut chunk).map_err(|e| multipart_error(format!("cannot read body: {}", e)))?;
+        if len == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+        self.read += len as u64;
+        if self.read > self.options.max_body_size {
+            Err(multipart_error(format!("body exceeds the size limit of {} bytes", self.options.max_body_size)))?
+        }
+        self.buffer.extend_from_slice(&chunk[..len]);
+        Ok(true)
+    }
+
+    // Makes sure the buffer holds at least `len` bytes.
+    fn require(&mut self, len: usize) -> Result<()> {
+        while self.buffer.len() < len {
+            if !self.fill()? {
+                Err(multipart_error("unexpected end of body"))?
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(&mut self) -> Result<Value> {
+        let query_string_options = QueryStringOptions { depth: self.options.depth, array_limit: self.options.array_limit, ..Default::default() };
+        let mut builder = Builder::new(&query_string_options);
+        // skip the preamble
+        self.read_part(&mut None)?;
+        let mut parts = 0;
+        while self.after_delimiter()? {
+            parts += 1;
+            if parts > self.options.max_parts {
+                Err(multipart_error(format!("body exceeds the limit of {} parts", self.options.max_parts)))?
+            }
+            let headers = self.read_headers()?;
+            let (disposition, params) = parse_header_value(headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-disposition")).map(|(_, v)| v.as_str()).unwrap_or(""));
+            if !disposition.eq_ignore_ascii_case("form-data") {
+                Err(multipart_error("part without form-data content disposition"))?
+            }
+            let param = |name: &str| params.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone());
+            let name = param("name").ok_or_else(|| multipart_error("part without a name"))?;
+            let filename = match param("filename*").and_then(|f| decode_ext_value(&f)) {
+                Some(filename) => Some(filename),
+                None => param("filename"),
+            };
+            let content_type = headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-type")).map(|(_, v)| v.clone());
+            match filename.as_deref().map(base_name) {
+                Some("") => self.read_part(&mut None)?,
+                Some(filename) => {
+                    let file = self.read_file(filename, content_type)?;
+                    builder.insert(&name, Value::File(file))?;
+                }
+                None => {
+                    let mut sink = Some(Sink::Field(vec![]));
+                    self.read_part(&mut sink)?;
+                    let Some(Sink::Field(bytes)) = sink else { unreachable!() };
+                    let text = String::from_utf8(bytes).map_err(|_| multipart_error(format!("field `{}` is not valid UTF-8", name)))?;
+                    builder.insert(&name, Value::String(text))?;
+                }
+            }
+        }
+        Ok(builder.finish())
+    }
+
+    // Consumes what follows a delimiter, returning false for the closing delimiter.
+    fn after_delimiter(&mut self) -> Result<bool> {
+        self.require(2)?;
+        if self.buffer.starts_with(b"--") {
+            return Ok(false);
+        }
+        // skip transport padding, and leave the line break for `read_headers`
+        loop {
+            match self.buffer.first() {
+                Some(b' ' | b'\t') => {
+                    self.buffer.remove(0);
+                }
+                Some(_) => break,
+                None => self.require(1)?,
+            }
+        }
+        self.require(2)?;
+        if !self.buffer.starts_with(b"\r\n") {
+            Err(multipart_error("invalid delimiter line"))?
+        }
+        Ok(true)
+    }
+
+    fn read_headers(&mut self) -> Result<Vec<(String, String)>> {
+        let end = loop {
+            if let Some(end) = find(&self.buffer, b"\r\n\r\n") {
+                break end;
+            }
+            if self.buffer.len() > MAX_HEADER_SIZE {
+                Err(multipart_error(format!("part headers exceed the size limit of {} bytes", MAX_HEADER_SIZE)))?
+            }
+            if !self.fill()? {
+                Err(multipart_error("unexpected end of body"))?
+            }
+        };
+        let text = String::from_utf8_lossy(if end >= 2 { &self.buffer[2..end] } else { &[] }).to_string();
+        self.buffer.drain(..end + 4);
+        let mut headers = vec![];
+        for line in text.split("\r\n").filter(|l| !l.is_empty()) {
+            let (name, value) = line.split_once(':').ok_or_else(|| multipart_error(format!("invalid header `{}`", line)))?;
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+        Ok(headers)
+    }
+
+    // Streams the part body into `sink` up to the next delimiter, which is consumed.
+    fn read_part(&mut self, sink: &mut Option<Sink>) -> Result<()> {
+        loop {
+            if let Some(end) = find(&self.buffer, &self.delimiter) {
+                self.write(sink, end)?;
+                self.buffer.drain(..self.delimiter.len());
+                return Ok(());
+            }
+            // keep a possible partial delimiter in the buffer
+            let keep = self.delimiter.len() - 1;
+            if self.buffer.len() > keep {
+                self.write(sink, self.buffer.len() - keep)?;
+            }
+            if !self.fill()? {
+                Err(multipart_error("unexpected end of body"))?
+            }
+        }
+    }
+
+    // Moves the first `len` bytes of the buffer into `sink`.
+    fn write(&mut self, sink: &mut Option<Sink>, len: usize) -> Result<()> {
+        match sink {
+            None => (),
+            Some(Sink::Field(bytes)) => {
+                if bytes.len() + len > self.options.max_field_size {
+                    Err(multipart_error(format!("field exceeds the size limit of {} bytes", self.options.max_field_size)))?
+                }
+                bytes.extend_from_slice(&self.buffer[..len]);
+            }
+            Some(Sink::File(file, size)) => {
+                *size += len as u64;
+                if *size > self.options.max_file_size {
+                    Err(multipart_error(format!("file exceeds the size limit of {} bytes", self.options.max_file_size)))?
+                }
+                file.write_all(&self.buffer[..len]).map_err(|e| multipart_error(format!("cannot write file: {}", e)))?;
+            }
+        }
+        self.buffer.drain(..len);
+        Ok(())
+    }
+
+    fn read_file(&mut self, filename: &str, content_type: Option<String>) -> Result<File> {
+        let filename_ext = Path::new(filename).extension().map(|ext| ext.to_string_lossy().to_string());
+        let (path, file) = self.create_temp_file(filename_ext.as_deref())?;
+        let mut sink = Some(Sink::File(file, 0));
+        self.read_part(&mut sink)?;
+        if let Some(Sink::File(file, _)) = sink {
+            file.sync_all().map_err(|e| multipart_error(format!("cannot write file: {}", e)))?;
+        }
+        Ok(File {
+            filepath: path.to_string_lossy().to_string(),
+            content_type: content_type.or_else(|| filename_ext.as_deref().and_then(mime_from_extension).map(ToOwned::to_owned)),
+            filename: filename.to_owned(),
+            filename_ext,
+            content: None,
+        })
+    }
+
+    fn create_temp_file(&mut self, ext: Option<&str>) -> Result<(PathBuf, fs::File)> {
+        fs::create_dir_all(&self.options.temp_dir).map_err(|e| multipart_error(format!("cannot create directory `{}`: {}", self.options.temp_dir.display(), e)))?;
+        let mut random = [0u8; 16];
+        getrandom::getrandom(&mut random).map_err(|e| multipart_error(format!("cannot name file: {}", e)))?;
+        let mut name = format!("teon-upload-{}", to_hex(&random));
+        // only keep extensions which can't change the meaning of the path
+        if let Some(ext) = ext.filter(|ext| ext.bytes().all(|b| b.is_ascii_alphanumeric())) {
+            name = format!("{}.{}", name, ext);
+        }
+        let path = self.options.temp_dir.join(name);
+        let file = fs::OpenOptions::new().write(true).create_new(true).open(&path).map_err(|e| multipart_error(format!("cannot create file `{}`: {}", path.display(), e)))?;
+        self.files.push(path.clone());
+        Ok((path, file))
+    }
+}
+
+// The last segment of a filename, as some clients send full paths.
+fn base_name(filename: &str) -> &str {
+    filename.rsplit(['/', '\\']).next().unwrap_or(filename)
+}
+
+// Decodes an RFC 5987 extended value like `UTF-8''na%C3%AFve.txt`.
+fn decode_ext_value(value: &str) -> Option<String> {
+    let (charset, rest) = value.split_once('\'')?;
+    let (_, encoded) = rest.split_once('\'')?;
+    if !charset.eq_ignore_ascii_case("utf-8") {
+        return None;
+    }
+    percent_decode(encoded, false)
+}
+
+// Splits a header value like `form-data; name="a"` into its first item and parameters. Quoted
+// parameter values may contain `;`. Backslashes are kept as they are, since browsers escape
+// quotes in filenames as `%22` and older ones send Windows paths.
+fn parse_header_value(value: &str) -> (String, Vec<(String, String)>) {
+    let mut items = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => items.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    items.push(current);
+    let mut items = items.into_iter();
+    let first = items.next().unwrap_or_default().trim().to_owned();
+    let params = items.filter_map(|item| {
+        let (k, v) = item.split_once('=')?;
+        Some((k.trim().to_owned(), v.trim().to_owned()))
+    }).collect();
+    (first, params)
+}
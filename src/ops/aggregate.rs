@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive, Zero};
+use teo_result::{Error, Result};
+use crate::value::{operand_error_message, Value};
+
+/// How aggregate functions treat `null` and missing values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullHandling {
+    /// Leave nulls out of the aggregation.
+    #[default]
+    Skip,
+    /// Fail when a null is encountered.
+    Error,
+}
+
+/// Options for aggregate functions over arrays.
+#[derive(Debug, Clone, Default)]
+pub struct AggregateOptions {
+    /// A dotted path into each element which selects the aggregated value, see
+    /// `Value::get_path`. Elements are aggregated directly when this is `None`.
+    pub path: Option<String>,
+    pub nulls: NullHandling,
+}
+
+impl AggregateOptions {
+
+    pub fn path(path: impl Into<String>) -> Self {
+        Self { path: Some(path.into()), nulls: NullHandling::Skip }
+    }
+}
+
+impl Value {
+
+    fn aggregated_values(&self, options: &AggregateOptions, name: &str) -> Result<Vec<&Value>> {
+        let array = match self {
+            Value::Array(a) => a,
+            _ => Err(operand_error_message(self, name))?,
+        };
+        let mut values = vec![];
+        for item in array {
+            let value = match &options.path {
+                Some(path) => item.get_path(path).unwrap_or(&Value::Null),
+                None => item,
+            };
+            if value.is_null() {
+                match options.nulls {
+                    NullHandling::Skip => continue,
+                    NullHandling::Error => Err(operand_error_message(value, name))?,
+                }
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// The sum of numbers. The result is a `Decimal` when any element is a `Decimal`, a `Float`
+    /// when any element is a float, an `Int64` when any element is an `Int64` or the sum does
+    /// not fit in an `Int`, and an `Int` otherwise. The sum of no elements is `0`.
+    pub fn sum(&self, options: &AggregateOptions) -> Result<Value> {
+        let values = self.aggregated_values(options, "sum")?;
+        sum_numbers(&values, "sum")
+    }
+
+    /// The arithmetic mean of numbers, as a `Decimal` when any element is a `Decimal` and a
+    /// `Float` otherwise. Returns `null` when there are no elements.
+    pub fn avg(&self, options: &AggregateOptions) -> Result<Value> {
+        let values = self.aggregated_values(options, "avg")?;
+        if values.is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(match sum_numbers(&values, "avg")? {
+            Value::Decimal(d) => Value::Decimal(d / BigDecimal::from(values.len() as i64)),
+            sum => Value::Float(sum.to_float().unwrap() / values.len() as f64),
+        })
+    }
+
+    /// The smallest element. Returns `null` when there are no elements.
+    pub fn min(&self, options: &AggregateOptions) -> Result<Value> {
+        let values = self.aggregated_values(options, "min")?;
+        extreme(&values, "min", Ordering::Less)
+    }
+
+    /// The largest element. Returns `null` when there are no elements.
+    pub fn max(&self, options: &AggregateOptions) -> Result<Value> {
+        let values = self.aggregated_values(options, "max")?;
+        extreme(&values, "max", Ordering::Greater)
+    }
+
+    /// The number of aggregated elements.
+    pub fn count(&self, options: &AggregateOptions) -> Result<Value> {
+        Ok(Value::Int64(self.aggregated_values(options, "count")?.len() as i64))
+    }
+
+    /// The number of distinct aggregated elements.
+    pub fn count_distinct(&self, options: &AggregateOptions) -> Result<Value> {
+        let values = self.aggregated_values(options, "count_distinct")?;
+        let mut distinct: Vec<&Value> = vec![];
+        for v in values {
+            if !distinct.contains(&v) {
+                distinct.push(v);
+            }
+        }
+        Ok(Value::Int64(distinct.len() as i64))
+    }
+
+    /// The median of numbers, see `percentile`.
+    pub fn median(&self, options: &AggregateOptions) -> Result<Value> {
+        self.percentile(50.0, options)
+    }
+
+    /// The `p`th percentile of numbers, with `p` between 0 and 100, using linear interpolation
+    /// between the closest ranks. The result is a `Decimal` when any element is a `Decimal` and
+    /// a `Float` otherwise. Returns `null` when there are no elements.
+    pub fn percentile(&self, p: f64, options: &AggregateOptions) -> Result<Value> {
+        if !(0.0..=100.0).contains(&p) {
+            Err(Error::new(format!("cannot percentile with {p}, expect a value between 0 and 100")))?
+        }
+        let values = self.aggregated_values(options, "percentile")?;
+        if values.is_empty() {
+            return Ok(Value::Null);
+        }
+        check_numbers(&values, "percentile")?;
+        let rank = p / 100.0 * (values.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let fraction = rank - lower as f64;
+        if values.iter().any(|v| v.is_decimal()) {
+            let mut sorted: Vec<BigDecimal> = values.iter().map(|v| to_decimal(v)).collect::<Result<_>>()?;
+            sorted.sort();
+            let fraction = BigDecimal::from_f64(fraction).unwrap();
+            let result = &sorted[lower] + (&sorted[upper] - &sorted[lower]) * fraction;
+            Ok(Value::Decimal(result))
+        } else {
+            let mut sorted: Vec<f64> = values.iter().map(|v| v.to_float().unwrap()).collect();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            Ok(Value::Float(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction))
+        }
+    }
+}
+
+fn check_numbers(values: &[&Value], name: &str) -> Result<()> {
+    match values.iter().find(|v| !v.is_any_number()) {
+        Some(v) => Err(operand_error_message(v, name)),
+        None => Ok(()),
+    }
+}
+
+fn to_decimal(value: &Value) -> Result<BigDecimal> {
+    Ok(match value {
+        Value::Decimal(d) => d.clone(),
+        Value::Int(i) => BigDecimal::from(*i),
+        Value::Int64(i) => BigDecimal::from(*i),
+        Value::Float32(f) => BigDecimal::from_f32(*f).ok_or_else(|| Error::new(format!("cannot convert {f} into Decimal")))?,
+        Value::Float(f) => BigDecimal::from_f64(*f).ok_or_else(|| Error::new(format!("cannot convert {f} into Decimal")))?,
+        _ => Err(operand_error_message(value, "convert into Decimal"))?,
+    })
+}
+
+fn sum_numbers(values: &[&Value], name: &str) -> Result<Value> {
+    check_numbers(values, name)?;
+    if values.iter().any(|v| v.is_decimal()) {
+        let mut sum = BigDecimal::zero();
+        for v in values {
+            sum += to_decimal(v)?;
+        }
+        Ok(Value::Decimal(sum))
+    } else if values.iter().any(|v| v.is_any_float()) {
+        Ok(Value::Float(values.iter().map(|v| v.to_float().unwrap()).sum()))
+    } else {
+        let mut sum: i64 = 0;
+        for v in values {
+            sum = sum.checked_add(v.to_int64().unwrap()).ok_or_else(|| Error::new(format!("cannot {name} Int64, overflow")))?;
+        }
+        if values.iter().any(|v| v.is_int64()) {
+            Ok(Value::Int64(sum))
+        } else {
+            Ok(sum.to_i32().map(Value::Int).unwrap_or(Value::Int64(sum)))
+        }
+    }
+}
+
+/// Compare two values, comparing a decimal with another number as decimals.
+pub(crate) fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    if (a.is_decimal() || b.is_decimal()) && a.is_any_number() && b.is_any_number() {
+        return Some(to_decimal(a).ok()?.cmp(&to_decimal(b).ok()?));
+    }
+    a.partial_cmp(b)
+}
+
+fn extreme(values: &[&Value], name: &str, wanted: Ordering) -> Result<Value> {
+    let mut result: Option<&Value> = None;
+    for v in values {
+        result = Some(match result {
+            None => v,
+            Some(current) => match compare(v, current) {
+                Some(ordering) if ordering == wanted => v,
+                Some(_) => current,
+                None => Err(Error::new(format!("cannot {name} {} with {}", current.type_hint(), v.type_hint())))?,
+            },
+        });
+    }
+    Ok(result.cloned().unwrap_or(Value::Null))
+}
@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use indexmap::IndexMap;
+use teo_result::{Error, Result};
+use crate::ops::aggregate::compare;
+use crate::value::{operand_error_message, operands_error_message, Value};
+
+impl Value {
+
+    /// Whether a value can take part in set operations. Floats are excluded since `NaN` is not
+    /// equal to itself, and so are containers other than tuples of hashable values.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Value::Null | Value::Bool(_) | Value::Int(_) | Value::Int64(_) | Value::Decimal(_) |
+            Value::ObjectId(_) | Value::String(_) | Value::Date(_) | Value::DateTime(_) |
+            Value::EnumVariant(_) | Value::OptionVariant(_) | Value::Bytes(_) => true,
+            Value::Tuple(t) => t.iter().all(|v| v.is_hashable()),
+            _ => false,
+        }
+    }
+
+    fn as_sequence(&self, name: &str) -> Result<&Vec<Value>> {
+        match self {
+            Value::Array(a) | Value::Tuple(a) => Ok(a),
+            _ => Err(operand_error_message(self, name)),
+        }
+    }
+
+    /// Whether an array or tuple contains `item`.
+    pub fn contains(&self, item: &Value) -> Result<Value> {
+        Ok(Value::Bool(self.as_sequence("contains")?.contains(item)))
+    }
+
+    /// The index of the first element equal to `item`, or `null` if there is none.
+    pub fn index_of(&self, item: &Value) -> Result<Value> {
+        Ok(match self.as_sequence("index_of")?.iter().position(|v| v == item) {
+            Some(index) => Value::Int64(index as i64),
+            None => Value::Null,
+        })
+    }
+
+    /// The elements of an array with duplicates removed, keeping the first occurrence.
+    pub fn unique(&self) -> Result<Value> {
+        let array = self.as_sequence("unique")?;
+        let mut result: Vec<Value> = vec![];
+        for v in array {
+            if !result.contains(v) {
+                result.push(v.clone());
+            }
+        }
+        Ok(Value::Array(result))
+    }
+
+    /// Flatten nested arrays by one level.
+    pub fn flatten(&self) -> Result<Value> {
+        let array = self.as_sequence("flatten")?;
+        let mut result = vec![];
+        for v in array {
+            match v {
+                Value::Array(inner) => result.extend(inner.iter().cloned()),
+                _ => result.push(v.clone()),
+            }
+        }
+        Ok(Value::Array(result))
+    }
+
+    /// Sort an array by the value at `path` in each element. Missing values and nulls sort first.
+    /// The sort is stable.
+    pub fn sort_by_key(&self, path: &str) -> Result<Value> {
+        let array = self.as_sequence("sort")?;
+        let mut keyed: Vec<(&Value, &Value)> = array.iter().map(|v| (v.get_path(path).unwrap_or(&Value::Null), v)).collect();
+        let mut error = None;
+        keyed.sort_by(|(a, _), (b, _)| {
+            match (a.is_null(), b.is_null()) {
+                (true, true) => std::cmp::Ordering::Equal,
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                (false, false) => compare(a, b).unwrap_or_else(|| {
+                    error.get_or_insert_with(|| operands_error_message(a, b, "compare"));
+                    std::cmp::Ordering::Equal
+                }),
+            }
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(Value::Array(keyed.into_iter().map(|(_, v)| v.clone()).collect())),
+        }
+    }
+
+    /// Group the elements of an array by the value at `path` in each element, as an array of
+    /// `(key, elements)` tuples in the order keys first appear. Keys are compared by type and
+    /// value, so `1`, `"1"` and `null` form different groups, and missing values group as
+    /// `null`.
+    pub fn group_by(&self, path: &str) -> Result<Value> {
+        let array = self.as_sequence("group")?;
+        let mut groups: IndexMap<Vec<u8>, (Value, Vec<Value>)> = IndexMap::new();
+        for v in array {
+            let key = v.get_path(path).unwrap_or(&Value::Null);
+            groups.entry(key.canonical_bytes()).or_insert_with(|| (key.clone(), vec![])).1.push(v.clone());
+        }
+        Ok(Value::Array(groups.into_values().map(|(key, group)| Value::Tuple(vec![key, Value::Array(group)])).collect()))
+    }
+
+    /// Split an array into arrays of `size` elements. The last chunk may be shorter.
+    pub fn chunk(&self, size: usize) -> Result<Value> {
+        let array = self.as_sequence("chunk")?;
+        if size == 0 {
+            Err(Error::new("cannot chunk Array into chunks of size 0"))?
+        }
+        Ok(Value::Array(array.chunks(size).map(|c| Value::Array(c.to_vec())).collect()))
+    }
+
+    /// Pair up the elements of two arrays as tuples, stopping at the shorter one.
+    pub fn zip(&self, other: &Value) -> Result<Value> {
+        let array = self.as_sequence("zip")?;
+        let other_array = match other {
+            Value::Array(a) | Value::Tuple(a) => a,
+            _ => Err(operands_error_message(self, other, "zip"))?,
+        };
+        Ok(Value::Array(array.iter().zip(other_array).map(|(a, b)| Value::Tuple(vec![a.clone(), b.clone()])).collect()))
+    }
+}
+
+fn check_hashable(values: &[Value], name: &str) -> Result<()> {
+    match values.iter().find(|v| !v.is_hashable()) {
+        Some(v) => Err(Error::new(format!("cannot {name} Array with unhashable {}", v.type_hint()))),
+        None => Ok(()),
+    }
+}
+
+// Set operations key values by their canonical bytes, which are equal for equal hashable values.
+fn keys(values: &[Value]) -> HashSet<Vec<u8>> {
+    values.iter().map(Value::canonical_bytes).collect()
+}
+
+pub(crate) fn union(lhs: &[Value], rhs: &[Value], name: &str) -> Result<Vec<Value>> {
+    check_hashable(lhs, name)?;
+    check_hashable(rhs, name)?;
+    let mut seen = HashSet::new();
+    Ok(lhs.iter().chain(rhs).filter(|v| seen.insert(v.canonical_bytes())).cloned().collect())
+}
+
+pub(crate) fn intersection(lhs: &[Value], rhs: &[Value], name: &str) -> Result<Vec<Value>> {
+    check_hashable(lhs, name)?;
+    check_hashable(rhs, name)?;
+    let rhs = keys(rhs);
+    let mut seen = HashSet::new();
+    Ok(lhs.iter().filter(|v| {
+        let key = v.canonical_bytes();
+        rhs.contains(&key) && seen.insert(key)
+    }).cloned().collect())
+}
+
+pub(crate) fn difference(lhs: &[Value], rhs: &[Value], name: &str) -> Result<Vec<Value>> {
+    check_hashable(lhs, name)?;
+    check_hashable(rhs, name)?;
+    let rhs = keys(rhs);
+    let mut seen = HashSet::new();
+    Ok(lhs.iter().filter(|v| {
+        let key = v.canonical_bytes();
+        !rhs.contains(&key) && seen.insert(key)
+    }).cloned().collect())
+}
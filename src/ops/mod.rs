@@ -0,0 +1,3 @@
+pub mod string;
+pub mod collection;
+pub mod aggregate;
@@ -0,0 +1,166 @@
+use teo_result::{Error, Result};
+use crate::value::{operand_error_message, operands_error_message, Value};
+
+/// The largest string, in bytes, built by `String * Int` repetition or by padding. Larger
+/// results are errors rather than allocation failures, which abort the process.
+pub const MAX_STRING_SIZE: usize = 64 * 1024 * 1024;
+
+pub(crate) fn size_limit_error(name: &str) -> Error {
+    Error::new(format!("cannot {name} String, the result exceeds {MAX_STRING_SIZE} bytes"))
+}
+
+/// String operations on a Teon value, created with `Value::str_ops`.
+///
+/// Every operation returns an error when the value or an argument has the wrong type.
+pub struct StrOps<'a> {
+    value: &'a Value,
+}
+
+impl Value {
+
+    pub fn str_ops(&self) -> StrOps<'_> {
+        StrOps { value: self }
+    }
+}
+
+impl<'a> StrOps<'a> {
+
+    fn string(&self, name: &str) -> Result<&'a str> {
+        self.value.as_str().ok_or_else(|| operand_error_message(self.value, name))
+    }
+
+    fn argument<'b>(&self, argument: &'b Value, name: &str) -> Result<&'b str> {
+        argument.as_str().ok_or_else(|| operands_error_message(self.value, argument, name))
+    }
+
+    /// The number of characters.
+    pub fn len(&self) -> Result<Value> {
+        Ok(Value::Int64(self.string("len")?.chars().count() as i64))
+    }
+
+    pub fn is_empty(&self) -> Result<Value> {
+        Ok(Value::Bool(self.string("is_empty")?.is_empty()))
+    }
+
+    pub fn upper(&self) -> Result<Value> {
+        Ok(Value::String(self.string("upper")?.to_uppercase()))
+    }
+
+    pub fn lower(&self) -> Result<Value> {
+        Ok(Value::String(self.string("lower")?.to_lowercase()))
+    }
+
+    pub fn trim(&self) -> Result<Value> {
+        Ok(Value::String(self.string("trim")?.trim().to_owned()))
+    }
+
+    pub fn trim_start(&self) -> Result<Value> {
+        Ok(Value::String(self.string("trim_start")?.trim_start().to_owned()))
+    }
+
+    pub fn trim_end(&self) -> Result<Value> {
+        Ok(Value::String(self.string("trim_end")?.trim_end().to_owned()))
+    }
+
+    /// Split by a string or a regex separator into an array of strings.
+    pub fn split(&self, separator: &Value) -> Result<Value> {
+        let s = self.string("split")?;
+        let parts: Vec<Value> = match separator {
+            Value::String(separator) => s.split(separator.as_str()).map(|p| Value::String(p.to_owned())).collect(),
+            Value::Regex(regex) => regex.as_regex().split(s).map(|p| Value::String(p.to_owned())).collect(),
+            _ => Err(operands_error_message(self.value, separator, "split"))?,
+        };
+        Ok(Value::Array(parts))
+    }
+
+    /// Join an array of strings with `separator`.
+    pub fn join(&self, separator: &Value) -> Result<Value> {
+        let array = self.value.as_array().ok_or_else(|| operand_error_message(self.value, "join"))?;
+        let separator = self.argument(separator, "join")?;
+        let mut parts = Vec::with_capacity(array.len());
+        for item in array {
+            parts.push(item.as_str().ok_or_else(|| operand_error_message(item, "join"))?);
+        }
+        Ok(Value::String(parts.join(separator)))
+    }
+
+    /// Whether the string contains a substring, or matches a regex.
+    pub fn contains(&self, pattern: &Value) -> Result<Value> {
+        let s = self.string("contains")?;
+        Ok(Value::Bool(match pattern {
+            Value::String(pattern) => s.contains(pattern.as_str()),
+            Value::Regex(regex) => regex.is_match(s),
+            _ => Err(operands_error_message(self.value, pattern, "contains"))?,
+        }))
+    }
+
+    pub fn starts_with(&self, prefix: &Value) -> Result<Value> {
+        let s = self.string("starts_with")?;
+        Ok(Value::Bool(s.starts_with(self.argument(prefix, "starts_with")?)))
+    }
+
+    pub fn ends_with(&self, suffix: &Value) -> Result<Value> {
+        let s = self.string("ends_with")?;
+        Ok(Value::Bool(s.ends_with(self.argument(suffix, "ends_with")?)))
+    }
+
+    /// Replace every occurrence of a string, or every match of a regex. Regex replacements may
+    /// refer to capture groups with `$1` or `$name`.
+    pub fn replace(&self, pattern: &Value, replacement: &Value) -> Result<Value> {
+        let s = self.string("replace")?;
+        let replacement = self.argument(replacement, "replace")?;
+        Ok(match pattern {
+            Value::String(pattern) => Value::String(s.replace(pattern.as_str(), replacement)),
+            Value::Regex(regex) => regex.replace(s, replacement),
+            _ => Err(operands_error_message(self.value, pattern, "replace"))?,
+        })
+    }
+
+    /// Pad at the start with `fill` until the string is `width` characters long.
+    pub fn pad_start(&self, width: &Value, fill: &Value) -> Result<Value> {
+        let (s, padding) = self.padding(width, fill, "pad_start")?;
+        Ok(Value::String(padding + s))
+    }
+
+    /// Pad at the end with `fill` until the string is `width` characters long.
+    pub fn pad_end(&self, width: &Value, fill: &Value) -> Result<Value> {
+        let (s, padding) = self.padding(width, fill, "pad_end")?;
+        Ok(Value::String(s.to_owned() + &padding))
+    }
+
+    /// Pad on both sides with `fill` until the string is `width` characters long. When the
+    /// padding cannot be split evenly, the extra character goes to the end.
+    pub fn pad(&self, width: &Value, fill: &Value) -> Result<Value> {
+        let (s, padding) = self.padding(width, fill, "pad")?;
+        let count = padding.chars().count();
+        let start: String = padding.chars().take(count / 2).collect();
+        let end: String = padding.chars().take(count - count / 2).collect();
+        Ok(Value::String(start + s + &end))
+    }
+
+    fn padding(&self, width: &Value, fill: &Value, name: &str) -> Result<(&'a str, String)> {
+        let s = self.string(name)?;
+        let width = match width.to_int64() {
+            Some(width) if width >= 0 => width as u64,
+            _ => Err(operands_error_message(self.value, width, name))?,
+        };
+        let fill = self.argument(fill, name)?;
+        // each padding character takes at most as many bytes as the longest fill character
+        let char_size = fill.chars().map(char::len_utf8).max().unwrap_or(1) as u64;
+        if width.saturating_mul(char_size).saturating_add(s.len() as u64) > MAX_STRING_SIZE as u64 {
+            Err(size_limit_error(name))?
+        }
+        let width = width as usize;
+        if fill.is_empty() {
+            Err(Error::new(format!("cannot {name} String with empty fill")))?
+        }
+        let len = s.chars().count();
+        let padding = if width > len {
+            fill.chars().cycle().take(width - len).collect()
+        } else {
+            String::new()
+        };
+        Ok((s, padding))
+    }
+}
+
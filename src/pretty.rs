@@ -0,0 +1,114 @@
+use crate::utils::literal::string_literal;
+use crate::value::Value;
+
+/// Options for printing Teon text over multiple lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// Number of spaces per indentation level.
+    pub indent: usize,
+    /// Arrays, tuples and dictionaries which fit in this many columns stay on one line. Use `0`
+    /// to always break non-empty collections.
+    pub max_width: usize,
+    /// Whether to write a comma after the last item of a broken collection.
+    pub trailing_commas: bool,
+    /// Whether to write dictionary keys in sorted order instead of insertion order.
+    pub sort_keys: bool,
+}
+
+impl Default for PrettyOptions {
+
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            max_width: 80,
+            trailing_commas: false,
+            sort_keys: false,
+        }
+    }
+}
+
+impl Value {
+
+    /// Print the value as Teon text, breaking collections which don't fit in
+    /// `options.max_width` over multiple lines. `format!("{:#}", value)` prints with the default
+    /// options.
+    pub fn to_pretty_string(&self, options: PrettyOptions) -> String {
+        let mut output = String::new();
+        write_pretty(self, &options, 0, 0, &mut output);
+        output
+    }
+}
+
+type Entries<'a> = Vec<(Option<&'a String>, &'a Value)>;
+
+fn entries<'a>(value: &'a Value, options: &PrettyOptions) -> Option<(&'static str, &'static str, Entries<'a>)> {
+    match value {
+        Value::Array(items) => Some(("[", "]", items.iter().map(|v| (None, v)).collect())),
+        Value::Tuple(items) => Some(("(", ")", items.iter().map(|v| (None, v)).collect())),
+        Value::Dictionary(map) => {
+            let mut entries: Vec<(Option<&String>, &Value)> = map.iter().map(|(k, v)| (Some(k), v)).collect();
+            if options.sort_keys {
+                entries.sort_by_key(|(key, _)| *key);
+            }
+            Some(("{", "}", entries))
+        }
+        _ => None,
+    }
+}
+
+fn write_key(key: &str, output: &mut String) {
+    output.push_str(&string_literal(key));
+    output.push_str(": ");
+}
+
+fn write_flat(value: &Value, options: &PrettyOptions, output: &mut String) {
+    match entries(value, options) {
+        Some((open, close, entries)) => {
+            output.push_str(open);
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i != 0 {
+                    output.push_str(", ");
+                }
+                if let Some(key) = key {
+                    write_key(key, output);
+                }
+                write_flat(value, options, output);
+            }
+            if value.is_tuple() && entries.len() == 1 {
+                output.push(',');
+            }
+            output.push_str(close);
+        }
+        None => output.push_str(&value.to_string()),
+    }
+}
+
+fn write_pretty(value: &Value, options: &PrettyOptions, level: usize, column: usize, output: &mut String) {
+    let Some((open, close, entries)) = entries(value, options) else {
+        return output.push_str(&value.to_string());
+    };
+    let mut flat = String::new();
+    write_flat(value, options, &mut flat);
+    // leave room for a comma following the value
+    if entries.is_empty() || (column + flat.chars().count() < options.max_width && !flat.contains('\n')) {
+        return output.push_str(&flat);
+    }
+    let indent = " ".repeat(options.indent * (level + 1));
+    output.push_str(open);
+    output.push('\n');
+    for (i, (key, item)) in entries.iter().enumerate() {
+        let start = output.len();
+        output.push_str(&indent);
+        if let Some(key) = key {
+            write_key(key, output);
+        }
+        let column = output[start..].chars().count();
+        write_pretty(item, options, level + 1, column, output);
+        if i != entries.len() - 1 || options.trailing_commas || (value.is_tuple() && entries.len() == 1) {
+            output.push(',');
+        }
+        output.push('\n');
+    }
+    output.push_str(&" ".repeat(options.indent * level));
+    output.push_str(close);
+}
@@ -0,0 +1,233 @@
+//! Applying Teo's find arguments to records in memory.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::query::query;
+//! #
+//! let records = vec![teon!({"id": 1, "age": 30}), teon!({"id": 2, "age": 20}), teon!({"id": 3, "age": 25})];
+//! let args = teon!({"where": {"age": {"gte": 21}}, "orderBy": {"age": "asc"}, "select": {"id": true}});
+//! assert_eq!(query(records, &args).unwrap(), vec![teon!({"id": 3}), teon!({"id": 1})]);
+//! ```
+
+use std::cmp::Ordering;
+use indexmap::IndexMap;
+use teo_result::{Error, Result};
+use crate::filter::matches;
+use crate::ops::aggregate::compare;
+use crate::value::{operands_error_message, Value};
+
+/// Filter, order, paginate and project `records` with a dictionary of find arguments: `where`,
+/// `orderBy`, `cursor`, `skip`, `take`, and `select` or `omit`. Each argument is optional.
+pub fn query(records: Vec<Value>, args: &Value) -> Result<Vec<Value>> {
+    let args = match args {
+        Value::Null => return Ok(records),
+        Value::Dictionary(args) => args,
+        _ => Err(Error::new(format!("cannot query with {}, expect Dictionary", args.type_hint())))?,
+    };
+    for key in args.keys() {
+        if !["where", "orderBy", "cursor", "skip", "take", "select", "omit"].contains(&key.as_str()) {
+            Err(Error::new(format!("unknown query argument `{}`", key)))?;
+        }
+    }
+    let mut records = match args.get("where") {
+        Some(filter) => {
+            let mut filtered = vec![];
+            for record in records {
+                if matches(&record, filter)? {
+                    filtered.push(record);
+                }
+            }
+            filtered
+        }
+        None => records,
+    };
+    if let Some(order) = args.get("orderBy") {
+        order_by(&mut records, order)?;
+    }
+    let records = paginate(records, non_null(args.get("cursor")), int_argument(args, "skip")?, int_argument(args, "take")?)?;
+    match (non_null(args.get("select")), non_null(args.get("omit"))) {
+        (Some(_), Some(_)) => Err(Error::new("cannot query with both `select` and `omit`")),
+        (Some(projection), None) => records.iter().map(|r| select(r, projection)).collect(),
+        (None, Some(projection)) => records.iter().map(|r| omit(r, projection)).collect(),
+        (None, None) => Ok(records),
+    }
+}
+
+fn non_null(value: Option<&Value>) -> Option<&Value> {
+    value.filter(|v| !v.is_null())
+}
+
+fn int_argument(args: &IndexMap<String, Value>, name: &str) -> Result<Option<i64>> {
+    match non_null(args.get(name)) {
+        None => Ok(None),
+        Some(value) if value.is_any_int() => Ok(value.to_int64()),
+        Some(value) => Err(Error::new(format!("cannot query with `{}` {}, expect Int", name, value.type_hint()))),
+    }
+}
+
+struct SortKey {
+    path: Vec<String>,
+    descending: bool,
+    nulls_first: bool,
+}
+
+/// Stably sort `records` by an `orderBy` argument, a dictionary or an array of dictionaries.
+/// A direction is `"asc"` or `"desc"`, or `{"sort": .., "nulls": "first" | "last"}`, and a nested
+/// dictionary orders by a field of a nested dictionary. Nulls sort first in ascending order and
+/// last in descending order unless specified.
+pub fn order_by(records: &mut [Value], order: &Value) -> Result<()> {
+    let mut keys = vec![];
+    match order {
+        Value::Array(orders) => for order in orders {
+            collect_sort_keys(order, vec![], &mut keys)?;
+        },
+        _ => collect_sort_keys(order, vec![], &mut keys)?,
+    }
+    let mut error = None;
+    records.sort_by(|a, b| {
+        for key in &keys {
+            let lhs = value_at(a, &key.path);
+            let rhs = value_at(b, &key.path);
+            let ordering = match (lhs.is_null(), rhs.is_null()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => if key.nulls_first { Ordering::Less } else { Ordering::Greater },
+                (false, true) => if key.nulls_first { Ordering::Greater } else { Ordering::Less },
+                (false, false) => {
+                    let ordering = compare(lhs, rhs).unwrap_or_else(|| {
+                        error.get_or_insert_with(|| operands_error_message(lhs, rhs, "compare"));
+                        Ordering::Equal
+                    });
+                    if key.descending { ordering.reverse() } else { ordering }
+                }
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+fn collect_sort_keys(order: &Value, path: Vec<String>, keys: &mut Vec<SortKey>) -> Result<()> {
+    let order = order.as_dictionary().ok_or_else(|| Error::new(format!("cannot order by {}, expect Dictionary", order.type_hint())))?;
+    for (field, direction) in order {
+        let mut path = path.clone();
+        path.push(field.clone());
+        match direction {
+            Value::Dictionary(map) if map.contains_key("sort") => {
+                let descending = parse_direction(map.get("sort").unwrap())?;
+                let nulls_first = match map.get("nulls") {
+                    None | Some(Value::Null) => !descending,
+                    Some(nulls) => match enum_like(nulls) {
+                        Some("first") => true,
+                        Some("last") => false,
+                        _ => Err(Error::new(format!("invalid nulls order {}", nulls)))?,
+                    },
+                };
+                keys.push(SortKey { path, descending, nulls_first });
+            }
+            Value::Dictionary(_) => collect_sort_keys(direction, path, keys)?,
+            _ => {
+                let descending = parse_direction(direction)?;
+                keys.push(SortKey { path, descending, nulls_first: !descending });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn enum_like(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s.as_str()),
+        Value::EnumVariant(e) => Some(e.value.as_str()),
+        _ => None,
+    }
+}
+
+fn parse_direction(direction: &Value) -> Result<bool> {
+    match enum_like(direction) {
+        Some("asc") => Ok(false),
+        Some("desc") => Ok(true),
+        _ => Err(Error::new(format!("invalid sort order {}", direction))),
+    }
+}
+
+fn value_at<'a>(record: &'a Value, path: &[String]) -> &'a Value {
+    let mut current = record;
+    for key in path {
+        match current.get(key.as_str()) {
+            Some(value) => current = value,
+            None => return &Value::Null,
+        }
+    }
+    current
+}
+
+/// Apply cursor pagination, then `skip` and `take`, to ordered records. The cursor is a
+/// dictionary of field values identifying the first record to include; when no record matches,
+/// the result is empty. Negative `skip` and `take` are errors.
+pub fn paginate(records: Vec<Value>, cursor: Option<&Value>, skip: Option<i64>, take: Option<i64>) -> Result<Vec<Value>> {
+    if skip.is_some_and(|skip| skip < 0) {
+        Err(Error::new("cannot query with negative `skip`"))?;
+    }
+    if take.is_some_and(|take| take < 0) {
+        Err(Error::new("cannot query with negative `take`"))?;
+    }
+    let start = match cursor {
+        Some(cursor) => {
+            let fields = cursor.as_dictionary().ok_or_else(|| Error::new(format!("cannot query with cursor {}, expect Dictionary", cursor.type_hint())))?;
+            match records.iter().position(|record| fields.iter().all(|(k, v)| record.get(k.as_str()).unwrap_or(&Value::Null) == v)) {
+                Some(index) => index,
+                None => return Ok(vec![]),
+            }
+        }
+        None => 0,
+    };
+    let skip = skip.map_or(0, |skip| usize::try_from(skip).unwrap_or(usize::MAX));
+    let take = take.map_or(usize::MAX, |take| usize::try_from(take).unwrap_or(usize::MAX));
+    Ok(records.into_iter().skip(start.saturating_add(skip)).take(take).collect())
+}
+
+/// Project a record to the fields set to `true` in `projection`. A nested dictionary, optionally
+/// wrapped in `{"select": ..}`, projects a nested dictionary or each dictionary in a nested array.
+pub fn select(record: &Value, projection: &Value) -> Result<Value> {
+    project(record, projection, true)
+}
+
+/// Remove the fields set to `true` in `projection` from a record. Nested dictionaries, optionally
+/// wrapped in `{"omit": ..}`, omit fields of nested dictionaries or of each dictionary in an array.
+pub fn omit(record: &Value, projection: &Value) -> Result<Value> {
+    project(record, projection, false)
+}
+
+fn project(record: &Value, projection: &Value, keep: bool) -> Result<Value> {
+    let name = if keep { "select" } else { "omit" };
+    let projection = projection.as_dictionary().ok_or_else(|| Error::new(format!("cannot {} with {}, expect Dictionary", name, projection.type_hint())))?;
+    let record = match record {
+        Value::Dictionary(record) => record,
+        Value::Array(records) => return Ok(Value::Array(records.iter().map(|r| project(r, &Value::Dictionary(projection.clone()), keep)).collect::<Result<_>>()?)),
+        Value::Null => return Ok(Value::Null),
+        _ => Err(Error::new(format!("cannot {} fields of {}", name, record.type_hint())))?,
+    };
+    let mut result = IndexMap::new();
+    for (key, value) in record {
+        match projection.get(key) {
+            Some(Value::Bool(flag)) => if *flag == keep {
+                result.insert(key.clone(), value.clone());
+            },
+            Some(Value::Dictionary(nested)) => {
+                let nested = nested.get(name).filter(|v| nested.len() == 1 && v.is_dictionary()).unwrap_or(projection.get(key).unwrap());
+                result.insert(key.clone(), project(value, nested, keep)?);
+            }
+            Some(Value::Null) | None => if !keep {
+                result.insert(key.clone(), value.clone());
+            },
+            Some(flag) => Err(Error::new(format!("cannot {} `{}` with {}, expect Bool or Dictionary", name, key, flag.type_hint())))?,
+        }
+    }
+    Ok(Value::Dictionary(result))
+}
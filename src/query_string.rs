@@ -0,0 +1,294 @@
+//! Query string and `application/x-www-form-urlencoded` decoding and encoding.
+//!
+//! Keys use bracket notation for nesting: `a[b]=1` is a dictionary, and `a[]=1`, `a[0]=1` and
+//! repeated keys `a=1&a=2` are arrays. Decoded values are always strings.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::Value;
+//! # use teo_teon::query_string::QueryStringOptions;
+//! #
+//! let value = Value::from_query_string("a[b][0]=1&a[c]=x%20y", &QueryStringOptions::default()).unwrap();
+//! assert_eq!(value, teon!({"a": {"b": ["1"], "c": "x y"}}));
+//! assert_eq!(value.to_query_string(&QueryStringOptions::default()).unwrap(), "a[b][0]=1&a[c]=x%20y");
+//! ```
+
+use std::collections::BTreeMap;
+use chrono::SecondsFormat;
+use indexmap::IndexMap;
+use teo_result::{Error, Result};
+use crate::utils::percent::{percent_decode, percent_encode};
+use crate::value::Value;
+
+/// How arrays are written in query strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayFormat {
+    /// `a[0]=x&a[1]=y`
+    #[default]
+    Indices,
+    /// `a[]=x&a[]=y`
+    Brackets,
+    /// `a=x&a=y`
+    Repeat,
+    /// `a=x,y`
+    Comma,
+}
+
+/// Options for [`Value::from_query_string`] and [`Value::to_query_string`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryStringOptions {
+    /// How arrays are written. Indices, brackets and repeated keys are always read as arrays.
+    /// With `Comma`, values containing commas are also read as arrays.
+    pub array_format: ArrayFormat,
+    /// The maximum number of bracket segments read from a key. The rest of a deeper key is kept
+    /// as a single literal key, so `a[b][c]` with depth 1 is `{"a": {"b": {"[c]": ..}}}`.
+    pub depth: usize,
+    /// The largest index read as an array index. Larger indices are read as dictionary keys, so
+    /// `a[1000000]=x` doesn't allocate a huge array.
+    pub array_limit: usize,
+}
+
+impl Default for QueryStringOptions {
+
+    fn default() -> Self {
+        Self {
+            array_format: ArrayFormat::Indices,
+            depth: 5,
+            array_limit: 20,
+        }
+    }
+}
+
+impl Value {
+
+    /// Decode a query string or form body into a dictionary. A leading `?` is ignored, `+` is
+    /// read as a space, and sparse array indices are compacted in order.
+    pub fn from_query_string(text: &str, options: &QueryStringOptions) -> Result<Value> {
+        let mut builder = Builder::new(options);
+        for pair in text.strip_prefix('?').unwrap_or(text).split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let key = decode(key)?;
+            let value = match options.array_format {
+                ArrayFormat::Comma if value.contains(',') => {
+                    let mut items = BTreeMap::new();
+                    for (i, item) in value.split(',').enumerate() {
+                        items.insert(i, Node::Leaf(Value::String(decode(item)?)));
+                    }
+                    Node::Array(items)
+                }
+                _ => Node::Leaf(Value::String(decode(value)?)),
+            };
+            builder.insert_node(&key, value)?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Encode a dictionary as a query string. Nested dictionaries use bracket notation, and
+    /// arrays use `options.array_format`, except that arrays and dictionaries inside arrays are
+    /// always written with indices. A single element array written with `Comma` reads back as
+    /// a plain value. Keys and values are percent-encoded; structural brackets are not.
+    pub fn to_query_string(&self, options: &QueryStringOptions) -> Result<String> {
+        let map = self.as_dictionary().ok_or_else(|| Error::new(format!("Cannot convert {} into query string, expect Dictionary", self.type_hint())))?;
+        let mut pairs = vec![];
+        for (key, value) in map {
+            write(value, percent_encode(key), options, &mut pairs)?;
+        }
+        Ok(pairs.join("&"))
+    }
+}
+
+// Builds a dictionary from keys in bracket notation. Multipart decoding shares it.
+pub(crate) struct Builder<'a> {
+    root: Node,
+    options: &'a QueryStringOptions,
+}
+
+impl<'a> Builder<'a> {
+
+    pub(crate) fn new(options: &'a QueryStringOptions) -> Self {
+        Self { root: Node::Dictionary(IndexMap::new()), options }
+    }
+
+    pub(crate) fn insert(&mut self, key: &str, value: Value) -> Result<()> {
+        self.insert_node(key, Node::Leaf(value))
+    }
+
+    fn insert_node(&mut self, key: &str, value: Node) -> Result<()> {
+        assign(&mut self.root, key, &parse_key(key, self.options.depth), value, self.options)
+    }
+
+    pub(crate) fn finish(self) -> Value {
+        self.root.into_value()
+    }
+}
+
+enum Node {
+    Missing,
+    Leaf(Value),
+    Array(BTreeMap<usize, Node>),
+    Dictionary(IndexMap<String, Node>),
+}
+
+impl Node {
+
+    fn into_value(self) -> Value {
+        match self {
+            Node::Missing => Value::Null,
+            Node::Leaf(value) => value,
+            Node::Array(items) => Value::Array(items.into_values().map(Node::into_value).collect()),
+            Node::Dictionary(map) => Value::Dictionary(map.into_iter().map(|(k, v)| (k, v.into_value())).collect()),
+        }
+    }
+}
+
+enum Segment<'a> {
+    Push,
+    Index(usize),
+    Key(&'a str),
+}
+
+fn segment<'a>(segment: &'a str, options: &QueryStringOptions) -> Segment<'a> {
+    if segment.is_empty() {
+        return Segment::Push;
+    }
+    let canonical = segment.bytes().all(|b| b.is_ascii_digit()) && (segment == "0" || !segment.starts_with('0'));
+    match segment.parse::<usize>() {
+        Ok(index) if canonical && index <= options.array_limit => Segment::Index(index),
+        _ => Segment::Key(segment),
+    }
+}
+
+// Splits `a[b][c]` into `a`, `b` and `c`, keeping whatever follows `depth` segments as one key.
+fn parse_key(key: &str, depth: usize) -> Vec<String> {
+    let Some(open) = key.find('[').filter(|i| *i > 0 && key[*i..].contains(']')) else {
+        return vec![key.to_owned()];
+    };
+    let mut segments = vec![key[..open].to_owned()];
+    let mut rest = &key[open..];
+    while segments.len() <= depth {
+        let Some(close) = rest.strip_prefix('[').and_then(|r| r.find(']')) else {
+            break;
+        };
+        segments.push(rest[1..close + 1].to_owned());
+        rest = &rest[close + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(rest.to_owned());
+    }
+    segments
+}
+
+fn next_index(items: &BTreeMap<usize, Node>) -> usize {
+    items.keys().next_back().map_or(0, |i| i + 1)
+}
+
+fn append(items: &mut BTreeMap<usize, Node>, value: Node) {
+    match value {
+        Node::Array(values) => for value in values.into_values() {
+            items.insert(next_index(items), value);
+        },
+        value => {
+            items.insert(next_index(items), value);
+        }
+    }
+}
+
+fn conflict(key: &str) -> Error {
+    Error::new(format!("query string error: conflicting values for `{}`", key))
+}
+
+fn assign(node: &mut Node, key: &str, segments: &[String], value: Node, options: &QueryStringOptions) -> Result<()> {
+    let Some((first, rest)) = segments.split_first() else {
+        *node = match std::mem::replace(node, Node::Missing) {
+            Node::Missing => value,
+            Node::Leaf(existing) => {
+                let mut items = BTreeMap::from([(0, Node::Leaf(existing))]);
+                append(&mut items, value);
+                Node::Array(items)
+            }
+            Node::Array(mut items) => {
+                append(&mut items, value);
+                Node::Array(items)
+            }
+            Node::Dictionary(_) => return Err(conflict(key)),
+        };
+        return Ok(());
+    };
+    let segment = segment(first, options);
+    *node = match (std::mem::replace(node, Node::Missing), &segment) {
+        (Node::Missing, Segment::Key(_)) => Node::Dictionary(IndexMap::new()),
+        (Node::Missing, _) => Node::Array(BTreeMap::new()),
+        (Node::Leaf(_), Segment::Key(_)) => return Err(conflict(key)),
+        (Node::Leaf(existing), _) => Node::Array(BTreeMap::from([(0, Node::Leaf(existing))])),
+        (Node::Array(items), Segment::Key(_)) => Node::Dictionary(items.into_iter().map(|(i, v)| (i.to_string(), v)).collect()),
+        (node, _) => node,
+    };
+    let child = match node {
+        Node::Array(items) => {
+            let index = match segment {
+                Segment::Index(index) => index,
+                _ => next_index(items),
+            };
+            items.entry(index).or_insert(Node::Missing)
+        }
+        Node::Dictionary(map) => {
+            let name = match segment {
+                Segment::Key(name) => name.to_owned(),
+                Segment::Index(index) => index.to_string(),
+                Segment::Push => map.len().to_string(),
+            };
+            map.entry(name).or_insert(Node::Missing)
+        }
+        _ => unreachable!(),
+    };
+    assign(child, key, rest, value, options)
+}
+
+fn decode(text: &str) -> Result<String> {
+    percent_decode(text, true).ok_or_else(|| Error::new(format!("query string error: invalid UTF-8 in `{}`", text)))
+}
+
+fn scalar(value: &Value, key: &str) -> Result<String> {
+    Ok(match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Int64(i) => i.to_string(),
+        Value::Float32(f) => f.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Decimal(d) => d.normalized().to_string(),
+        Value::ObjectId(o) => o.to_hex(),
+        Value::String(s) => s.clone(),
+        Value::Date(d) => d.format("%Y-%m-%d").to_string(),
+        Value::DateTime(d) => d.to_rfc3339_opts(SecondsFormat::Millis, true),
+        Value::EnumVariant(e) => e.value.clone(),
+        Value::OptionVariant(o) => o.value.to_string(),
+        _ => Err(Error::new(format!("Cannot convert {} at `{}` into query string", value.type_hint(), key)))?,
+    })
+}
+
+fn write(value: &Value, key: String, options: &QueryStringOptions, pairs: &mut Vec<String>) -> Result<()> {
+    match value {
+        Value::Dictionary(map) => for (k, v) in map {
+            write(v, format!("{}[{}]", key, percent_encode(k)), options, pairs)?;
+        },
+        Value::Array(items) if options.array_format == ArrayFormat::Comma && !items.is_empty() && items.iter().all(|v| !v.is_array() && !v.is_dictionary()) => {
+            let mut values = vec![];
+            for item in items {
+                values.push(percent_encode(&scalar(item, &key)?));
+            }
+            pairs.push(format!("{}={}", key, values.join(",")));
+        }
+        Value::Array(items) => for (i, item) in items.iter().enumerate() {
+            let key = match options.array_format {
+                _ if item.is_array() || item.is_dictionary() => format!("{}[{}]", key, i),
+                ArrayFormat::Indices | ArrayFormat::Comma => format!("{}[{}]", key, i),
+                ArrayFormat::Brackets => format!("{}[]", key),
+                ArrayFormat::Repeat => key.clone(),
+            };
+            write(item, key, options, pairs)?;
+        },
+        _ => pairs.push(format!("{}={}", key, percent_encode(&scalar(value, &key)?))),
+    }
+    Ok(())
+}
@@ -0,0 +1,356 @@
+//! Redaction of sensitive values for logging.
+//!
+//! [`RedactionRules`] match dictionary keys by name or glob, values by their [`Path`], and
+//! substrings of strings, keys and file names by regular expressions. Matched values are masked,
+//! partially masked or hashed with a secret key. [`Value::redact`] returns a redacted copy, and
+//! [`Value::redacted`] wraps a value so that it's redacted whenever it's formatted.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::redact::{Redaction, RedactionRules};
+//! # use teo_teon::types::regex::TeonRegex;
+//! #
+//! let rules = RedactionRules::new()
+//!     .key("password", Redaction::Mask)
+//!     .key("*token*", Redaction::Mask)
+//!     .path("$.card.number", Redaction::Partial(4))
+//!     .pattern(TeonRegex::new(r"[\w.]+@[\w.]+", "").unwrap(), Redaction::Mask);
+//! let input = teon!({
+//!     "user": "ann",
+//!     "Password": "hunter2",
+//!     "refresh_token": "abc",
+//!     "card": {"number": "4111-1111-1111-1234"},
+//!     "note": "mail ann@example.com",
+//! });
+//! assert_eq!(input.redact(&rules), teon!({
+//!     "user": "ann",
+//!     "Password": "[REDACTED]",
+//!     "refresh_token": "[REDACTED]",
+//!     "card": {"number": "****-****-****-1234"},
+//!     "note": "mail [REDACTED]",
+//! }));
+//! assert!(!input.redacted(&rules).to_string().contains("hunter2"));
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::mem;
+use std::sync::Arc;
+use teo_result::{Error, Result};
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::regex::TeonRegex;
+use crate::utils::sha256::{hmac_sha256, to_hex};
+use crate::value::Value;
+use crate::visit::{Path, VisitorMut, Walk};
+
+/// How a matched value is replaced. The replacement is always a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Redaction {
+    /// Replace with [`RedactionRules::mask`].
+    Mask,
+    /// Replace letters and digits with `*` except the last n of them, keeping separators, so
+    /// `4111-1111-1111-1234` becomes `****-****-****-1234`. Values with at most n letters and
+    /// digits are masked entirely.
+    Partial(usize),
+    /// Replace with `hmac-sha256:` and the hex HMAC-SHA256 of the string, or of the canonical
+    /// bytes of other values, under a secret key.
+    ///
+    /// This is pseudonymization, not anonymization: equal values hash equally so that they can
+    /// be correlated across log lines, and anyone holding the key can confirm a guessed value.
+    Hash(HashKey),
+}
+
+/// The secret key of [`Redaction::Hash`]. Its `Debug` output doesn't show the key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HashKey(Arc<[u8]>);
+
+impl HashKey {
+
+    /// A key, which can't be empty. Use a random secret of at least 32 bytes which isn't stored
+    /// with the logs, otherwise values with few possibilities, like phone numbers, can be
+    /// recovered by hashing every candidate.
+    pub fn new(key: impl AsRef<[u8]>) -> Result<Self> {
+        let key = key.as_ref();
+        if key.is_empty() {
+            Err(Error::new("cannot hash redacted values with an empty key"))?
+        }
+        Ok(Self(Arc::from(key)))
+    }
+}
+
+impl Debug for HashKey {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HashKey(..)")
+    }
+}
+
+/// What a rule matches.
+#[derive(Debug, Clone)]
+pub enum RedactionMatcher {
+    /// Dictionary values whose key matches a glob, ignoring case.
+    Key(String),
+    /// Values whose path, as printed by [`Path`], matches a glob.
+    Path(String),
+    /// The substrings of strings which match a regular expression.
+    Pattern(TeonRegex),
+}
+
+#[derive(Debug, Clone)]
+pub struct RedactionRule {
+    pub matcher: RedactionMatcher,
+    pub redaction: Redaction,
+}
+
+/// Rules for [`Value::redact`].
+///
+/// Globs match `*` to any characters and `?` to one character. In path globs these don't cross
+/// `.`, `[` and `]`, and `**` matches any characters, so `$.users[*].ssn` matches the `ssn` of
+/// each user and `$.**.ssn` any `ssn` below the root.
+///
+/// Key and path rules replace the whole value, including nested values, and the first matching
+/// rule applies. Pattern rules then apply in order to the remaining strings, to dictionary keys
+/// and enum variant argument names, and to the names and paths of files. Keys which become equal
+/// to another key of the same dictionary are numbered, as in `[REDACTED] (2)`.
+#[derive(Debug, Clone)]
+pub struct RedactionRules {
+    pub rules: Vec<RedactionRule>,
+    /// The replacement for [`Redaction::Mask`], `[REDACTED]` by default.
+    pub mask: String,
+}
+
+impl Default for RedactionRules {
+
+    fn default() -> Self {
+        Self {
+            rules: vec![],
+            mask: "[REDACTED]".to_owned(),
+        }
+    }
+}
+
+impl RedactionRules {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules masking keys commonly holding credentials: passwords, secrets, tokens, API keys,
+    /// authorization headers and cookies.
+    pub fn common() -> Self {
+        ["*password*", "*passwd*", "*secret*", "*token*", "*api_key*", "*apikey*", "authorization", "cookie", "set-cookie"]
+            .into_iter()
+            .fold(Self::new(), |rules, key| rules.key(key, Redaction::Mask))
+    }
+
+    /// Redact dictionary values whose key matches a name or glob, ignoring case.
+    pub fn key(mut self, glob: impl Into<String>, redaction: Redaction) -> Self {
+        self.rules.push(RedactionRule { matcher: RedactionMatcher::Key(glob.into()), redaction });
+        self
+    }
+
+    /// Redact values whose path matches a glob like `$.card.number` or `$.users[*].ssn`.
+    pub fn path(mut self, glob: impl Into<String>, redaction: Redaction) -> Self {
+        self.rules.push(RedactionRule { matcher: RedactionMatcher::Path(glob.into()), redaction });
+        self
+    }
+
+    /// Redact the substrings of strings which match a regular expression.
+    pub fn pattern(mut self, regex: TeonRegex, redaction: Redaction) -> Self {
+        self.rules.push(RedactionRule { matcher: RedactionMatcher::Pattern(regex), redaction });
+        self
+    }
+
+    pub fn mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    // The redaction of the first key or path rule matching `path`.
+    fn matching(&self, path: &Path) -> Option<&Redaction> {
+        let mut printed = None;
+        self.rules.iter().find(|rule| match &rule.matcher {
+            RedactionMatcher::Key(glob) => path.key().is_some_and(|key| {
+                let glob: Vec<char> = glob.to_lowercase().chars().collect();
+                let key: Vec<char> = key.to_lowercase().chars().collect();
+                glob_match(&glob, &key, &[])
+            }),
+            RedactionMatcher::Path(glob) => {
+                let printed = printed.get_or_insert_with(|| path.to_string().chars().collect::<Vec<char>>());
+                glob_match(&glob.chars().collect::<Vec<char>>(), printed, &['.', '[', ']'])
+            }
+            RedactionMatcher::Pattern(_) => false,
+        }).map(|rule| &rule.redaction)
+    }
+
+    fn apply(&self, redaction: &Redaction, value: &Value) -> String {
+        match (redaction, value) {
+            (Redaction::Mask, _) => self.mask.clone(),
+            (Redaction::Partial(visible), Value::String(s)) => partial(s, *visible),
+            (Redaction::Partial(visible), Value::Int(_) | Value::Int64(_) | Value::Decimal(_)) => partial(&value.to_string(), *visible),
+            (Redaction::Partial(_), _) => self.mask.clone(),
+            (Redaction::Hash(key), Value::String(s)) => hash(key, s.as_bytes()),
+            (Redaction::Hash(key), value) => hash(key, &value.canonical_bytes()),
+        }
+    }
+
+    fn has_pattern_match(&self, text: &str) -> bool {
+        self.rules.iter().any(|rule| matches!(&rule.matcher, RedactionMatcher::Pattern(regex) if regex.is_match(text)))
+    }
+
+    // Applies the pattern rules to a string.
+    fn redact_patterns(&self, text: &mut String) {
+        for rule in &self.rules {
+            if let RedactionMatcher::Pattern(regex) = &rule.matcher {
+                if regex.is_match(text) {
+                    let replaced = regex.as_regex().replace_all(text, |captures: &regex::Captures| {
+                        self.apply(&rule.redaction, &Value::String(captures[0].to_owned()))
+                    });
+                    *text = replaced.into_owned();
+                }
+            }
+        }
+    }
+
+    // Applies the pattern rules to keys. A redacted key which is taken by another key is numbered,
+    // and keys which aren't redacted keep their names.
+    fn redact_keys(&self, entries: Vec<(String, Value)>) -> Vec<(String, Value)> {
+        let mut taken: HashSet<String> = entries.iter().map(|(key, _)| key).filter(|key| !self.has_pattern_match(key)).cloned().collect();
+        entries.into_iter().map(|(key, item)| {
+            if !self.has_pattern_match(&key) {
+                return (key, item);
+            }
+            let mut redacted = key;
+            self.redact_patterns(&mut redacted);
+            let mut numbered = redacted.clone();
+            let mut number = 2;
+            while taken.contains(&numbered) {
+                numbered = format!("{} ({})", redacted, number);
+                number += 1;
+            }
+            taken.insert(numbered.clone());
+            (numbered, item)
+        }).collect()
+    }
+}
+
+fn hash(key: &HashKey, bytes: &[u8]) -> String {
+    format!("hmac-sha256:{}", to_hex(&hmac_sha256(&key.0, bytes)))
+}
+
+fn partial(text: &str, visible: usize) -> String {
+    let total = text.chars().filter(|c| c.is_alphanumeric()).count();
+    let hidden = if total > visible { total - visible } else { total };
+    let mut seen = 0;
+    text.chars().map(|c| {
+        if !c.is_alphanumeric() {
+            return c;
+        }
+        seen += 1;
+        if seen <= hidden { '*' } else { c }
+    }).collect()
+}
+
+fn glob_match(pattern: &[char], text: &[char], separators: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_match(rest, &text[i..], separators)),
+        ['*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..], separators) {
+                    return true;
+                }
+                if i < text.len() && separators.contains(&text[i]) {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => text.first().is_some_and(|c| !separators.contains(c)) && glob_match(rest, &text[1..], separators),
+        [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..], separators),
+    }
+}
+
+struct Redactor<'a> {
+    rules: &'a RedactionRules,
+}
+
+impl VisitorMut for Redactor<'_> {
+
+    fn enter(&mut self, path: &Path, value: &mut Value) -> Walk {
+        match self.rules.matching(path) {
+            Some(redaction) => {
+                *value = Value::String(self.rules.apply(redaction, value));
+                Walk::Skip
+            }
+            None => Walk::Continue,
+        }
+    }
+
+    // Keys are redacted after their values, which key and path rules match by the original keys.
+    fn leave(&mut self, _path: &Path, value: &mut Value) {
+        match value {
+            Value::Dictionary(map) if map.keys().any(|key| self.rules.has_pattern_match(key)) => {
+                *map = self.rules.redact_keys(mem::take(map).into_iter().collect()).into_iter().collect();
+            }
+            Value::EnumVariant(EnumVariant { args: Some(args), .. }) if args.keys().any(|key| self.rules.has_pattern_match(key)) => {
+                *args = self.rules.redact_keys(mem::take(args).into_iter().collect()).into_iter().collect();
+            }
+            _ => (),
+        }
+    }
+
+    fn visit_string(&mut self, _path: &Path, value: &mut String) {
+        self.rules.redact_patterns(value);
+    }
+
+    fn visit_file(&mut self, _path: &Path, value: &mut File) {
+        self.rules.redact_patterns(&mut value.filename);
+        self.rules.redact_patterns(&mut value.filepath);
+    }
+}
+
+impl Value {
+
+    /// A copy of this value with the values matched by `rules` redacted.
+    pub fn redact(&self, rules: &RedactionRules) -> Value {
+        let mut value = self.clone();
+        value.walk_mut(&mut Redactor { rules });
+        value
+    }
+
+    /// Wrap this value so that it's redacted when formatted with `Display` or `Debug`.
+    pub fn redacted<'a>(&'a self, rules: &'a RedactionRules) -> Redacted<'a> {
+        Redacted::new(self, rules)
+    }
+}
+
+/// A value which is redacted when formatted, for logging. Both `Display` and `Debug` print the
+/// redacted value in Teon notation, so neither can print the raw value.
+#[derive(Clone, Copy)]
+pub struct Redacted<'a> {
+    value: &'a Value,
+    rules: &'a RedactionRules,
+}
+
+impl<'a> Redacted<'a> {
+
+    pub fn new(value: &'a Value, rules: &'a RedactionRules) -> Self {
+        Self { value, rules }
+    }
+}
+
+impl Display for Redacted<'_> {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.value.redact(self.rules), f)
+    }
+}
+
+impl Debug for Redacted<'_> {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,559 @@
+//! JSON Schema (draft 2020-12) export and import for [`Type`], and validation of values.
+//!
+//! Values are described in their JSON form for APIs: numbers, strings and booleans are plain,
+//! dates and datetimes are strings with `format: date` and `format: date-time`, object ids are
+//! hex strings, bytes are base64 strings, enum variants are their names, option variants are
+//! integers and regexes are pattern strings. Decimals keep the `{"$decimal": "1.5"}` tag shape
+//! so they don't lose precision. Tuples are arrays described with `prefixItems`, and ranges are
+//! `[start, end]` arrays. Types which share a JSON form are told apart on import by an
+//! `x-teon` keyword, which validators ignore.
+//!
+//! ```
+//! # use serde_json::json;
+//! # use teo_teon::teon;
+//! # use teo_teon::schema::Type;
+//! # use teo_teon::schema::json_schema::{from_json_schema, to_json_schema, validate};
+//! #
+//! let t = Type::Array(Box::new(Type::Date));
+//! let schema = to_json_schema(&t);
+//! assert_eq!(schema, json!({"type": "array", "items": {"type": "string", "format": "date"}}));
+//! assert_eq!(from_json_schema(&schema).unwrap(), t);
+//! assert!(validate(&teon!(["2024-01-02"]), &schema).is_ok());
+//! assert!(validate(&teon!(["today"]), &schema).is_err());
+//! ```
+
+use std::fmt::{Display, Formatter};
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use indexmap::IndexMap;
+use regex::Regex;
+use serde_json::{json, Map, Number, Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::value::Value;
+use super::ty::Type;
+
+/// The `$schema` of generated documents.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const OBJECT_ID_PATTERN: &str = "^[0-9a-fA-F]{24}$";
+const DECIMAL_PATTERN: &str = "^-?[0-9]+(\\.[0-9]+)?$";
+const MAX_REF_DEPTH: usize = 64;
+
+/// The JSON Schema of a type. References point into `#/$defs`.
+pub fn to_json_schema(t: &Type) -> JsonValue {
+    match t {
+        Type::Any => json!({}),
+        Type::Null => json!({"type": "null"}),
+        Type::Bool => json!({"type": "boolean"}),
+        Type::Int => json!({"type": "integer", "format": "int32"}),
+        Type::Int64 => json!({"type": "integer", "format": "int64"}),
+        Type::Float32 => json!({"type": "number", "format": "float"}),
+        Type::Float => json!({"type": "number", "format": "double"}),
+        Type::Decimal => json!({
+            "type": "object",
+            "properties": {"$decimal": {"type": "string", "pattern": DECIMAL_PATTERN}},
+            "required": ["$decimal"],
+            "additionalProperties": false,
+            "x-teon": "Decimal",
+        }),
+        Type::ObjectId => json!({"type": "string", "pattern": OBJECT_ID_PATTERN, "x-teon": "ObjectId"}),
+        Type::String => json!({"type": "string"}),
+        Type::Date => json!({"type": "string", "format": "date"}),
+        Type::DateTime => json!({"type": "string", "format": "date-time"}),
+        Type::Bytes => json!({"type": "string", "contentEncoding": "base64"}),
+        Type::File => json!({
+            "type": "object",
+            "properties": {
+                "filepath": {"type": "string"},
+                "contentType": {"type": ["string", "null"]},
+                "filename": {"type": "string"},
+                "filenameExt": {"type": ["string", "null"]},
+            },
+            "required": ["filepath", "filename"],
+            "x-teon": "File",
+        }),
+        Type::Regex => json!({"type": "string", "format": "regex"}),
+        Type::OptionVariant => json!({"type": "integer", "format": "int32", "x-teon": "OptionVariant"}),
+        Type::Optional(inner) if inner.is_optional() => to_json_schema(inner),
+        Type::Optional(inner) => json!({"anyOf": [to_json_schema(inner), {"type": "null"}]}),
+        Type::Array(inner) => json!({"type": "array", "items": to_json_schema(inner)}),
+        Type::Dictionary(inner) => json!({"type": "object", "additionalProperties": to_json_schema(inner)}),
+        Type::Shape(fields) => {
+            let properties: Map<String, JsonValue> = fields.iter().map(|(k, t)| (k.clone(), to_json_schema(t))).collect();
+            let required: Vec<&String> = fields.iter().filter(|(_, t)| !matches!(t, Type::Optional(_))).map(|(k, _)| k).collect();
+            json!({"type": "object", "properties": properties, "required": required})
+        }
+        Type::Tuple(items) => json!({
+            "type": "array",
+            "prefixItems": items.iter().map(to_json_schema).collect::<Vec<_>>(),
+            "items": false,
+            "minItems": items.len(),
+        }),
+        Type::Range(inner) => json!({
+            "type": "array",
+            "prefixItems": [to_json_schema(inner), to_json_schema(inner)],
+            "items": false,
+            "minItems": 2,
+            "x-teon": "Range",
+        }),
+        Type::Enum(names) => json!({"type": "string", "enum": names}),
+        Type::Union(members) if members.is_empty() => JsonValue::Bool(false),
+        Type::Union(members) => json!({"anyOf": members.iter().map(to_json_schema).collect::<Vec<_>>()}),
+        Type::Reference(name) => json!({"$ref": format!("#/$defs/{}", escape_pointer(name))}),
+    }
+}
+
+/// A JSON Schema document declaring the types in `$defs`.
+pub fn to_json_schema_document(types: &IndexMap<String, Type>) -> JsonValue {
+    let defs: Map<String, JsonValue> = types.iter().map(|(k, t)| (k.clone(), to_json_schema(t))).collect();
+    json!({"$schema": DIALECT, "$defs": defs})
+}
+
+/// The type described by a JSON Schema. Besides schemas produced by [`to_json_schema`], common
+/// constructs are understood: `type` arrays, `anyOf` and `oneOf` as unions, string `enum` and
+/// `const`, and `$ref` into `#/$defs` or `#/definitions`. Other keywords are ignored.
+pub fn from_json_schema(schema: &JsonValue) -> Result<Type> {
+    let object = match schema {
+        JsonValue::Bool(true) => return Ok(Type::Any),
+        JsonValue::Bool(false) => return Ok(Type::Union(vec![])),
+        JsonValue::Object(object) => object,
+        _ => Err(Error::new(format!("Cannot convert json schema into type, invalid schema `{}`", schema)))?,
+    };
+    if let Some(reference) = object.get("$ref") {
+        let reference = reference.as_str().unwrap_or_default();
+        let name = reference.strip_prefix("#/$defs/").or_else(|| reference.strip_prefix("#/definitions/"))
+            .filter(|name| !name.contains('/'))
+            .ok_or_else(|| Error::new(format!("Cannot convert json schema into type, unsupported reference `{}`", reference)))?;
+        return Ok(Type::Reference(unescape_pointer(name)));
+    }
+    if let Some(members) = object.get("anyOf").or_else(|| object.get("oneOf")) {
+        let members = members.as_array().ok_or_else(|| Error::new("Cannot convert json schema into type, invalid `anyOf`"))?;
+        return members.iter().map(from_json_schema).try_fold(Type::Union(vec![]), |union, t| Ok(match (union, t?) {
+            (Type::Union(members), t) if members.is_empty() => t,
+            (union, t) => union.unify(t),
+        }));
+    }
+    if let Some(names) = object.get("enum").and_then(JsonValue::as_array) {
+        return Ok(match names.iter().map(|n| n.as_str().map(ToOwned::to_owned)).collect::<Option<Vec<_>>>() {
+            Some(names) => Type::Enum(names),
+            None => Type::Any,
+        });
+    }
+    if let Some(name) = object.get("const").and_then(JsonValue::as_str) {
+        return Ok(Type::Enum(vec![name.to_owned()]));
+    }
+    match object.get("x-teon").and_then(JsonValue::as_str) {
+        Some("Decimal") => return Ok(Type::Decimal),
+        Some("ObjectId") => return Ok(Type::ObjectId),
+        Some("File") => return Ok(Type::File),
+        Some("OptionVariant") => return Ok(Type::OptionVariant),
+        Some("Range") => {
+            let bound = object.get("prefixItems").and_then(|p| p.get(0)).map(from_json_schema).transpose()?.unwrap_or(Type::Any);
+            return Ok(Type::Range(Box::new(bound)));
+        }
+        _ => (),
+    }
+    let types: Vec<&str> = match object.get("type") {
+        None => return Ok(Type::Any),
+        Some(JsonValue::String(t)) => vec![t.as_str()],
+        Some(JsonValue::Array(types)) => types.iter().filter_map(JsonValue::as_str).collect(),
+        Some(t) => Err(Error::new(format!("Cannot convert json schema into type, invalid type `{}`", t)))?,
+    };
+    let mut result = Type::Union(vec![]);
+    for t in types {
+        let t = match t {
+            "null" => Type::Null,
+            "boolean" => Type::Bool,
+            "integer" => match object.get("format").and_then(JsonValue::as_str) {
+                Some("int32") => Type::Int,
+                _ => Type::Int64,
+            },
+            "number" => match object.get("format").and_then(JsonValue::as_str) {
+                Some("float") => Type::Float32,
+                _ => Type::Float,
+            },
+            "string" => match (object.get("format").and_then(JsonValue::as_str), object.get("contentEncoding").and_then(JsonValue::as_str)) {
+                (Some("date"), _) => Type::Date,
+                (Some("date-time"), _) => Type::DateTime,
+                (Some("regex"), _) => Type::Regex,
+                (_, Some("base64")) => Type::Bytes,
+                _ if object.get("pattern").and_then(JsonValue::as_str) == Some(OBJECT_ID_PATTERN) => Type::ObjectId,
+                _ => Type::String,
+            },
+            "array" => match (object.get("prefixItems").and_then(JsonValue::as_array), object.get("items")) {
+                (Some(items), _) => Type::Tuple(items.iter().map(from_json_schema).collect::<Result<_>>()?),
+                (None, Some(items)) => Type::Array(Box::new(from_json_schema(items)?)),
+                (None, None) => Type::Array(Box::new(Type::Any)),
+            },
+            "object" => match (object.get("properties").and_then(JsonValue::as_object), object.get("additionalProperties")) {
+                (Some(properties), _) => {
+                    let required: Vec<&str> = object.get("required").and_then(JsonValue::as_array).map(|r| r.iter().filter_map(JsonValue::as_str).collect()).unwrap_or_default();
+                    let mut fields = IndexMap::new();
+                    for (key, schema) in properties {
+                        let t = from_json_schema(schema)?;
+                        fields.insert(key.clone(), if required.contains(&key.as_str()) { t } else { t.or_missing() });
+                    }
+                    Type::Shape(fields)
+                }
+                (None, Some(values)) => Type::Dictionary(Box::new(from_json_schema(values)?)),
+                (None, None) => Type::Dictionary(Box::new(Type::Any)),
+            },
+            t => Err(Error::new(format!("Cannot convert json schema into type, unknown type `{}`", t)))?,
+        };
+        result = match result {
+            Type::Union(members) if members.is_empty() => t,
+            result => result.unify(t),
+        };
+    }
+    Ok(result)
+}
+
+/// The types declared in `$defs`, or `definitions`, of a JSON Schema document.
+pub fn from_json_schema_document(document: &JsonValue) -> Result<IndexMap<String, Type>> {
+    let defs = document.get("$defs").or_else(|| document.get("definitions")).and_then(JsonValue::as_object)
+        .ok_or_else(|| Error::new("Cannot convert json schema document into types, missing `$defs`"))?;
+    let mut types = IndexMap::new();
+    for (name, schema) in defs {
+        types.insert(name.clone(), from_json_schema(schema)?);
+    }
+    Ok(types)
+}
+
+/// A value which doesn't satisfy a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The JSON pointer of the invalid value, empty for the validated value itself.
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "value {}", self.message)
+        } else {
+            write!(f, "value at `{}` {}", self.path, self.message)
+        }
+    }
+}
+
+/// Validate `value` against a JSON Schema, and return every validation error.
+///
+/// The value is validated in its JSON form described in the [module documentation](self).
+/// Besides the core applicators and validation keywords, the `date`, `date-time`, `int32` and
+/// `regex` formats are asserted. `$ref` may point anywhere into `schema`; other references are
+/// an error, as are schemas which are not valid.
+pub fn validation_errors(value: &Value, schema: &JsonValue) -> Result<Vec<ValidationError>> {
+    let instance = json_form(value);
+    Validator { root: schema }.errors(schema, &instance, "", 0)
+}
+
+/// Validate `value` against a JSON Schema. See [`validation_errors`].
+pub fn validate(value: &Value, schema: &JsonValue) -> Result<()> {
+    let errors = validation_errors(value, schema)?;
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::new(errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")))
+    }
+}
+
+fn number(n: f64) -> JsonValue {
+    Number::from_f64(n).map_or(JsonValue::Null, JsonValue::Number)
+}
+
+// The JSON form of a value used for validation.
+fn json_form(value: &Value) -> JsonValue {
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Bool(b) => JsonValue::Bool(*b),
+        Value::Int(i) => json!(i),
+        Value::Int64(i) => json!(i),
+        Value::Float32(f) => number(*f as f64),
+        Value::Float(f) => number(*f),
+        Value::Decimal(d) => json!({"$decimal": d.normalized().to_string()}),
+        Value::ObjectId(o) => JsonValue::String(o.to_hex()),
+        Value::String(s) => JsonValue::String(s.clone()),
+        Value::Date(d) => JsonValue::String(d.format("%Y-%m-%d").to_string()),
+        Value::DateTime(d) => JsonValue::String(d.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Value::Array(items) | Value::Tuple(items) => JsonValue::Array(items.iter().map(json_form).collect()),
+        Value::Dictionary(map) => JsonValue::Object(map.iter().map(|(k, v)| (k.clone(), json_form(v))).collect()),
+        Value::Range(r) => JsonValue::Array(vec![json_form(&r.start), json_form(&r.end)]),
+        Value::EnumVariant(e) => JsonValue::String(e.value.clone()),
+        Value::OptionVariant(o) => json!(o.value),
+        Value::Regex(r) => JsonValue::String(r.pattern().to_owned()),
+        Value::File(f) => json!({"filepath": f.filepath, "contentType": f.content_type, "filename": f.filename, "filenameExt": f.filename_ext}),
+        Value::Bytes(b) => JsonValue::String(BASE64.encode(b)),
+    }
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn type_name(instance: &JsonValue) -> &'static str {
+    match instance {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+fn has_type(instance: &JsonValue, t: &str) -> bool {
+    match t {
+        "integer" => instance.as_f64().is_some_and(|n| n.fract() == 0.0),
+        t => type_name(instance) == t,
+    }
+}
+
+// Equality where numbers compare by value, so `1` equals `1.0`.
+fn json_eq(a: &JsonValue, b: &JsonValue) -> bool {
+    match (a, b) {
+        (JsonValue::Number(a), JsonValue::Number(b)) => a.as_f64() == b.as_f64(),
+        (JsonValue::Array(a), JsonValue::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b)),
+        (JsonValue::Object(a), JsonValue::Object(b)) => a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| json_eq(v, w))),
+        (a, b) => a == b,
+    }
+}
+
+fn format_matches(format: &str, s: &str) -> bool {
+    match format {
+        "date" => NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        "date-time" => DateTime::parse_from_rfc3339(s).is_ok(),
+        "regex" => Regex::new(s).is_ok(),
+        _ => true,
+    }
+}
+
+fn invalid_schema(message: impl Display) -> Error {
+    Error::new(format!("Invalid json schema: {}", message))
+}
+
+struct Validator<'a> {
+    root: &'a JsonValue,
+}
+
+impl<'a> Validator<'a> {
+
+    fn resolve(&self, reference: &str) -> Result<&'a JsonValue> {
+        let pointer = reference.strip_prefix('#').ok_or_else(|| invalid_schema(format!("unsupported reference `{}`", reference)))?;
+        self.root.pointer(pointer).ok_or_else(|| invalid_schema(format!("unresolvable reference `{}`", reference)))
+    }
+
+    fn is_valid(&self, schema: &'a JsonValue, instance: &JsonValue, path: &str, depth: usize) -> Result<bool> {
+        Ok(self.errors(schema, instance, path, depth)?.is_empty())
+    }
+
+    fn errors(&self, schema: &'a JsonValue, instance: &JsonValue, path: &str, depth: usize) -> Result<Vec<ValidationError>> {
+        let schema = match schema {
+            JsonValue::Bool(true) => return Ok(vec![]),
+            JsonValue::Bool(false) => return Ok(vec![ValidationError { path: path.to_owned(), message: "is not allowed".to_owned() }]),
+            JsonValue::Object(schema) => schema,
+            _ => Err(invalid_schema(format!("expect object or boolean, found `{}`", schema)))?,
+        };
+        let mut errors = vec![];
+        let mut error = |message: String| errors.push(ValidationError { path: path.to_owned(), message });
+        let mut nested = vec![];
+        if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+            if depth >= MAX_REF_DEPTH {
+                Err(invalid_schema(format!("reference `{}` nests too deeply", reference)))?
+            }
+            nested.extend(self.errors(self.resolve(reference)?, instance, path, depth + 1)?);
+        }
+        match schema.get("type") {
+            None | Some(JsonValue::String(_) | JsonValue::Array(_)) => (),
+            Some(t) => Err(invalid_schema(format!("invalid type `{}`", t)))?,
+        }
+        let types: Vec<&str> = match schema.get("type") {
+            Some(JsonValue::String(t)) => vec![t.as_str()],
+            Some(JsonValue::Array(types)) => types.iter().filter_map(JsonValue::as_str).collect(),
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(instance, t)) {
+            error(format!("expect {}, found {}", types.join(" or "), type_name(instance)));
+        }
+        if let Some(values) = schema.get("enum").and_then(JsonValue::as_array) {
+            if !values.iter().any(|v| json_eq(v, instance)) {
+                error(format!("expect one of {}", JsonValue::Array(values.clone())));
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            if !json_eq(value, instance) {
+                error(format!("expect {}", value));
+            }
+        }
+        if let Some(n) = instance.as_f64() {
+            let bound = |key: &str| schema.get(key).and_then(JsonValue::as_f64);
+            if let Some(min) = bound("minimum").filter(|min| n < *min) {
+                error(format!("must be at least {}", min));
+            }
+            if let Some(max) = bound("maximum").filter(|max| n > *max) {
+                error(format!("must be at most {}", max));
+            }
+            if let Some(min) = bound("exclusiveMinimum").filter(|min| n <= *min) {
+                error(format!("must be greater than {}", min));
+            }
+            if let Some(max) = bound("exclusiveMaximum").filter(|max| n >= *max) {
+                error(format!("must be less than {}", max));
+            }
+            if let Some(divisor) = bound("multipleOf").filter(|d| *d > 0.0 && (n / d).fract() != 0.0) {
+                error(format!("must be a multiple of {}", divisor));
+            }
+            if schema.get("format").and_then(JsonValue::as_str) == Some("int32") && (n.fract() != 0.0 || n.to_i32().is_none()) {
+                error("expect format int32".to_owned());
+            }
+        }
+        if let Some(s) = instance.as_str() {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(JsonValue::as_u64).filter(|min| len < *min) {
+                error(format!("must have at least {} characters", min));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(JsonValue::as_u64).filter(|max| len > *max) {
+                error(format!("must have at most {} characters", max));
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(JsonValue::as_str) {
+                let regex = Regex::new(pattern).map_err(|_| invalid_schema(format!("invalid pattern `{}`", pattern)))?;
+                if !regex.is_match(s) {
+                    error(format!("must match pattern `{}`", pattern));
+                }
+            }
+            if let Some(format) = schema.get("format").and_then(JsonValue::as_str) {
+                if !format_matches(format, s) {
+                    error(format!("expect format {}", format));
+                }
+            }
+        }
+        if let Some(items) = instance.as_array() {
+            let prefix = schema.get("prefixItems").and_then(JsonValue::as_array).map_or(&[][..], Vec::as_slice);
+            for (i, (item, item_schema)) in items.iter().zip(prefix).enumerate() {
+                nested.extend(self.errors(item_schema, item, &format!("{}/{}", path, i), depth)?);
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate().skip(prefix.len()) {
+                    nested.extend(self.errors(item_schema, item, &format!("{}/{}", path, i), depth)?);
+                }
+            }
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(JsonValue::as_u64).filter(|min| len < *min) {
+                error(format!("must have at least {} items", min));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64).filter(|max| len > *max) {
+                error(format!("must have at most {} items", max));
+            }
+            if schema.get("uniqueItems") == Some(&JsonValue::Bool(true)) && items.iter().enumerate().any(|(i, a)| items[..i].iter().any(|b| json_eq(a, b))) {
+                error("must have unique items".to_owned());
+            }
+            if let Some(contains) = schema.get("contains") {
+                let mut count = 0;
+                for item in items {
+                    if self.is_valid(contains, item, path, depth)? {
+                        count += 1;
+                    }
+                }
+                let min = schema.get("minContains").and_then(JsonValue::as_u64).unwrap_or(1);
+                if count < min {
+                    error(format!("must contain at least {} matching items", min));
+                }
+                if let Some(max) = schema.get("maxContains").and_then(JsonValue::as_u64).filter(|max| count > *max) {
+                    error(format!("must contain at most {} matching items", max));
+                }
+            }
+        }
+        if let Some(object) = instance.as_object() {
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            let mut pattern_properties = vec![];
+            if let Some(patterns) = schema.get("patternProperties").and_then(JsonValue::as_object) {
+                for (pattern, property_schema) in patterns {
+                    pattern_properties.push((Regex::new(pattern).map_err(|_| invalid_schema(format!("invalid pattern `{}`", pattern)))?, property_schema));
+                }
+            }
+            for key in schema.get("required").and_then(JsonValue::as_array).into_iter().flatten().filter_map(JsonValue::as_str) {
+                if !object.contains_key(key) {
+                    error(format!("is missing required key `{}`", key));
+                }
+            }
+            for (key, item) in object {
+                let item_path = format!("{}/{}", path, escape_pointer(key));
+                let mut matched = false;
+                if let Some(property_schema) = properties.and_then(|p| p.get(key)) {
+                    matched = true;
+                    nested.extend(self.errors(property_schema, item, &item_path, depth)?);
+                }
+                for (regex, property_schema) in &pattern_properties {
+                    if regex.is_match(key) {
+                        matched = true;
+                        nested.extend(self.errors(property_schema, item, &item_path, depth)?);
+                    }
+                }
+                match schema.get("additionalProperties") {
+                    Some(JsonValue::Bool(false)) if !matched => error(format!("has unexpected key `{}`", key)),
+                    Some(additional) if !matched => nested.extend(self.errors(additional, item, &item_path, depth)?),
+                    _ => (),
+                }
+                if let Some(names) = schema.get("propertyNames") {
+                    if !self.is_valid(names, &JsonValue::String(key.clone()), &item_path, depth)? {
+                        error(format!("has invalid key `{}`", key));
+                    }
+                }
+            }
+            let len = object.len() as u64;
+            if let Some(min) = schema.get("minProperties").and_then(JsonValue::as_u64).filter(|min| len < *min) {
+                error(format!("must have at least {} keys", min));
+            }
+            if let Some(max) = schema.get("maxProperties").and_then(JsonValue::as_u64).filter(|max| len > *max) {
+                error(format!("must have at most {} keys", max));
+            }
+        }
+        if let Some(schemas) = schema.get("allOf").and_then(JsonValue::as_array) {
+            for sub in schemas {
+                nested.extend(self.errors(sub, instance, path, depth)?);
+            }
+        }
+        if let Some(schemas) = schema.get("anyOf").and_then(JsonValue::as_array) {
+            let mut any = false;
+            for sub in schemas {
+                if self.is_valid(sub, instance, path, depth)? {
+                    any = true;
+                    break;
+                }
+            }
+            if !any {
+                error("must match at least one schema of `anyOf`".to_owned());
+            }
+        }
+        if let Some(schemas) = schema.get("oneOf").and_then(JsonValue::as_array) {
+            let mut count = 0;
+            for sub in schemas {
+                if self.is_valid(sub, instance, path, depth)? {
+                    count += 1;
+                }
+            }
+            if count != 1 {
+                error(format!("must match exactly one schema of `oneOf`, matched {}", count));
+            }
+        }
+        if let Some(sub) = schema.get("not") {
+            if self.is_valid(sub, instance, path, depth)? {
+                error("must not match the schema of `not`".to_owned());
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.is_valid(condition, instance, path, depth)? { "then" } else { "else" };
+            if let Some(sub) = schema.get(branch) {
+                nested.extend(self.errors(sub, instance, path, depth)?);
+            }
+        }
+        errors.extend(nested);
+        Ok(errors)
+    }
+}
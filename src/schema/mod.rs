@@ -0,0 +1,18 @@
+//! Descriptions of the shapes of Teon values, code generation from them, and JSON Schema.
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::indexmap::indexmap;
+//! # use teo_teon::schema::{typescript, Type};
+//! #
+//! let user = Type::from_values(&[teon!({"name": "Ann", "age": 30}), teon!({"name": "Bob"})]);
+//! let generated = typescript::generate(&indexmap! { "User".to_owned() => user }).unwrap();
+//! assert!(generated.declarations.contains("export interface User {\n    name: string;\n    age?: number | null;\n}"));
+//! assert!(generated.runtime.contains("export function decodeUser(json)"));
+//! ```
+
+pub mod ty;
+pub mod typescript;
+pub mod json_schema;
+
+pub use ty::Type;
@@ -0,0 +1,230 @@
+use std::fmt::{Display, Formatter};
+use std::mem;
+use indexmap::IndexMap;
+use crate::utils::literal::{is_identifier, string_literal};
+use crate::value::Value;
+
+/// A description of the shape of Teon values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// Any value.
+    Any,
+    Null,
+    Bool,
+    Int,
+    Int64,
+    Float32,
+    Float,
+    Decimal,
+    ObjectId,
+    String,
+    Date,
+    DateTime,
+    Bytes,
+    File,
+    Regex,
+    OptionVariant,
+    /// The inner type or null. As a field of a [`Type::Shape`], the field may also be missing.
+    Optional(Box<Type>),
+    Array(Box<Type>),
+    /// A dictionary with arbitrary keys whose values have the inner type.
+    Dictionary(Box<Type>),
+    /// A dictionary with these keys.
+    Shape(IndexMap<String, Type>),
+    Tuple(Vec<Type>),
+    /// A range whose bounds have the inner type.
+    Range(Box<Type>),
+    /// Enum variants with these names and no arguments.
+    Enum(Vec<String>),
+    /// Any of these types.
+    Union(Vec<Type>),
+    /// A named type declared elsewhere, e.g. in the declarations passed to a code generator.
+    Reference(String),
+}
+
+impl Type {
+
+    /// The most specific type of `value`. Dictionaries are described as shapes, and the element
+    /// type of an array is unified from its elements, which is `Never` for an empty array.
+    pub fn of(value: &Value) -> Type {
+        match value {
+            Value::Null => Type::Null,
+            Value::Bool(_) => Type::Bool,
+            Value::Int(_) => Type::Int,
+            Value::Int64(_) => Type::Int64,
+            Value::Float32(_) => Type::Float32,
+            Value::Float(_) => Type::Float,
+            Value::Decimal(_) => Type::Decimal,
+            Value::ObjectId(_) => Type::ObjectId,
+            Value::String(_) => Type::String,
+            Value::Date(_) => Type::Date,
+            Value::DateTime(_) => Type::DateTime,
+            Value::Array(items) => Type::Array(Box::new(items.iter().map(Type::of).fold(Type::Union(vec![]), Type::unify))),
+            Value::Dictionary(map) => Type::Shape(map.iter().map(|(k, v)| (k.clone(), Type::of(v))).collect()),
+            Value::Range(r) => Type::Range(Box::new(Type::of(&r.start).unify(Type::of(&r.end)))),
+            Value::Tuple(items) => Type::Tuple(items.iter().map(Type::of).collect()),
+            Value::EnumVariant(e) => Type::Enum(vec![e.value.clone()]),
+            Value::OptionVariant(_) => Type::OptionVariant,
+            Value::Regex(_) => Type::Regex,
+            Value::File(_) => Type::File,
+            Value::Bytes(_) => Type::Bytes,
+        }
+    }
+
+    /// The unified type of sample values, or `Any` without samples.
+    pub fn from_values<'a>(values: impl IntoIterator<Item = &'a Value>) -> Type {
+        values.into_iter().map(Type::of).reduce(Type::unify).unwrap_or(Type::Any)
+    }
+
+    /// Wrap this type in `Optional` unless it already accepts null.
+    pub fn optional(self) -> Type {
+        match self {
+            Type::Any | Type::Null | Type::Optional(_) => self,
+            t => Type::Optional(Box::new(t)),
+        }
+    }
+
+    /// The type of a shape field which may be missing. Only `Optional` fields may be missing,
+    /// so unlike [`Type::optional`] this also wraps `Null` and `Any`.
+    pub fn or_missing(self) -> Type {
+        match self {
+            Type::Optional(_) => self,
+            t => Type::Optional(Box::new(t)),
+        }
+    }
+
+    /// Whether this type accepts null.
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Type::Any | Type::Null | Type::Optional(_))
+    }
+
+    /// The narrowest type describing values of both types. `Int` widens to `Int64` and `Float32`
+    /// to `Float`, shape fields missing on either side become optional, null makes a type
+    /// optional, and otherwise different types form a union. `Never`, the empty union, unifies
+    /// to the other type.
+    pub fn unify(self, other: Type) -> Type {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Type::Union(members), t) | (t, Type::Union(members)) if members.is_empty() => t,
+            (Type::Optional(a), b) | (b, Type::Optional(a)) => a.unify(b).or_missing(),
+            (Type::Any, _) | (_, Type::Any) => Type::Any,
+            (Type::Null, t) | (t, Type::Null) => t.optional(),
+            (Type::Int, Type::Int64) | (Type::Int64, Type::Int) => Type::Int64,
+            (Type::Float32, Type::Float) | (Type::Float, Type::Float32) => Type::Float,
+            (Type::Array(a), Type::Array(b)) => Type::Array(Box::new(a.unify(*b))),
+            (Type::Dictionary(a), Type::Dictionary(b)) => Type::Dictionary(Box::new(a.unify(*b))),
+            (Type::Range(a), Type::Range(b)) => Type::Range(Box::new(a.unify(*b))),
+            (Type::Tuple(a), Type::Tuple(b)) if a.len() == b.len() => Type::Tuple(a.into_iter().zip(b).map(|(a, b)| a.unify(b)).collect()),
+            (Type::Shape(mut a), Type::Shape(mut b)) => {
+                for (key, t) in a.iter_mut() {
+                    *t = match b.shift_remove(key) {
+                        Some(other) => mem::replace(t, Type::Any).unify(other),
+                        None => mem::replace(t, Type::Any).or_missing(),
+                    };
+                }
+                a.extend(b.into_iter().map(|(k, t)| (k, t.or_missing())));
+                Type::Shape(a)
+            }
+            (Type::Enum(mut a), Type::Enum(b)) => {
+                for name in b {
+                    if !a.contains(&name) {
+                        a.push(name);
+                    }
+                }
+                Type::Enum(a)
+            }
+            (Type::Union(mut members), t) | (t, Type::Union(mut members)) => {
+                insert_member(&mut members, t);
+                Type::Union(members)
+            }
+            (a, b) => {
+                let mut members = vec![a];
+                insert_member(&mut members, b);
+                Type::Union(members)
+            }
+        }
+    }
+}
+
+/// Types print in the notation of Teo schemas: `Int?` is optional, `Int[]` an array, `Int{}` a
+/// dictionary and `.a | .b` an enum.
+impl Display for Type {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Optional(inner) => write!(f, "{}?", Grouped(inner)),
+            Type::Array(inner) => write!(f, "{}[]", Grouped(inner)),
+            Type::Dictionary(inner) => write!(f, "{}{{}}", Grouped(inner)),
+            Type::Shape(fields) if fields.is_empty() => f.write_str("{}"),
+            Type::Shape(fields) => {
+                f.write_str("{ ")?;
+                for (i, (key, t)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    if is_identifier(key) {
+                        write!(f, "{}: {}", key, t)?;
+                    } else {
+                        write!(f, "{}: {}", string_literal(key), t)?;
+                    }
+                }
+                f.write_str(" }")
+            }
+            Type::Tuple(items) => {
+                f.write_str("(")?;
+                for (i, t) in items.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", t)?;
+                }
+                if items.len() == 1 {
+                    f.write_str(",")?;
+                }
+                f.write_str(")")
+            }
+            Type::Range(inner) => write!(f, "Range<{}>", inner),
+            Type::Enum(names) if names.is_empty() => f.write_str("Never"),
+            Type::Enum(names) => f.write_str(&names.iter().map(|n| format!(".{}", n)).collect::<Vec<_>>().join(" | ")),
+            Type::Union(members) if members.is_empty() => f.write_str("Never"),
+            Type::Union(members) => f.write_str(&members.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ")),
+            Type::Reference(name) => f.write_str(name),
+            t => write!(f, "{:?}", t),
+        }
+    }
+}
+
+// Parenthesizes unions and enums before a suffix.
+struct Grouped<'a>(&'a Type);
+
+impl Display for Grouped<'_> {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Type::Union(members) if members.len() > 1 => write!(f, "({})", self.0),
+            Type::Enum(names) if names.len() > 1 => write!(f, "({})", self.0),
+            t => write!(f, "{}", t),
+        }
+    }
+}
+
+// Whether `unify` merges the types rather than forming a union.
+fn mergeable(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Int | Type::Int64, Type::Int | Type::Int64) => true,
+        (Type::Float32 | Type::Float, Type::Float32 | Type::Float) => true,
+        (Type::Tuple(a), Type::Tuple(b)) => a.len() == b.len(),
+        (Type::Array(_), Type::Array(_)) | (Type::Dictionary(_), Type::Dictionary(_)) | (Type::Shape(_), Type::Shape(_)) | (Type::Range(_), Type::Range(_)) | (Type::Enum(_), Type::Enum(_)) => true,
+        (a, b) => a == b,
+    }
+}
+
+fn insert_member(members: &mut Vec<Type>, t: Type) {
+    match t {
+        Type::Union(others) => others.into_iter().for_each(|t| insert_member(members, t)),
+        t => match members.iter_mut().find(|member| mergeable(member, &t)) {
+            Some(member) => *member = mem::replace(member, Type::Any).unify(t),
+            None => members.push(t),
+        },
+    }
+}
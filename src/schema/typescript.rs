@@ -0,0 +1,448 @@
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::utils::literal::is_identifier;
+use super::ty::Type;
+
+/// TypeScript generated by [`generate`]: a JavaScript module and its type declarations, to be
+/// saved side by side as e.g. `models.js` and `models.d.ts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeScript {
+    /// The `.d.ts` declarations.
+    pub declarations: String,
+    /// The JavaScript module with the decoders and encoders.
+    pub runtime: String,
+}
+
+// Type names declared by the prelude, or used by the declarations from the global scope.
+const RESERVED: [&str; 22] = [
+    "Decimal", "Range", "OptionVariant", "TeonFile", "Date", "RegExp", "Uint8Array", "Record",
+    "Array", "Object", "String", "Number", "Boolean", "any", "unknown", "never", "string", "number",
+    "boolean", "null", "undefined", "object",
+];
+
+// Reserved words of JavaScript and TypeScript, which can't name an interface or a type alias.
+const KEYWORDS: [&str; 47] = [
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+    "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "import",
+    "in", "instanceof", "new", "return", "super", "switch", "this", "throw", "true", "try",
+    "typeof", "var", "void", "while", "with", "yield", "let", "static", "implements", "interface",
+    "package", "private", "protected", "public", "await", "symbol", "bigint",
+];
+
+const DECLARATIONS_PRELUDE: &str = r#"// Generated by teo-teon. Do not edit.
+
+export declare class Decimal {
+    constructor(value: string | number);
+    readonly value: string;
+    toString(): string;
+    toNumber(): number;
+    toJSON(): string;
+}
+
+export interface Range<T> {
+    start: T;
+    end: T;
+    closed: boolean;
+}
+
+export interface OptionVariant {
+    value: number;
+    display: string;
+}
+
+export interface TeonFile {
+    filepath: string;
+    contentType?: string | null;
+    filename: string;
+    filenameExt?: string | null;
+}
+
+/** Decode tagged Teon JSON without a declared type. */
+export declare function decodeTeon(json: unknown): unknown;
+
+/** Encode into tagged Teon JSON without a declared type. Integers are encoded as `Int64`,
+ * other numbers as `Float` and dates as `DateTime`. */
+export declare function encodeTeon(value: unknown): unknown;
+"#;
+
+const RUNTIME: &str = r#"// Generated by teo-teon. Do not edit.
+
+export class Decimal {
+    constructor(value) {
+        this.value = String(value);
+    }
+    toString() {
+        return this.value;
+    }
+    toNumber() {
+        return Number(this.value);
+    }
+    toJSON() {
+        return this.value;
+    }
+}
+
+const TAGS = ["$int", "$int64", "$float32", "$float", "$decimal", "$objectId", "$date", "$datetime", "$range", "$tuple", "$enumVariant", "$optionVariant", "$regex", "$file", "$bytes"];
+
+function fail(expected, json) {
+    throw new TypeError(`expect ${expected}, found ${JSON.stringify(json)}`);
+}
+
+function isObject(json) {
+    return json !== null && typeof json === "object" && !Array.isArray(json);
+}
+
+function tagged(json, ...tags) {
+    const tag = isObject(json) ? tags.find((tag) => tag in json) : undefined;
+    if (tag === undefined) fail(tags.join(" or "), json);
+    return json[tag];
+}
+
+function checked(json, type) {
+    if (typeof json !== type) fail(type, json);
+    return json;
+}
+
+function decodeDate(json) {
+    if (typeof json !== "string" || !/^\d{4}-\d{2}-\d{2}$/.test(json)) fail("date", json);
+    return new Date(`${json}T00:00:00.000Z`);
+}
+
+function decodeDateTime(json) {
+    const date = new Date(checked(json, "string"));
+    if (isNaN(date.getTime())) fail("datetime", json);
+    return date;
+}
+
+function decodeBytes(json) {
+    const binary = atob(checked(json, "string"));
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) bytes[i] = binary.charCodeAt(i);
+    return bytes;
+}
+
+function encodeBytes(bytes) {
+    let binary = "";
+    for (const byte of bytes) binary += String.fromCharCode(byte);
+    return btoa(binary);
+}
+
+function encodeRegex(regex) {
+    return regex.flags ? { $regex: regex.source, $options: regex.flags } : { $regex: regex.source };
+}
+
+function decodeTag(tag, payload, json) {
+    switch (tag) {
+        case "$int": case "$int64": case "$float32": case "$float": return checked(payload, "number");
+        case "$decimal": return new Decimal(checked(payload, "string"));
+        case "$objectId": return checked(payload, "string");
+        case "$date": return decodeDate(payload);
+        case "$datetime": return decodeDateTime(payload);
+        case "$range": return { start: decodeTeon(payload.start), end: decodeTeon(payload.end), closed: payload.closed };
+        case "$tuple": return payload.map(decodeTeon);
+        case "$enumVariant": return payload.value;
+        case "$optionVariant": return { value: payload.value, display: payload.display };
+        case "$regex": return new RegExp(payload, json.$options ?? "");
+        case "$file": return payload;
+        case "$bytes": return decodeBytes(payload);
+    }
+}
+
+export function decodeTeon(json) {
+    if (Array.isArray(json)) return json.map(decodeTeon);
+    if (isObject(json)) {
+        const keys = Object.keys(json);
+        const tag = keys.find((key) => TAGS.includes(key));
+        if (tag !== undefined && (keys.length === 1 || (tag === "$regex" && keys.length === 2 && "$options" in json))) {
+            return decodeTag(tag, json[tag], json);
+        }
+        return Object.fromEntries(keys.map((key) => [key, decodeTeon(json[key])]));
+    }
+    return json;
+}
+
+export function encodeTeon(value) {
+    if (value === null || value === undefined) return null;
+    if (typeof value === "number") return Number.isInteger(value) ? { $int64: value } : { $float: value };
+    if (typeof value !== "object") return value;
+    if (value instanceof Decimal) return { $decimal: value.value };
+    if (value instanceof Date) return { $datetime: value.toISOString() };
+    if (value instanceof RegExp) return encodeRegex(value);
+    if (value instanceof Uint8Array) return { $bytes: encodeBytes(value) };
+    if (Array.isArray(value)) return value.map(encodeTeon);
+    return Object.fromEntries(Object.entries(value).filter(([, v]) => v !== undefined).map(([k, v]) => [k, encodeTeon(v)]));
+}
+
+function decode(type, json) {
+    if (typeof type === "string") {
+        switch (type) {
+            case "Any": return decodeTeon(json);
+            case "Null": if (json !== null) fail("null", json); return null;
+            case "Bool": return checked(json, "boolean");
+            case "String": return checked(json, "string");
+            case "Int": return checked(tagged(json, "$int"), "number");
+            case "Int64": return checked(tagged(json, "$int64", "$int"), "number");
+            case "Float32": return checked(tagged(json, "$float32"), "number");
+            case "Float": return checked(tagged(json, "$float", "$float32"), "number");
+            case "Decimal": return new Decimal(checked(tagged(json, "$decimal"), "string"));
+            case "ObjectId": return checked(tagged(json, "$objectId"), "string");
+            case "Date": return decodeDate(tagged(json, "$date"));
+            case "DateTime": return decodeDateTime(tagged(json, "$datetime"));
+            case "Bytes": return decodeBytes(tagged(json, "$bytes"));
+            case "File": return tagged(json, "$file");
+            case "Regex": return new RegExp(checked(tagged(json, "$regex"), "string"), json.$options ?? "");
+            case "OptionVariant": return decodeTag("$optionVariant", tagged(json, "$optionVariant"), json);
+        }
+    }
+    if ("optional" in type) return json === null || json === undefined ? null : decode(type.optional, json);
+    if ("array" in type) {
+        if (!Array.isArray(json)) fail("array", json);
+        return json.map((item) => decode(type.array, item));
+    }
+    if ("dictionary" in type) {
+        if (!isObject(json)) fail("object", json);
+        return Object.fromEntries(Object.entries(json).map(([key, value]) => [key, decode(type.dictionary, value)]));
+    }
+    if ("shape" in type) {
+        if (!isObject(json)) fail("object", json);
+        const result = {};
+        for (const [key, field] of Object.entries(type.shape)) {
+            if (key in json) {
+                result[key] = decode(field, json[key]);
+            } else if (!(typeof field === "object" && "optional" in field)) {
+                fail(`key "${key}"`, json);
+            }
+        }
+        return result;
+    }
+    if ("tuple" in type) {
+        const items = tagged(json, "$tuple");
+        if (!Array.isArray(items) || items.length !== type.tuple.length) fail(`tuple of ${type.tuple.length}`, json);
+        return items.map((item, i) => decode(type.tuple[i], item));
+    }
+    if ("range" in type) {
+        const range = tagged(json, "$range");
+        return { start: decode(type.range, range.start), end: decode(type.range, range.end), closed: checked(range.closed, "boolean") };
+    }
+    if ("enum" in type) {
+        const name = tagged(json, "$enumVariant").value;
+        if (!type.enum.includes(name)) fail(type.enum.map((name) => JSON.stringify(name)).join(" | "), name);
+        return name;
+    }
+    if ("union" in type) {
+        for (const member of type.union) {
+            try {
+                return decode(member, json);
+            } catch (e) {
+                if (!(e instanceof TypeError)) throw e;
+            }
+        }
+        fail("a member of the union", json);
+    }
+    return decode(DECLARATIONS[type.ref], json);
+}
+
+function matches(type, value) {
+    if (typeof type === "string") {
+        switch (type) {
+            case "Any": return true;
+            case "Null": return value === null || value === undefined;
+            case "Bool": return typeof value === "boolean";
+            case "String": case "ObjectId": return typeof value === "string";
+            case "Int": case "Int64": return Number.isInteger(value);
+            case "Float32": case "Float": return typeof value === "number";
+            case "Decimal": return value instanceof Decimal;
+            case "Date": case "DateTime": return value instanceof Date;
+            case "Bytes": return value instanceof Uint8Array;
+            case "Regex": return value instanceof RegExp;
+            case "File": case "OptionVariant": return isObject(value);
+        }
+    }
+    if ("optional" in type) return value === null || value === undefined || matches(type.optional, value);
+    if ("array" in type) return Array.isArray(value) && value.every((item) => matches(type.array, item));
+    if ("dictionary" in type) return isObject(value) && Object.values(value).every((item) => matches(type.dictionary, item));
+    if ("shape" in type) return isObject(value) && Object.entries(type.shape).every(([key, field]) => matches(field, value[key]));
+    if ("tuple" in type) return Array.isArray(value) && value.length === type.tuple.length && value.every((item, i) => matches(type.tuple[i], item));
+    if ("range" in type) return isObject(value) && matches(type.range, value.start) && matches(type.range, value.end);
+    if ("enum" in type) return type.enum.includes(value);
+    if ("union" in type) return type.union.some((member) => matches(member, value));
+    return matches(DECLARATIONS[type.ref], value);
+}
+
+function encode(type, value) {
+    if (typeof type === "string") {
+        switch (type) {
+            case "Any": return encodeTeon(value);
+            case "Null": return null;
+            case "Bool": case "String": return value;
+            case "Int": return { $int: value };
+            case "Int64": return { $int64: value };
+            case "Float32": return { $float32: value };
+            case "Float": return { $float: value };
+            case "Decimal": return { $decimal: String(value) };
+            case "ObjectId": return { $objectId: value };
+            case "Date": return { $date: value.toISOString().slice(0, 10) };
+            case "DateTime": return { $datetime: value.toISOString() };
+            case "Bytes": return { $bytes: encodeBytes(value) };
+            case "File": return { $file: value };
+            case "Regex": return encodeRegex(value);
+            case "OptionVariant": return { $optionVariant: { value: value.value, display: value.display } };
+        }
+    }
+    if ("optional" in type) return value === null || value === undefined ? null : encode(type.optional, value);
+    if ("array" in type) return value.map((item) => encode(type.array, item));
+    if ("dictionary" in type) return Object.fromEntries(Object.entries(value).map(([key, item]) => [key, encode(type.dictionary, item)]));
+    if ("shape" in type) {
+        const result = {};
+        for (const [key, field] of Object.entries(type.shape)) {
+            if (value[key] !== undefined) result[key] = encode(field, value[key]);
+        }
+        return result;
+    }
+    if ("tuple" in type) return { $tuple: value.map((item, i) => encode(type.tuple[i], item)) };
+    if ("range" in type) return { $range: { start: encode(type.range, value.start), end: encode(type.range, value.end), closed: value.closed } };
+    if ("enum" in type) return { $enumVariant: { value, args: null } };
+    if ("union" in type) {
+        const member = type.union.find((member) => matches(member, value));
+        if (member === undefined) fail("a member of the union", value);
+        return encode(member, value);
+    }
+    return encode(DECLARATIONS[type.ref], value);
+}
+"#;
+
+/// Generate TypeScript declarations, decoders and encoders for named types.
+///
+/// Each type `T` is declared as an interface when it's a shape, and as a type alias otherwise,
+/// along with `decodeT(json)` which checks and decodes the `$`-tagged JSON produced by the
+/// `Serialize` implementation of `Value`, and `encodeT(value)` which produces it. Decoded values
+/// use `Date` for dates and datetimes, the `Decimal` wrapper class for decimals, `Uint8Array`
+/// for bytes, `RegExp` for regular expressions and string literal unions for enums. Integers
+/// are decoded as `number`, so `Int64` values beyond 2<sup>53</sup> lose precision.
+///
+/// Types are usually written by hand, or inferred from sample values with
+/// [`Type::from_values`]. Names must be JavaScript identifiers, and references must name one of
+/// the types. Names can't shadow the types the declarations use, like `Date` or `Decimal`, and
+/// their decoders and encoders can't collide with the runtime helpers, like `decodeBytes`.
+pub fn generate(types: &IndexMap<String, Type>) -> Result<TypeScript> {
+    let runtime_names = runtime_names();
+    for name in types.keys() {
+        if !is_js_identifier(name) || RESERVED.contains(&name.as_str()) || KEYWORDS.contains(&name.as_str()) {
+            Err(Error::new(format!("Cannot generate typescript for `{}`, expect an identifier which is not reserved", name)))?
+        }
+        if let Some(function) = [format!("decode{}", name), format!("encode{}", name)].into_iter().find(|f| runtime_names.contains(&f.as_str())) {
+            Err(Error::new(format!("Cannot generate typescript for `{}`, `{}` is declared by the runtime", name, function)))?
+        }
+    }
+    for (name, t) in types {
+        check_references(t, name, types)?;
+    }
+    let mut declarations = DECLARATIONS_PRELUDE.to_owned();
+    let mut runtime = RUNTIME.to_owned();
+    let mut descriptors = Map::new();
+    for (name, t) in types {
+        declarations.push('\n');
+        match t {
+            Type::Shape(fields) => {
+                declarations.push_str(&format!("export interface {} {{\n", name));
+                for (key, field) in fields {
+                    declarations.push_str(&format!("    {};\n", field_declaration(key, field)));
+                }
+                declarations.push_str("}\n");
+            }
+            t => declarations.push_str(&format!("export type {} = {};\n", name, type_expression(t))),
+        }
+        declarations.push_str(&format!("export declare function decode{}(json: unknown): {};\n", name, name));
+        declarations.push_str(&format!("export declare function encode{}(value: {}): unknown;\n", name, name));
+        descriptors.insert(name.clone(), descriptor(t));
+    }
+    runtime.push_str(&format!("\nconst DECLARATIONS = {};\n", serde_json::to_string_pretty(&JsonValue::Object(descriptors)).unwrap()));
+    for name in types.keys() {
+        runtime.push_str(&format!("\nexport function decode{}(json) {{\n    return decode(DECLARATIONS.{}, json);\n}}\n", name, name));
+        runtime.push_str(&format!("\nexport function encode{}(value) {{\n    return encode(DECLARATIONS.{}, value);\n}}\n", name, name));
+    }
+    Ok(TypeScript { declarations, runtime })
+}
+
+// The top-level functions, classes and constants of the runtime.
+fn runtime_names() -> Vec<&'static str> {
+    RUNTIME.lines().filter_map(|line| {
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let rest = line.strip_prefix("function ").or_else(|| line.strip_prefix("class ")).or_else(|| line.strip_prefix("const "))?;
+        rest.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$')).next()
+    }).collect()
+}
+
+// Teon identifiers which are also JavaScript identifiers.
+fn is_js_identifier(name: &str) -> bool {
+    name.is_ascii() && is_identifier(name)
+}
+
+fn check_references(t: &Type, name: &str, types: &IndexMap<String, Type>) -> Result<()> {
+    match t {
+        Type::Reference(reference) if !types.contains_key(reference) => Err(Error::new(format!("Cannot generate typescript for `{}`, undeclared type `{}`", name, reference))),
+        Type::Optional(t) | Type::Array(t) | Type::Dictionary(t) | Type::Range(t) => check_references(t, name, types),
+        Type::Shape(fields) => fields.values().try_for_each(|t| check_references(t, name, types)),
+        Type::Tuple(items) | Type::Union(items) => items.iter().try_for_each(|t| check_references(t, name, types)),
+        _ => Ok(()),
+    }
+}
+
+fn property_name(key: &str) -> String {
+    if is_js_identifier(key) { key.to_owned() } else { serde_json::to_string(key).unwrap() }
+}
+
+fn field_declaration(key: &str, t: &Type) -> String {
+    match t {
+        Type::Optional(inner) => format!("{}?: {} | null", property_name(key), type_expression(inner)),
+        t => format!("{}: {}", property_name(key), type_expression(t)),
+    }
+}
+
+fn type_expression(t: &Type) -> String {
+    match t {
+        Type::Any => "unknown".to_owned(),
+        Type::Null => "null".to_owned(),
+        Type::Bool => "boolean".to_owned(),
+        Type::Int | Type::Int64 | Type::Float32 | Type::Float => "number".to_owned(),
+        Type::Decimal => "Decimal".to_owned(),
+        Type::ObjectId | Type::String => "string".to_owned(),
+        Type::Date | Type::DateTime => "Date".to_owned(),
+        Type::Bytes => "Uint8Array".to_owned(),
+        Type::File => "TeonFile".to_owned(),
+        Type::Regex => "RegExp".to_owned(),
+        Type::OptionVariant => "OptionVariant".to_owned(),
+        Type::Optional(inner) => format!("{} | null", type_expression(inner)),
+        Type::Array(inner) => match inner.as_ref() {
+            Type::Optional(_) | Type::Union(_) | Type::Enum(_) => format!("({})[]", type_expression(inner)),
+            inner => format!("{}[]", type_expression(inner)),
+        },
+        Type::Dictionary(inner) => format!("Record<string, {}>", type_expression(inner)),
+        Type::Shape(fields) if fields.is_empty() => "{}".to_owned(),
+        Type::Shape(fields) => format!("{{ {} }}", fields.iter().map(|(k, t)| field_declaration(k, t)).collect::<Vec<_>>().join("; ")),
+        Type::Tuple(items) => format!("[{}]", items.iter().map(type_expression).collect::<Vec<_>>().join(", ")),
+        Type::Range(inner) => format!("Range<{}>", type_expression(inner)),
+        Type::Enum(names) if names.is_empty() => "never".to_owned(),
+        Type::Enum(names) => names.iter().map(|n| serde_json::to_string(n).unwrap()).collect::<Vec<_>>().join(" | "),
+        Type::Union(members) if members.is_empty() => "never".to_owned(),
+        Type::Union(members) => members.iter().map(type_expression).collect::<Vec<_>>().join(" | "),
+        Type::Reference(name) => name.clone(),
+    }
+}
+
+// The runtime description of a type interpreted by the generated decoders and encoders.
+fn descriptor(t: &Type) -> JsonValue {
+    match t {
+        Type::Optional(inner) => json!({"optional": descriptor(inner)}),
+        Type::Array(inner) => json!({"array": descriptor(inner)}),
+        Type::Dictionary(inner) => json!({"dictionary": descriptor(inner)}),
+        Type::Shape(fields) => json!({"shape": fields.iter().map(|(k, t)| (k.clone(), descriptor(t))).collect::<Map<_, _>>()}),
+        Type::Tuple(items) => json!({"tuple": items.iter().map(descriptor).collect::<Vec<_>>()}),
+        Type::Range(inner) => json!({"range": descriptor(inner)}),
+        Type::Enum(names) => json!({"enum": names}),
+        Type::Union(members) => json!({"union": members.iter().map(descriptor).collect::<Vec<_>>()}),
+        Type::Reference(name) => json!({"ref": name}),
+        t => JsonValue::String(format!("{:?}", t)),
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Serialize, Serializer};
 use serde::ser::{SerializeMap};
 use chrono::SecondsFormat;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use crate::Value;
 
 impl Serialize for Value {
@@ -73,8 +75,11 @@ impl Serialize for Value {
                 map.end()
             }
             Value::Regex(r) => {
-                let mut map = serializer.serialize_map(Some(1))?;
-                map.serialize_entry("$regex", &r.to_string())?;
+                let mut map = serializer.serialize_map(Some(if r.flags().is_empty() { 1 } else { 2 }))?;
+                map.serialize_entry("$regex", r.pattern())?;
+                if !r.flags().is_empty() {
+                    map.serialize_entry("$options", r.flags())?;
+                }
                 map.end()
             }
             Value::File(f) => {
@@ -82,6 +87,11 @@ impl Serialize for Value {
                 map.serialize_entry("$file", &f)?;
                 map.end()
             }
+            Value::Bytes(b) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("$bytes", &BASE64.encode(b))?;
+                map.end()
+            }
         }
     }
 }
@@ -0,0 +1,473 @@
+//! A value whose nested data is reference counted.
+//!
+//! [`SharedValue`] mirrors [`Value`], but strings, bytes, arrays, dictionaries and the other
+//! heap allocated variants are held in `Arc`s. Cloning a shared value, or any value nested in
+//! it, is O(1) regardless of its size, so large results can be handed between pipeline stages
+//! and threads without deep copies. Accessors borrow, and the `_mut` accessors copy on write:
+//! only a container which is shared with another clone is copied, and only one level deep.
+//!
+//! ```
+//! # use teo_teon::{teon, Value};
+//! # use teo_teon::shared::SharedValue;
+//! #
+//! let shared = SharedValue::from(teon!({"users": [{"name": "Ann"}, {"name": "Bob"}]}));
+//! let users = shared.get("users").unwrap().clone();
+//! assert!(users.ptr_eq(shared.get("users").unwrap()));
+//! assert_eq!(users.get(1).and_then(|u| u.get("name")).and_then(|n| n.as_str()), Some("Bob"));
+//!
+//! let mut changed = shared.clone();
+//! changed.as_dictionary_mut().unwrap().insert("count".to_owned(), SharedValue::Int(2));
+//! assert!(changed.get("users").unwrap().ptr_eq(&users));
+//! assert_eq!(Value::from(changed), teon!({"users": [{"name": "Ann"}, {"name": "Bob"}], "count": 2}));
+//! assert_eq!(shared.as_dictionary().unwrap().len(), 1);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::option_variant::OptionVariant;
+use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+/// A [`Value`] with reference counted nested data. See the [module documentation](self).
+///
+/// Equality follows [`Value`]: numbers of different variants are equal when their values are.
+#[derive(Debug, Clone)]
+pub enum SharedValue {
+    Null,
+    Bool(bool),
+    Int(i32),
+    Int64(i64),
+    Float32(f32),
+    Float(f64),
+    Decimal(Arc<BigDecimal>),
+    ObjectId(ObjectId),
+    String(Arc<str>),
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+    Array(Arc<Vec<SharedValue>>),
+    Dictionary(Arc<IndexMap<String, SharedValue>>),
+    Range(Arc<Range>),
+    Tuple(Arc<Vec<SharedValue>>),
+    EnumVariant(Arc<EnumVariant>),
+    OptionVariant(Arc<OptionVariant>),
+    Regex(Arc<TeonRegex>),
+    File(Arc<File>),
+    Bytes(Arc<[u8]>),
+}
+
+impl SharedValue {
+
+    // Access
+
+    pub fn get<I: Index>(&self, index: I) -> Option<&SharedValue> {
+        index.index_into(self)
+    }
+
+    /// Get a nested value by a dotted path like `"user.posts.0.title"`, as
+    /// [`Value::get_path`] does.
+    pub fn get_path(&self, path: &str) -> Option<&SharedValue> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        let mut current = self;
+        for segment in path.split('.') {
+            current = match current {
+                SharedValue::Array(_) | SharedValue::Tuple(_) => current.get(segment.parse::<usize>().ok()?)?,
+                _ => current.get(segment)?,
+            };
+        }
+        Some(current)
+    }
+
+    /// Whether both values hold the same allocation, so that they are clones of each other.
+    /// Always false for variants without heap data.
+    pub fn ptr_eq(&self, other: &SharedValue) -> bool {
+        use SharedValue::*;
+        match (self, other) {
+            (Decimal(s), Decimal(o)) => Arc::ptr_eq(s, o),
+            (String(s), String(o)) => Arc::ptr_eq(s, o),
+            (Array(s), Array(o)) | (Tuple(s), Tuple(o)) => Arc::ptr_eq(s, o),
+            (Dictionary(s), Dictionary(o)) => Arc::ptr_eq(s, o),
+            (Range(s), Range(o)) => Arc::ptr_eq(s, o),
+            (EnumVariant(s), EnumVariant(o)) => Arc::ptr_eq(s, o),
+            (OptionVariant(s), OptionVariant(o)) => Arc::ptr_eq(s, o),
+            (Regex(s), Regex(o)) => Arc::ptr_eq(s, o),
+            (File(s), File(o)) => Arc::ptr_eq(s, o),
+            (Bytes(s), Bytes(o)) => Arc::ptr_eq(s, o),
+            _ => false,
+        }
+    }
+
+    /// A deep copy as a [`Value`]. Use `Value::from` to reuse allocations which aren't shared.
+    pub fn to_value(&self) -> Value {
+        Value::from(self.clone())
+    }
+
+    // Value
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, SharedValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SharedValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i32> {
+        match self {
+            SharedValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_int64(&self) -> Option<i64> {
+        match self {
+            SharedValue::Int64(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_float32(&self) -> Option<f32> {
+        match self {
+            SharedValue::Float32(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            SharedValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal(&self) -> Option<&BigDecimal> {
+        match self {
+            SharedValue::Decimal(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_id(&self) -> Option<&ObjectId> {
+        match self {
+            SharedValue::ObjectId(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SharedValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<&NaiveDate> {
+        match self {
+            SharedValue::Date(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn as_datetime(&self) -> Option<&DateTime<Utc>> {
+        match self {
+            SharedValue::DateTime(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[SharedValue]> {
+        match self {
+            SharedValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The elements of an array, copied first if the array is shared.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<SharedValue>> {
+        match self {
+            SharedValue::Array(items) => Some(Arc::make_mut(items)),
+            _ => None,
+        }
+    }
+
+    pub fn as_dictionary(&self) -> Option<&IndexMap<String, SharedValue>> {
+        match self {
+            SharedValue::Dictionary(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// The entries of a dictionary, copied first if the dictionary is shared.
+    pub fn as_dictionary_mut(&mut self) -> Option<&mut IndexMap<String, SharedValue>> {
+        match self {
+            SharedValue::Dictionary(map) => Some(Arc::make_mut(map)),
+            _ => None,
+        }
+    }
+
+    pub fn as_range(&self) -> Option<&Range> {
+        match self {
+            SharedValue::Range(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    pub fn as_tuple(&self) -> Option<&[SharedValue]> {
+        match self {
+            SharedValue::Tuple(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_enum_variant(&self) -> Option<&EnumVariant> {
+        match self {
+            SharedValue::EnumVariant(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub fn as_option_variant(&self) -> Option<&OptionVariant> {
+        match self {
+            SharedValue::OptionVariant(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    pub fn as_teon_regex(&self) -> Option<&TeonRegex> {
+        match self {
+            SharedValue::Regex(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    pub fn as_file(&self) -> Option<&File> {
+        match self {
+            SharedValue::File(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            SharedValue::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn is_any_int(&self) -> bool {
+        matches!(self, SharedValue::Int(_) | SharedValue::Int64(_))
+    }
+
+    fn to_int64(&self) -> Option<i64> {
+        match self {
+            SharedValue::Int(i) => Some(*i as i64),
+            SharedValue::Int64(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn to_float(&self) -> Option<f64> {
+        match self {
+            SharedValue::Int(i) => Some(*i as f64),
+            SharedValue::Int64(i) => Some(*i as f64),
+            SharedValue::Float32(f) => Some(*f as f64),
+            SharedValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn type_hint(&self) -> &str {
+        match self {
+            SharedValue::Null => "Null",
+            SharedValue::Bool(_) => "Bool",
+            SharedValue::Int(_) => "Int",
+            SharedValue::Int64(_) => "Int64",
+            SharedValue::Float32(_) => "Float32",
+            SharedValue::Float(_) => "Float",
+            SharedValue::Decimal(_) => "Decimal",
+            SharedValue::ObjectId(_) => "ObjectId",
+            SharedValue::String(_) => "String",
+            SharedValue::Date(_) => "Date",
+            SharedValue::DateTime(_) => "DateTime",
+            SharedValue::Array(_) => "Array",
+            SharedValue::Dictionary(_) => "Dictionary",
+            SharedValue::Range(_) => "Range",
+            SharedValue::Tuple(_) => "Tuple",
+            SharedValue::EnumVariant(_) => "EnumVariant",
+            SharedValue::OptionVariant(_) => "OptionVariant",
+            SharedValue::Regex(_) => "RegExp",
+            SharedValue::File(_) => "File",
+            SharedValue::Bytes(_) => "Bytes",
+        }
+    }
+}
+
+impl PartialEq for SharedValue {
+
+    fn eq(&self, other: &Self) -> bool {
+        use SharedValue::*;
+        if self.is_any_int() && other.is_any_int() {
+            return self.to_int64() == other.to_int64();
+        }
+        if let (Some(s), Some(o)) = (self.to_float(), other.to_float()) {
+            return s == o;
+        }
+        match (self, other) {
+            (Null, Null) => true,
+            (Bool(s), Bool(o)) => s == o,
+            (Decimal(s), Decimal(o)) => s == o,
+            (ObjectId(s), ObjectId(o)) => s == o,
+            (String(s), String(o)) => s == o,
+            (Date(s), Date(o)) => s == o,
+            (DateTime(s), DateTime(o)) => s == o,
+            (Array(s), Array(o)) => s == o,
+            (Dictionary(s), Dictionary(o)) => s == o,
+            (Range(s), Range(o)) => s == o,
+            (Tuple(s), Tuple(o)) => s == o,
+            (EnumVariant(s), EnumVariant(o)) => s == o,
+            (OptionVariant(s), OptionVariant(o)) => s.value == o.value,
+            (Regex(s), Regex(o)) => s == o,
+            (File(s), File(o)) => s == o,
+            (Bytes(s), Bytes(o)) => s == o,
+            _ => false,
+        }
+    }
+}
+
+impl From<Value> for SharedValue {
+
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => SharedValue::Null,
+            Value::Bool(b) => SharedValue::Bool(b),
+            Value::Int(i) => SharedValue::Int(i),
+            Value::Int64(i) => SharedValue::Int64(i),
+            Value::Float32(f) => SharedValue::Float32(f),
+            Value::Float(f) => SharedValue::Float(f),
+            Value::Decimal(d) => SharedValue::Decimal(Arc::new(d)),
+            Value::ObjectId(o) => SharedValue::ObjectId(o),
+            Value::String(s) => SharedValue::String(Arc::from(s)),
+            Value::Date(d) => SharedValue::Date(d),
+            Value::DateTime(d) => SharedValue::DateTime(d),
+            Value::Array(items) => SharedValue::Array(Arc::new(items.into_iter().map(SharedValue::from).collect())),
+            Value::Dictionary(map) => SharedValue::Dictionary(Arc::new(map.into_iter().map(|(k, v)| (k, SharedValue::from(v))).collect())),
+            Value::Range(r) => SharedValue::Range(Arc::new(r)),
+            Value::Tuple(items) => SharedValue::Tuple(Arc::new(items.into_iter().map(SharedValue::from).collect())),
+            Value::EnumVariant(e) => SharedValue::EnumVariant(Arc::new(e)),
+            Value::OptionVariant(o) => SharedValue::OptionVariant(Arc::new(o)),
+            Value::Regex(r) => SharedValue::Regex(Arc::new(r)),
+            Value::File(f) => SharedValue::File(Arc::new(f)),
+            Value::Bytes(b) => SharedValue::Bytes(Arc::from(b)),
+        }
+    }
+}
+
+impl From<&Value> for SharedValue {
+
+    fn from(value: &Value) -> Self {
+        SharedValue::from(value.clone())
+    }
+}
+
+impl From<SharedValue> for Value {
+
+    fn from(value: SharedValue) -> Self {
+        match value {
+            SharedValue::Null => Value::Null,
+            SharedValue::Bool(b) => Value::Bool(b),
+            SharedValue::Int(i) => Value::Int(i),
+            SharedValue::Int64(i) => Value::Int64(i),
+            SharedValue::Float32(f) => Value::Float32(f),
+            SharedValue::Float(f) => Value::Float(f),
+            SharedValue::Decimal(d) => Value::Decimal(Arc::unwrap_or_clone(d)),
+            SharedValue::ObjectId(o) => Value::ObjectId(o),
+            SharedValue::String(s) => Value::String(s.to_string()),
+            SharedValue::Date(d) => Value::Date(d),
+            SharedValue::DateTime(d) => Value::DateTime(d),
+            SharedValue::Array(items) => Value::Array(Arc::unwrap_or_clone(items).into_iter().map(Value::from).collect()),
+            SharedValue::Dictionary(map) => Value::Dictionary(Arc::unwrap_or_clone(map).into_iter().map(|(k, v)| (k, Value::from(v))).collect()),
+            SharedValue::Range(r) => Value::Range(Arc::unwrap_or_clone(r)),
+            SharedValue::Tuple(items) => Value::Tuple(Arc::unwrap_or_clone(items).into_iter().map(Value::from).collect()),
+            SharedValue::EnumVariant(e) => Value::EnumVariant(Arc::unwrap_or_clone(e)),
+            SharedValue::OptionVariant(o) => Value::OptionVariant(Arc::unwrap_or_clone(o)),
+            SharedValue::Regex(r) => Value::Regex(Arc::unwrap_or_clone(r)),
+            SharedValue::File(f) => Value::File(Arc::unwrap_or_clone(f)),
+            SharedValue::Bytes(b) => Value::Bytes(b.to_vec()),
+        }
+    }
+}
+
+impl From<&SharedValue> for Value {
+
+    fn from(value: &SharedValue) -> Self {
+        value.to_value()
+    }
+}
+
+/// Formats like the equivalent [`Value`], including the pretty printed alternate form.
+impl Display for SharedValue {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.to_value(), f)
+    }
+}
+
+/// Types which index into a [`SharedValue`], like [`crate::index::Index`] for [`Value`].
+pub trait Index {
+    fn index_into<'v>(&self, v: &'v SharedValue) -> Option<&'v SharedValue>;
+}
+
+impl Index for usize {
+
+    fn index_into<'v>(&self, v: &'v SharedValue) -> Option<&'v SharedValue> {
+        match v {
+            SharedValue::Array(items) | SharedValue::Tuple(items) => items.get(*self),
+            _ => None,
+        }
+    }
+}
+
+impl Index for str {
+
+    fn index_into<'v>(&self, v: &'v SharedValue) -> Option<&'v SharedValue> {
+        match v {
+            SharedValue::Dictionary(map) => map.get(self),
+            _ => None,
+        }
+    }
+}
+
+impl Index for String {
+
+    fn index_into<'v>(&self, v: &'v SharedValue) -> Option<&'v SharedValue> {
+        self[..].index_into(v)
+    }
+}
+
+impl<T> Index for &T where T: ?Sized + Index {
+
+    fn index_into<'v>(&self, v: &'v SharedValue) -> Option<&'v SharedValue> {
+        (**self).index_into(v)
+    }
+}
+
+impl<I> std::ops::Index<I> for SharedValue where I: Index {
+
+    type Output = SharedValue;
+
+    fn index(&self, index: I) -> &SharedValue {
+        static NULL: SharedValue = SharedValue::Null;
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
@@ -0,0 +1,612 @@
+//! Incremental JSON parsing directly into Teon values.
+//!
+//! [`JsonReader`] reads from any [`Read`] without building a `serde_json::Value` tree first. It
+//! produces a stream of [`JsonEvent`]s, whole values with [`JsonReader::read_value`], or the
+//! elements of a top-level array one at a time with [`JsonReader::elements`].
+//!
+//! ```
+//! # use teo_teon::teon;
+//! # use teo_teon::stream::JsonReader;
+//! #
+//! let input = r#"[{"id": 1, "at": {"$date": "2024-01-01"}}, {"id": 2}]"#;
+//! let mut reader = JsonReader::tagged(input.as_bytes());
+//! let records: Vec<_> = reader.elements().unwrap().collect::<Result<_, _>>().unwrap();
+//! assert_eq!(records.len(), 2);
+//! assert!(records[0].get("at").unwrap().is_date());
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufReader, ErrorKind, Read};
+use std::str::FromStr;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
+use serde_json::{Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::option_variant::OptionVariant;
+use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
+use crate::value::Value;
+
+const TAGS: [&str; 15] = [
+    "$int", "$int64", "$float32", "$float", "$decimal", "$objectId", "$date", "$datetime", "$range",
+    "$tuple", "$enumVariant", "$optionVariant", "$regex", "$file", "$bytes",
+];
+
+/// A token of a JSON document. Tagged values read by a [`JsonReader::tagged`] reader are
+/// reported as a single `Scalar`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    StartDictionary,
+    Key(String),
+    EndDictionary,
+    StartArray,
+    EndArray,
+    Scalar(Value),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    Array { first: bool },
+    Dictionary { first: bool, after_key: bool },
+    Tagged,
+}
+
+/// A streaming JSON parser producing Teon values.
+pub struct JsonReader<R: Read> {
+    inner: BufReader<R>,
+    peeked: Option<u8>,
+    position: usize,
+    stack: Vec<Frame>,
+    pending: VecDeque<JsonEvent>,
+    finished: bool,
+    tagged: bool,
+    max_depth: usize,
+}
+
+impl<R: Read> JsonReader<R> {
+
+    /// A reader for plain JSON. Integers become `Int64`, other numbers `Float`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            inner: BufReader::new(reader),
+            peeked: None,
+            position: 0,
+            stack: vec![],
+            pending: VecDeque::new(),
+            finished: false,
+            tagged: false,
+            max_depth: 128,
+        }
+    }
+
+    /// A reader which also decodes the `$` tagged dictionaries written by `Serialize for Value`,
+    /// like `{"$date": "2024-01-01"}` or `{"$decimal": "1.5"}`.
+    pub fn tagged(reader: R) -> Self {
+        Self { tagged: true, ..Self::new(reader) }
+    }
+
+    /// Set the maximum nesting depth of arrays and dictionaries, 128 by default. Deeper input
+    /// is an error.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// The number of bytes consumed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Read the next event, or `None` at the end of the input. Trailing characters after the
+    /// top-level value are an error.
+    pub fn next_event(&mut self) -> Result<Option<JsonEvent>> {
+        if let Some(event) = self.pending.pop_front() {
+            return Ok(Some(event));
+        }
+        self.skip_whitespace()?;
+        let event = match self.stack.last().copied() {
+            None if self.finished => return match self.peek()? {
+                None => Ok(None),
+                Some(_) => Err(self.error("trailing characters")),
+            },
+            None | Some(Frame::Tagged) => self.value_start()?,
+            Some(Frame::Array { first }) => {
+                if self.peek()? == Some(b']') {
+                    self.bump()?;
+                    self.stack.pop();
+                    self.complete();
+                    return Ok(Some(JsonEvent::EndArray));
+                }
+                if !first {
+                    self.expect(b',')?;
+                    self.skip_whitespace()?;
+                }
+                *self.stack.last_mut().unwrap() = Frame::Array { first: false };
+                self.value_start()?
+            }
+            Some(Frame::Dictionary { first, after_key }) => {
+                if after_key {
+                    *self.stack.last_mut().unwrap() = Frame::Dictionary { first: false, after_key: false };
+                    self.value_start()?
+                } else {
+                    if self.peek()? == Some(b'}') {
+                        self.bump()?;
+                        self.stack.pop();
+                        self.complete();
+                        return Ok(Some(JsonEvent::EndDictionary));
+                    }
+                    if !first {
+                        self.expect(b',')?;
+                        self.skip_whitespace()?;
+                    }
+                    let key = self.parse_key()?;
+                    *self.stack.last_mut().unwrap() = Frame::Dictionary { first: false, after_key: true };
+                    JsonEvent::Key(key)
+                }
+            }
+        };
+        Ok(Some(event))
+    }
+
+    /// Read the next complete value. Returns `None` at the end of the input, or when the
+    /// enclosing array or dictionary ends.
+    pub fn read_value(&mut self) -> Result<Option<Value>> {
+        match self.next_event()? {
+            None | Some(JsonEvent::EndArray) | Some(JsonEvent::EndDictionary) => Ok(None),
+            Some(JsonEvent::Key(key)) => Err(self.error(&format!("expect value, found key `{key}`"))),
+            Some(event) => Ok(Some(self.build(event)?)),
+        }
+    }
+
+    /// Iterate over the elements of the array at the current position, reading each one only
+    /// when it's requested.
+    pub fn elements(&mut self) -> Result<Elements<'_, R>> {
+        match self.next_event()? {
+            Some(JsonEvent::StartArray) => Ok(Elements { reader: self, done: false }),
+            _ => Err(self.error("expect array")),
+        }
+    }
+
+    fn build(&mut self, event: JsonEvent) -> Result<Value> {
+        match event {
+            JsonEvent::Scalar(value) => Ok(value),
+            JsonEvent::StartArray => {
+                let mut items = vec![];
+                loop {
+                    match self.expect_event()? {
+                        JsonEvent::EndArray => return Ok(Value::Array(items)),
+                        event => items.push(self.build(event)?),
+                    }
+                }
+            }
+            JsonEvent::StartDictionary => {
+                let mut map = IndexMap::new();
+                loop {
+                    match self.expect_event()? {
+                        JsonEvent::EndDictionary => return Ok(Value::Dictionary(map)),
+                        JsonEvent::Key(key) => {
+                            let event = self.expect_event()?;
+                            map.insert(key, self.build(event)?);
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            _ => Err(self.error("unexpected end of container")),
+        }
+    }
+
+    fn expect_event(&mut self) -> Result<JsonEvent> {
+        self.next_event()?.ok_or_else(|| self.error("unexpected end of input"))
+    }
+
+    fn complete(&mut self) {
+        if self.stack.is_empty() {
+            self.finished = true;
+        }
+    }
+
+    fn value_start(&mut self) -> Result<JsonEvent> {
+        match self.peek()? {
+            Some(b'{') => {
+                self.check_depth()?;
+                self.bump()?;
+                self.skip_whitespace()?;
+                if self.peek()? == Some(b'"') {
+                    let key = self.parse_key()?;
+                    if self.tagged && key == "$options" {
+                        return self.options_first();
+                    }
+                    if self.tagged && TAGS.contains(&key.as_str()) {
+                        let value = self.read_tagged(IndexMap::new(), key)?;
+                        self.complete();
+                        return Ok(JsonEvent::Scalar(value));
+                    }
+                    self.stack.push(Frame::Dictionary { first: false, after_key: true });
+                    self.pending.push_back(JsonEvent::Key(key));
+                } else {
+                    self.stack.push(Frame::Dictionary { first: true, after_key: false });
+                }
+                Ok(JsonEvent::StartDictionary)
+            }
+            Some(b'[') => {
+                self.check_depth()?;
+                self.bump()?;
+                self.stack.push(Frame::Array { first: true });
+                Ok(JsonEvent::StartArray)
+            }
+            Some(b'"') => {
+                let string = self.parse_string()?;
+                self.complete();
+                Ok(JsonEvent::Scalar(Value::String(string)))
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let number = self.parse_number()?;
+                self.complete();
+                Ok(JsonEvent::Scalar(number))
+            }
+            Some(b'a'..=b'z') => {
+                let mut word = String::new();
+                while let Some(b @ b'a'..=b'z') = self.peek()? {
+                    word.push(b as char);
+                    self.bump()?;
+                }
+                let value = match word.as_str() {
+                    "null" => Value::Null,
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    _ => Err(self.error(&format!("unexpected `{word}`")))?,
+                };
+                self.complete();
+                Ok(JsonEvent::Scalar(value))
+            }
+            Some(_) => Err(self.error("expect value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn check_depth(&self) -> Result<()> {
+        if self.stack.len() >= self.max_depth {
+            Err(self.error(&format!("nesting exceeds the maximum depth of {}", self.max_depth)))?
+        }
+        Ok(())
+    }
+
+    // Reads a value inside a dictionary which may be tagged.
+    fn read_tagged_value(&mut self) -> Result<Value> {
+        self.stack.push(Frame::Tagged);
+        let event = self.expect_event()?;
+        let value = self.build(event)?;
+        self.stack.pop();
+        Ok(value)
+    }
+
+    // Reads the rest of a dictionary starting with `$options`. It's a regex when `$regex`
+    // follows, otherwise its events are replayed as a plain dictionary.
+    fn options_first(&mut self) -> Result<JsonEvent> {
+        let options = self.read_tagged_value()?;
+        self.skip_whitespace()?;
+        let next = if self.peek()? == Some(b'}') {
+            self.bump()?;
+            None
+        } else {
+            self.expect(b',')?;
+            self.skip_whitespace()?;
+            Some(self.parse_key()?)
+        };
+        if next.as_deref() == Some("$regex") {
+            let map = IndexMap::from([("$options".to_owned(), options)]);
+            let value = self.read_tagged(map, "$regex".to_owned())?;
+            self.complete();
+            return Ok(JsonEvent::Scalar(value));
+        }
+        self.pending.push_back(JsonEvent::Key("$options".to_owned()));
+        push_events(&mut self.pending, options);
+        match next {
+            Some(key) => {
+                self.stack.push(Frame::Dictionary { first: false, after_key: true });
+                self.pending.push_back(JsonEvent::Key(key));
+            }
+            None => {
+                self.pending.push_back(JsonEvent::EndDictionary);
+                self.complete();
+            }
+        }
+        Ok(JsonEvent::StartDictionary)
+    }
+
+    // Reads the rest of a tagged dictionary after its tag, with the entries read before it.
+    fn read_tagged(&mut self, mut map: IndexMap<String, Value>, tag: String) -> Result<Value> {
+        let mut key = tag;
+        loop {
+            let value = self.read_tagged_value()?;
+            map.insert(key, value);
+            self.skip_whitespace()?;
+            if self.peek()? == Some(b'}') {
+                self.bump()?;
+                break;
+            }
+            self.expect(b',')?;
+            self.skip_whitespace()?;
+            key = self.parse_key()?;
+        }
+        decode_tagged(map)
+    }
+
+    // Parses a dictionary key and the following colon.
+    fn parse_key(&mut self) -> Result<String> {
+        if self.peek()? != Some(b'"') {
+            return Err(self.error("expect key"));
+        }
+        let key = self.parse_string()?;
+        self.skip_whitespace()?;
+        self.expect(b':')?;
+        Ok(key)
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+        loop {
+            match self.bump()? {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => match self.bump()? {
+                    Some(b'"') => bytes.push(b'"'),
+                    Some(b'\\') => bytes.push(b'\\'),
+                    Some(b'/') => bytes.push(b'/'),
+                    Some(b'b') => bytes.push(0x08),
+                    Some(b'f') => bytes.push(0x0c),
+                    Some(b'n') => bytes.push(b'\n'),
+                    Some(b'r') => bytes.push(b'\r'),
+                    Some(b't') => bytes.push(b'\t'),
+                    Some(b'u') => {
+                        let mut code = self.parse_hex4()?;
+                        if (0xd800..0xdc00).contains(&code) {
+                            if self.bump()? != Some(b'\\') || self.bump()? != Some(b'u') {
+                                return Err(self.error("invalid surrogate pair"));
+                            }
+                            let low = self.parse_hex4()?;
+                            if !(0xdc00..0xe000).contains(&low) {
+                                return Err(self.error("invalid surrogate pair"));
+                            }
+                            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                        }
+                        let c = char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?;
+                        bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    }
+                    _ => return Err(self.error("invalid escape")),
+                },
+                Some(b) if b < 0x20 => return Err(self.error("control character in string")),
+                Some(b) => bytes.push(b),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid utf-8 in string"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.bump()?.and_then(|b| (b as char).to_digit(16)).ok_or_else(|| self.error("invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<Value> {
+        let mut text = String::new();
+        while let Some(b @ (b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) = self.peek()? {
+            text.push(b as char);
+            self.bump()?;
+        }
+        if !is_json_number(&text) {
+            return Err(self.error(&format!("invalid number `{text}`")));
+        }
+        if !text.contains(['.', 'e', 'E']) {
+            if let Ok(i) = text.parse::<i64>() {
+                return Ok(Value::Int64(i));
+            }
+        }
+        text.parse::<f64>().map(Value::Float).map_err(|_| self.error(&format!("invalid number `{text}`")))
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        match self.peek()? {
+            Some(b) if b == expected => {
+                self.bump()?;
+                Ok(())
+            }
+            Some(_) => Err(self.error(&format!("expect `{}`", expected as char))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        if self.peeked.is_none() {
+            let mut buf = [0u8; 1];
+            loop {
+                match self.inner.read(&mut buf) {
+                    Ok(0) => return Ok(None),
+                    Ok(_) => break,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(Error::new(format!("json error at {}: {}", self.position, e))),
+                }
+            }
+            self.peeked = Some(buf[0]);
+        }
+        Ok(self.peeked)
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>> {
+        let byte = self.peek()?;
+        if byte.is_some() {
+            self.peeked = None;
+            self.position += 1;
+        }
+        Ok(byte)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::new(format!("json error at {}: {}", self.position, message))
+    }
+}
+
+impl<R: Read> Iterator for JsonReader<R> {
+
+    type Item = Result<JsonEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+/// The elements of an array, see [`JsonReader::elements`].
+pub struct Elements<'a, R: Read> {
+    reader: &'a mut JsonReader<R>,
+    done: bool,
+}
+
+impl<'a, R: Read> Iterator for Elements<'a, R> {
+
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.reader.read_value().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+fn is_json_number(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < bytes.len() && bytes[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i - start
+    };
+    if bytes.first() == Some(&b'-') {
+        i += 1;
+    }
+    match digits(&mut i) {
+        0 => return false,
+        n if n > 1 && bytes[i - n] == b'0' => return false,
+        _ => (),
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    i == bytes.len()
+}
+
+// The events of a value which was read while looking for a tag.
+fn push_events(events: &mut VecDeque<JsonEvent>, value: Value) {
+    match value {
+        Value::Array(items) => {
+            events.push_back(JsonEvent::StartArray);
+            for item in items {
+                push_events(events, item);
+            }
+            events.push_back(JsonEvent::EndArray);
+        }
+        Value::Dictionary(map) => {
+            events.push_back(JsonEvent::StartDictionary);
+            for (key, item) in map {
+                events.push_back(JsonEvent::Key(key));
+                push_events(events, item);
+            }
+            events.push_back(JsonEvent::EndDictionary);
+        }
+        value => events.push_back(JsonEvent::Scalar(value)),
+    }
+}
+
+fn decode_tagged(mut map: IndexMap<String, Value>) -> Result<Value> {
+    let index = map.get_index_of("$regex").unwrap_or(0);
+    let (tag, payload) = map.shift_remove_index(index).unwrap();
+    let options = if tag == "$regex" { map.shift_remove("$options") } else { None };
+    if let Some(key) = map.keys().next() {
+        return Err(Error::new(format!("unexpected key `{key}` in `{tag}` value")));
+    }
+    let invalid = || Error::new(format!("invalid `{tag}` value {payload}"));
+    let string = || payload.as_str().ok_or_else(invalid);
+    Ok(match tag.as_str() {
+        "$int" => Value::Int(payload.to_int64().and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?),
+        "$int64" => Value::Int64(payload.to_int64().ok_or_else(invalid)?),
+        "$float32" => Value::Float32(payload.to_float32().ok_or_else(invalid)?),
+        "$float" => Value::Float(payload.to_float().ok_or_else(invalid)?),
+        "$decimal" => Value::Decimal(BigDecimal::from_str(string()?).map_err(|_| invalid())?),
+        "$objectId" => Value::ObjectId(ObjectId::parse_str(string()?).map_err(|_| invalid())?),
+        "$date" => Value::Date(NaiveDate::parse_from_str(string()?, "%Y-%m-%d").map_err(|_| invalid())?),
+        "$datetime" => Value::DateTime(DateTime::parse_from_rfc3339(string()?).map_err(|_| invalid())?.with_timezone(&Utc)),
+        "$range" => {
+            let range = payload.as_dictionary().ok_or_else(invalid)?;
+            Value::Range(Range {
+                closed: range.get("closed").and_then(Value::as_bool).ok_or_else(invalid)?,
+                start: Box::new(range.get("start").cloned().ok_or_else(invalid)?),
+                end: Box::new(range.get("end").cloned().ok_or_else(invalid)?),
+            })
+        }
+        "$tuple" => Value::Tuple(payload.as_array().cloned().ok_or_else(invalid)?),
+        "$enumVariant" => {
+            let variant = payload.as_dictionary().ok_or_else(invalid)?;
+            Value::EnumVariant(EnumVariant {
+                value: variant.get("value").and_then(Value::as_str).ok_or_else(invalid)?.to_owned(),
+                args: match variant.get("args") {
+                    None | Some(Value::Null) => None,
+                    Some(Value::Dictionary(args)) => Some(args.clone().into_iter().collect::<BTreeMap<_, _>>()),
+                    Some(_) => Err(invalid())?,
+                },
+            })
+        }
+        "$optionVariant" => {
+            let variant = payload.as_dictionary().ok_or_else(invalid)?;
+            Value::OptionVariant(OptionVariant {
+                value: variant.get("value").and_then(Value::to_int64).and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?,
+                display: variant.get("display").and_then(Value::as_str).ok_or_else(invalid)?.to_owned(),
+            })
+        }
+        "$regex" => {
+            let flags = match &options {
+                None => "",
+                Some(options) => options.as_str().ok_or_else(invalid)?,
+            };
+            Value::Regex(TeonRegex::new(string()?, flags)?)
+        }
+        "$file" => Value::File(File::try_from(&JsonValue::try_from(&payload)?)?),
+        "$bytes" => Value::Bytes(BASE64.decode(string()?).map_err(|_| invalid())?),
+        _ => unreachable!(),
+    })
+}
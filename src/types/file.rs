@@ -1,10 +1,18 @@
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use itertools::Itertools;
 use maplit::hashset;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use serde_json::{Value as JsonValue};
-use teo_result::Error;
+use teo_result::{Error, Result};
+use crate::utils::mime::{mime_from_extension, mime_from_magic_bytes, SIGNATURE_LEN};
+use crate::utils::sha256::{Sha256, to_hex};
+use crate::utils::literal::write_string_literal;
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct File {
@@ -14,14 +22,182 @@ pub struct File {
     pub filename: String,
     #[serde(rename(serialize = "filenameExt"))]
     pub filename_ext: Option<String>,
+    /// The file content when it is held in memory rather than at `filepath`.
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_content")]
+    pub content: Option<Vec<u8>>,
+}
+
+fn serialize_content<S>(content: &Option<Vec<u8>>, serializer: S) -> std::result::Result<S::Ok, S::Error> where S: Serializer {
+    match content {
+        Some(content) => serializer.serialize_str(&BASE64.encode(content)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn guess_content_type(filename_ext: Option<&str>, head: &[u8]) -> Option<String> {
+    filename_ext.and_then(mime_from_extension).or_else(|| mime_from_magic_bytes(head)).map(ToOwned::to_owned)
+}
+
+impl File {
+
+    /// The maximum size of in-memory content accepted when converting from json.
+    pub const DEFAULT_MAX_CONTENT_SIZE: usize = 16 * 1024 * 1024;
+
+    /// Create a file value from a path on disk.
+    ///
+    /// The filename and extension are taken from the path. The content type is guessed from the
+    /// extension, and from the leading bytes of the file when the extension is unknown.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<File> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path).map_err(|e| Error::new(format!("Cannot read file `{}`: {}", path.display(), e)))?;
+        if !metadata.is_file() {
+            Err(Error::new(format!("Cannot read file `{}`: not a regular file", path.display())))?
+        }
+        let filename = match path.file_name() {
+            Some(filename) => filename.to_string_lossy().to_string(),
+            None => Err(Error::new(format!("Cannot read file `{}`: missing filename", path.display())))?,
+        };
+        let filename_ext = path.extension().map(|ext| ext.to_string_lossy().to_string());
+        let mut head = Vec::with_capacity(SIGNATURE_LEN);
+        if filename_ext.as_deref().and_then(mime_from_extension).is_none() {
+            fs::File::open(path)
+                .and_then(|file| file.take(SIGNATURE_LEN as u64).read_to_end(&mut head))
+                .map_err(|e| Error::new(format!("Cannot read file `{}`: {}", path.display(), e)))?;
+        }
+        Ok(File {
+            filepath: path.to_string_lossy().to_string(),
+            content_type: guess_content_type(filename_ext.as_deref(), &head),
+            filename,
+            filename_ext,
+            content: None,
+        })
+    }
+
+    /// Create a file value which holds its content in memory.
+    ///
+    /// The content type is guessed from the extension of `filename`, and from the leading bytes
+    /// of the content when the extension is unknown.
+    pub fn from_bytes(filename: impl Into<String>, content: Vec<u8>) -> File {
+        let filename = filename.into();
+        let filename_ext = Path::new(&filename).extension().map(|ext| ext.to_string_lossy().to_string());
+        File {
+            filepath: String::new(),
+            content_type: guess_content_type(filename_ext.as_deref(), &content),
+            filename,
+            filename_ext,
+            content: Some(content),
+        }
+    }
+
+    /// Whether the file content is held in memory rather than on disk.
+    pub fn is_in_memory(&self) -> bool {
+        self.content.is_some()
+    }
+
+    /// Open the underlying file for reading. In-memory files have no file on disk, use
+    /// `reader` to read either kind.
+    pub fn open(&self) -> Result<fs::File> {
+        if self.is_in_memory() {
+            Err(Error::new(format!("Cannot open file `{}`: content is held in memory", self.filename)))?
+        }
+        fs::File::open(&self.filepath).map_err(|e| Error::new(format!("Cannot open file `{}`: {}", self.filepath, e)))
+    }
+
+    /// A reader over the file content, whether it is held in memory or on disk.
+    pub fn reader(&self) -> Result<Box<dyn Read + '_>> {
+        Ok(match &self.content {
+            Some(content) => Box::new(Cursor::new(content.as_slice())),
+            None => Box::new(self.open()?),
+        })
+    }
+
+    /// Read the whole file content.
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        if let Some(content) = &self.content {
+            return Ok(content.clone());
+        }
+        fs::read(&self.filepath).map_err(|e| Error::new(format!("Cannot read file `{}`: {}", self.filepath, e)))
+    }
+
+    /// The size of the file in bytes.
+    pub fn size(&self) -> Result<u64> {
+        if let Some(content) = &self.content {
+            return Ok(content.len() as u64);
+        }
+        let metadata = fs::metadata(&self.filepath).map_err(|e| Error::new(format!("Cannot read file `{}`: {}", self.filepath, e)))?;
+        Ok(metadata.len())
+    }
+
+    /// The hex encoded SHA-256 digest of the file content. The file is read in chunks, so large
+    /// files are never loaded into memory at once.
+    pub fn sha256(&self) -> Result<String> {
+        let mut file = self.reader()?;
+        let mut hasher = Sha256::new();
+        let mut buffer = [0u8; 8192];
+        loop {
+            let len = file.read(&mut buffer).map_err(|e| Error::new(format!("Cannot read {}: {}", self.description(), e)))?;
+            if len == 0 {
+                break;
+            }
+            hasher.update(&buffer[..len]);
+        }
+        Ok(to_hex(&hasher.finalize()))
+    }
+
+    // How errors refer to the file. In-memory files have no path, so they're named by filename.
+    fn description(&self) -> String {
+        match (self.is_in_memory(), self.filename.is_empty()) {
+            (false, _) => format!("file `{}`", self.filepath),
+            (true, false) => format!("in-memory file `{}`", self.filename),
+            (true, true) => "in-memory file".to_owned(),
+        }
+    }
+
+    /// Copy the file into `dir` under its filename, and return the file value of the copy.
+    ///
+    /// Neither `dir` nor the filename may contain `..` components, and the filename must be a
+    /// single plain path segment, so an uploaded filename can never escape `dir`.
+    pub fn persist_to(&self, dir: impl AsRef<Path>) -> Result<File> {
+        let dir = dir.as_ref();
+        if dir.components().any(|c| c == Component::ParentDir) {
+            Err(Error::new(format!("Cannot persist file into `{}`: parent directory components are not allowed", dir.display())))?
+        }
+        let mut components = Path::new(&self.filename).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => (),
+            _ => Err(Error::new(format!("Cannot persist file `{}`: invalid filename", self.filename)))?,
+        }
+        let destination: PathBuf = dir.join(&self.filename);
+        fs::create_dir_all(dir).map_err(|e| Error::new(format!("Cannot create directory `{}`: {}", dir.display(), e)))?;
+        match &self.content {
+            Some(content) => fs::write(&destination, content).map_err(|e| Error::new(format!("Cannot persist file `{}` to `{}`: {}", self.filename, destination.display(), e)))?,
+            None => {
+                fs::copy(&self.filepath, &destination).map_err(|e| Error::new(format!("Cannot persist file `{}` to `{}`: {}", self.filepath, destination.display(), e)))?;
+            }
+        }
+        Ok(File {
+            filepath: destination.to_string_lossy().to_string(),
+            content_type: self.content_type.clone(),
+            filename: self.filename.clone(),
+            filename_ext: self.filename_ext.clone(),
+            content: None,
+        })
+    }
 }
 
 impl Display for File {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("File(\"")?;
-        f.write_str(&self.filepath.as_str().replace("\"", "\\\""))?;
-        f.write_str("\")")
+        f.write_str("File(")?;
+        match &self.content {
+            Some(content) => {
+                f.write_str("memory ")?;
+                write_string_literal(f, &self.filename)?;
+                write!(f, ", {} bytes", content.len())?;
+            }
+            None => write_string_literal(f, &self.filepath)?,
+        }
+        f.write_str(")")
     }
 }
 
@@ -29,13 +205,40 @@ impl TryFrom<&JsonValue> for File {
 
     type Error = Error;
 
-    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+    fn try_from(value: &JsonValue) -> std::result::Result<Self, Self::Error> {
+        File::from_json_with_limit(value, File::DEFAULT_MAX_CONTENT_SIZE)
+    }
+}
+
+impl File {
+
+    /// Convert a json value into a file, rejecting in-memory content larger than
+    /// `max_content_size` bytes before it is decoded.
+    pub fn from_json_with_limit(value: &JsonValue, max_content_size: usize) -> Result<File> {
         if let Some(object) = value.as_object() {
             let keys_set: HashSet<&str> = object.keys().map(|k| k.as_str()).collect();
-            let difference: HashSet<&str> = keys_set.difference(&hashset!{"filepath", "contentType", "filename", "filenameExt"}).map(|s| *s).collect();
+            let difference: HashSet<&str> = keys_set.difference(&hashset!{"filepath", "contentType", "filename", "filenameExt", "content"}).map(|s| *s).collect();
             if !difference.is_empty() {
                 return Err(Error::new(format!("Connot convert json value to file, unexpected key {}", difference.iter().map(|k| format!("`{}`", *k)).join(", "))));
             }
+            let content = if let Some(content) = object.get("content") {
+                if let Some(content) = content.as_str() {
+                    if content.len() / 4 * 3 > max_content_size + 2 {
+                        Err(Error::new(format!("Cannot convert json value to file, content exceeds the size limit of {} bytes", max_content_size)))?
+                    }
+                    let content = BASE64.decode(content).map_err(|_| Error::new("Cannot convert json value to file, invalid value at `content`, expect base64 string"))?;
+                    if content.len() > max_content_size {
+                        Err(Error::new(format!("Cannot convert json value to file, content exceeds the size limit of {} bytes", max_content_size)))?
+                    }
+                    Some(content)
+                } else if content.is_null() {
+                    None
+                } else {
+                    Err(Error::new("Cannot convert json value to file, invalid value at `content`, expect base64 string"))?
+                }
+            } else {
+                None
+            };
             Ok(Self {
                 filepath: if let Some(filepath) = object.get("filepath") {
                     if let Some(filepath) = filepath.as_str() {
@@ -43,6 +246,8 @@ impl TryFrom<&JsonValue> for File {
                     } else {
                         Err(Error::new(format!("Cannot convert json value to file, invalid value at `filepath`, expect string")))?
                     }
+                } else if content.is_some() {
+                    String::new()
                 } else {
                     Err(Error::new(format!("Cannot convert json value to file, missing key `filepath`")))?
                 },
@@ -77,9 +282,32 @@ impl TryFrom<&JsonValue> for File {
                 } else {
                     None
                 },
+                content,
             })
         } else {
             Err(Error::new(format!("Cannot convert json value to file, value `{}` is not object", value)))
         }
     }
 }
+
+impl TryFrom<&File> for Vec<u8> {
+
+    type Error = Error;
+
+    fn try_from(value: &File) -> std::result::Result<Self, Self::Error> {
+        value.bytes()
+    }
+}
+
+impl TryFrom<File> for Vec<u8> {
+
+    type Error = Error;
+
+    fn try_from(value: File) -> std::result::Result<Self, Self::Error> {
+        match value.content {
+            Some(content) => Ok(content),
+            None => value.bytes(),
+        }
+    }
+}
+
@@ -1,4 +1,5 @@
 pub mod file;
 pub mod range;
 pub mod enum_variant;
-pub mod option_variant;
\ No newline at end of file
+pub mod option_variant;
+pub mod regex;
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use regex::{Regex, RegexBuilder};
+use serde_json::{Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::utils::literal::write_regex_literal;
+use crate::value::Value;
+
+/// A regular expression which keeps its source pattern and flags.
+///
+/// Supported flags are `i` (case-insensitive), `m` (multiline), `s` (dot matches new line) and
+/// `x` (ignore whitespace). Flags are stored sorted, so `/abc/mi` and `/abc/im` are equal.
+/// Escaped slashes are stored unescaped, so `a\/b` and `a/b` are equal, and a regex reads back
+/// equal from its display.
+#[derive(Debug, Clone)]
+pub struct TeonRegex {
+    pattern: String,
+    flags: String,
+    regex: Regex,
+}
+
+impl TeonRegex {
+
+    pub const SUPPORTED_FLAGS: &'static str = "imsx";
+
+    pub fn new(pattern: impl Into<String>, flags: &str) -> Result<Self> {
+        let pattern = unescape_slashes(pattern.into());
+        let mut sorted: Vec<char> = vec![];
+        for flag in flags.chars() {
+            if !Self::SUPPORTED_FLAGS.contains(flag) {
+                Err(Error::new(format!("Invalid regex flag `{}`", flag)))?
+            }
+            if !sorted.contains(&flag) {
+                sorted.push(flag);
+            }
+        }
+        sorted.sort();
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(sorted.contains(&'i'))
+            .multi_line(sorted.contains(&'m'))
+            .dot_matches_new_line(sorted.contains(&'s'))
+            .ignore_whitespace(sorted.contains(&'x'))
+            .build()
+            .map_err(|e| Error::new(format!("Invalid regex `{}`: {}", pattern, e)))?;
+        Ok(Self {
+            pattern,
+            flags: sorted.into_iter().collect(),
+            regex,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn flags(&self) -> &str {
+        &self.flags
+    }
+
+    pub fn has_flag(&self, flag: char) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn as_regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    pub fn into_regex(self) -> Regex {
+        self.regex
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// The capture groups of the first match as an array, with the whole match at index 0 and
+    /// `null` for groups which did not participate. Returns `null` when nothing matches.
+    pub fn captures(&self, text: &str) -> Value {
+        match self.regex.captures(text) {
+            Some(captures) => Value::Array(captures.iter().map(|m| match m {
+                Some(m) => Value::String(m.as_str().to_owned()),
+                None => Value::Null,
+            }).collect()),
+            None => Value::Null,
+        }
+    }
+
+    /// The named capture groups of the first match as a dictionary. Returns `null` when nothing
+    /// matches.
+    pub fn named_captures(&self, text: &str) -> Value {
+        match self.regex.captures(text) {
+            Some(captures) => Value::Dictionary(self.regex.capture_names().flatten().map(|name| {
+                (name.to_owned(), match captures.name(name) {
+                    Some(m) => Value::String(m.as_str().to_owned()),
+                    None => Value::Null,
+                })
+            }).collect()),
+            None => Value::Null,
+        }
+    }
+
+    /// Replace every match in `text`. `replacement` may refer to capture groups with `$1` or
+    /// `$name`.
+    pub fn replace(&self, text: &str, replacement: &str) -> Value {
+        Value::String(self.regex.replace_all(text, replacement).into_owned())
+    }
+}
+
+// `\/` matches the same as `/`, so slashes are stored unescaped.
+fn unescape_slashes(pattern: String) -> String {
+    if !pattern.contains("\\/") {
+        return pattern;
+    }
+    let mut result = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('/') => result.push('/'),
+                Some(c) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                None => result.push('\\'),
+            },
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+impl PartialEq for TeonRegex {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.flags == other.flags
+    }
+}
+
+impl From<Regex> for TeonRegex {
+
+    fn from(value: Regex) -> Self {
+        Self {
+            pattern: unescape_slashes(value.as_str().to_owned()),
+            flags: String::new(),
+            regex: value,
+        }
+    }
+}
+
+impl Display for TeonRegex {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_regex_literal(f, &self.pattern, &self.flags)
+    }
+}
+
+impl FromStr for TeonRegex {
+
+    type Err = Error;
+
+    /// Parse a regex literal like `/abc/im`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::new(format!("Invalid regex literal `{}`", s));
+        let body = s.strip_prefix('/').ok_or_else(invalid)?;
+        let mut pattern = String::new();
+        let mut chars = body.char_indices();
+        let mut end = None;
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, c)) => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                    None => Err(invalid())?,
+                },
+                '/' => {
+                    end = Some(i);
+                    break;
+                }
+                c => pattern.push(c),
+            }
+        }
+        let end = end.ok_or_else(invalid)?;
+        TeonRegex::new(pattern, &body[end + 1..])
+    }
+}
+
+impl TryFrom<&JsonValue> for TeonRegex {
+
+    type Error = Error;
+
+    /// Convert the tagged json form `{"$regex": "abc", "$options": "im"}`. `$options` may be
+    /// omitted when there are no flags.
+    fn try_from(value: &JsonValue) -> Result<Self> {
+        let object = value.as_object().ok_or_else(|| Error::new(format!("Cannot convert json value to regex, value `{}` is not object", value)))?;
+        if let Some(key) = object.keys().find(|k| k.as_str() != "$regex" && k.as_str() != "$options") {
+            Err(Error::new(format!("Cannot convert json value to regex, unexpected key `{}`", key)))?
+        }
+        let pattern = match object.get("$regex") {
+            Some(JsonValue::String(pattern)) => pattern,
+            Some(_) => Err(Error::new("Cannot convert json value to regex, invalid value at `$regex`, expect string"))?,
+            None => Err(Error::new("Cannot convert json value to regex, missing key `$regex`"))?,
+        };
+        let flags = match object.get("$options") {
+            Some(JsonValue::String(flags)) => flags.as_str(),
+            Some(JsonValue::Null) | None => "",
+            Some(_) => Err(Error::new("Cannot convert json value to regex, invalid value at `$options`, expect string"))?,
+        };
+        TeonRegex::new(pattern.as_str(), flags)
+    }
+}
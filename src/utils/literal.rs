@@ -0,0 +1,47 @@
+// Writers for Teon literals. String literals escape quotes, backslashes and control characters so
+// that the expression parser reads back the same string.
+
+use std::fmt::{Result, Write};
+
+pub(crate) fn write_string_literal<W: Write>(w: &mut W, s: &str) -> Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\t' => w.write_str("\\t")?,
+            '\r' => w.write_str("\\r")?,
+            '\0' => w.write_str("\\0")?,
+            c if c.is_control() => write!(w, "\\u{{{:x}}}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+pub(crate) fn string_literal(s: &str) -> String {
+    let mut output = String::with_capacity(s.len() + 2);
+    write_string_literal(&mut output, s).unwrap();
+    output
+}
+
+// Keys which are written without quotes, in paths and in shape types.
+pub(crate) fn is_identifier(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// Regex patterns keep their own escapes. Only unescaped delimiters are escaped.
+pub(crate) fn write_regex_literal<W: Write>(w: &mut W, pattern: &str, flags: &str) -> Result {
+    w.write_char('/')?;
+    let mut escaped = false;
+    for c in pattern.chars() {
+        if c == '/' && !escaped {
+            w.write_char('\\')?;
+        }
+        escaped = c == '\\' && !escaped;
+        w.write_char(c)?;
+    }
+    w.write_char('/')?;
+    w.write_str(flags)
+}
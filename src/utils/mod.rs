@@ -0,0 +1,4 @@
+pub(crate) mod sha256;
+pub(crate) mod mime;
+pub(crate) mod literal;
+pub(crate) mod percent;
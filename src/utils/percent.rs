@@ -0,0 +1,33 @@
+// Percent-encoding of URL components, as used in query strings and form bodies.
+
+/// Decode `%XX` escapes, and `+` as a space when `plus_as_space` is set. Malformed escapes are
+/// kept as they are. Returns `None` when the decoded bytes are not valid UTF-8.
+pub(crate) fn percent_decode(text: &str, plus_as_space: bool) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' if plus_as_space => output.push(b' '),
+            b'%' if i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() => {
+                output.push(u8::from_str_radix(&text[i + 1..i + 3], 16).unwrap());
+                i += 2;
+            }
+            b => output.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(output).ok()
+}
+
+/// Escape every byte except the unreserved characters `A-Z a-z 0-9 - . _ ~`.
+pub(crate) fn percent_encode(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(b as char),
+            b => output.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    output
+}
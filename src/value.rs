@@ -9,13 +9,20 @@ use bson::oid::ObjectId;
 use chrono::{NaiveDate, SecondsFormat};
 use regex::Regex;
 use bigdecimal::{BigDecimal, Zero};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use itertools::Itertools;
 use crate::types::enum_variant::EnumVariant;
 use crate::types::file::File;
 use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
 use super::index::Index;
 use teo_result::{Error, Result};
 use crate::types::option_variant::OptionVariant;
+use crate::ops::collection::{difference, intersection, union};
+use crate::ops::string::{size_limit_error, MAX_STRING_SIZE};
+use crate::pretty::PrettyOptions;
+use crate::utils::literal::{string_literal, write_string_literal};
 
 // Code from this file is inspired from serde json
 // https://github.com/serde-rs/json/blob/master/src/value/mod.rs
@@ -125,11 +132,20 @@ pub enum Value {
 
     /// Represents a Teon Regex.
     ///
-    Regex(Regex),
+    Regex(TeonRegex),
 
     /// Represents a Teon File.
     ///
     File(File),
+
+    /// Represents Teon Bytes.
+    ///
+    /// ```
+    /// # use teo_teon::teon;
+    /// #
+    /// let v = teon!(b"teon");
+    /// ```
+    Bytes(Vec<u8>),
 }
 
 impl Value {
@@ -144,6 +160,22 @@ impl Value {
         index.index_into_mut(self)
     }
 
+    /// Get a nested value by a dotted path like `"user.posts.0.title"`. Numeric segments index
+    /// into arrays and tuples. An empty path returns the value itself.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        let mut current = self;
+        for segment in path.split('.') {
+            current = match current {
+                Value::Array(_) | Value::Tuple(_) => current.get(segment.parse::<usize>().ok()?)?,
+                _ => current.get(segment)?,
+            };
+        }
+        Some(current)
+    }
+
     // Value
 
     pub fn is_null(&self) -> bool {
@@ -361,7 +393,7 @@ impl Value {
     }
 
     pub fn is_tuple(&self) -> bool {
-        self.as_range().is_some()
+        self.as_tuple().is_some()
     }
 
     pub fn as_tuple(&self) -> Option<&Vec<Value>> {
@@ -398,6 +430,13 @@ impl Value {
     }
 
     pub fn as_regexp(&self) -> Option<&Regex> {
+        match self {
+            Value::Regex(r) => Some(r.as_regex()),
+            _ => None,
+        }
+    }
+
+    pub fn as_teon_regex(&self) -> Option<&TeonRegex> {
         match self {
             Value::Regex(r) => Some(r),
             _ => None,
@@ -415,6 +454,31 @@ impl Value {
         }
     }
 
+    pub fn is_bytes(&self) -> bool {
+        self.as_bytes().is_some()
+    }
+
+    pub fn as_bytes(&self) -> Option<&Vec<u8>> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn into_bytes(self) -> Option<Vec<u8>> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
     // Compound queries
 
     pub fn is_any_int(&self) -> bool {
@@ -495,6 +559,7 @@ impl Value {
             Value::OptionVariant(_) => "OptionVariant",
             Value::Regex(_) => "RegExp",
             Value::File(_) => "File",
+            Value::Bytes(_) => "Bytes",
         }
     }
 
@@ -530,6 +595,7 @@ impl Value {
             Value::OptionVariant(o) => o.normal_not(),
             Value::Regex(_) => false,
             Value::File(_) => false,
+            Value::Bytes(b) => b.is_empty(),
         })
     }
 
@@ -572,7 +638,7 @@ fn check_enum_operands(name: &str, lhs: &Value, rhs: &Value) -> Result<()> {
     }
 }
 
-fn operand_error_message(operand: &Value, name: &str) -> Error {
+pub(crate) fn operand_error_message(operand: &Value, name: &str) -> Error {
     Error::new(format!("cannot {name} {}", operand.type_hint()))
 }
 
@@ -586,7 +652,7 @@ fn check_operands<F>(lhs: &Value, rhs: &Value, name: &str, matcher: F) -> Result
     Ok(())
 }
 
-fn operands_error_message(lhs: &Value, rhs: &Value, name: &str) -> Error {
+pub(crate) fn operands_error_message(lhs: &Value, rhs: &Value, name: &str) -> Error {
     Error::new(format!("cannot {name} {} with {}", lhs.type_hint(), rhs.type_hint()))
 }
 
@@ -620,6 +686,14 @@ impl Add for &Value {
                 check_operands(&self, &rhs, "add", |v| v.is_string())?;
                 Value::String(s.to_owned() + rhs.as_str().unwrap())
             }
+            Value::Array(a) => {
+                check_operands(self, rhs, "add", |v| v.is_array())?;
+                Value::Array(a.iter().chain(rhs.as_array().unwrap()).cloned().collect())
+            }
+            Value::Tuple(t) => {
+                check_operands(self, rhs, "add", |v| v.is_tuple())?;
+                Value::Tuple(t.iter().chain(rhs.as_tuple().unwrap()).cloned().collect())
+            }
             _ => Err(operands_error_message(self, rhs, "add"))?,
         })
     }
@@ -651,6 +725,10 @@ impl Sub for &Value {
                 check_operands(&self, &rhs, "sub", |v| v.is_decimal())?;
                 Value::Decimal(d - rhs.as_decimal().unwrap())
             },
+            Value::Array(a) => {
+                check_operands(self, rhs, "sub", |v| v.is_array())?;
+                Value::Array(difference(a, rhs.as_array().unwrap(), "sub")?)
+            },
             _ => Err(operands_error_message(self, rhs, "sub"))?,
         })
     }
@@ -682,6 +760,15 @@ impl Mul for &Value {
                 check_operands(&self, &rhs, "mul", |v| v.is_decimal())?;
                 Value::Decimal(d * rhs.as_decimal().unwrap())
             },
+            Value::String(s) => {
+                match rhs.to_int64() {
+                    Some(n) if n >= 0 => match usize::try_from(n).ok().and_then(|n| s.len().checked_mul(n)) {
+                        Some(len) if len <= MAX_STRING_SIZE => Value::String(s.repeat(n as usize)),
+                        _ => Err(size_limit_error("mul"))?,
+                    },
+                    _ => Err(operands_error_message(self, rhs, "mul"))?,
+                }
+            },
             _ => Err(operands_error_message(self, rhs, "mul"))?,
         })
     }
@@ -711,7 +798,11 @@ impl Div for &Value {
             },
             Value::Decimal(d) => {
                 check_operands(&self, &rhs, "div", |v| v.is_decimal())?;
-                Value::Decimal(d / rhs.as_decimal().unwrap())
+                let rhs = rhs.as_decimal().unwrap();
+                if rhs.is_zero() {
+                    Err(Error::new(format!("cannot div {self} by zero")))?
+                }
+                Value::Decimal(d / rhs)
             },
             _ => Err(operands_error_message(self, rhs, "div"))?,
         })
@@ -742,7 +833,11 @@ impl Rem for &Value {
             },
             Value::Decimal(d) => {
                 check_operands(&self, &rhs, "rem", |v| v.is_decimal())?;
-                Value::Decimal(d % rhs.as_decimal().unwrap())
+                let rhs = rhs.as_decimal().unwrap();
+                if rhs.is_zero() {
+                    Err(Error::new(format!("cannot rem {self} by zero")))?
+                }
+                Value::Decimal(d % rhs)
             },
             _ => Err(operands_error_message(self, rhs, "rem"))?,
         })
@@ -821,6 +916,10 @@ impl BitAnd for &Value {
                 check_enum_operands("bitand", self, rhs)?;
                 Value::OptionVariant((e & rhs.as_option_variant().unwrap())?)
             }
+            Value::Array(a) => {
+                check_operands(self, rhs, "bitand", |v| v.is_array())?;
+                Value::Array(intersection(a, rhs.as_array().unwrap(), "bitand")?)
+            }
             _ => Err(operand_error_message(self, "bitand"))?,
         })
     }
@@ -867,6 +966,18 @@ impl BitOr for &Value {
                 check_enum_operands("bitor", self, rhs)?;
                 Value::OptionVariant((e | rhs.as_option_variant().unwrap())?)
             }
+            Value::Array(a) => {
+                check_operands(self, rhs, "bitor", |v| v.is_array())?;
+                Value::Array(union(a, rhs.as_array().unwrap(), "bitor")?)
+            }
+            Value::Dictionary(d) => {
+                check_operands(self, rhs, "bitor", |v| v.is_dictionary())?;
+                let mut merged = d.clone();
+                for (k, v) in rhs.as_dictionary().unwrap() {
+                    merged.insert(k.clone(), v.clone());
+                }
+                Value::Dictionary(merged)
+            }
             _ => Err(operand_error_message(self, "bitor"))?,
         })
     }
@@ -914,8 +1025,9 @@ impl PartialEq for Value {
             (Tuple(s), Tuple(o)) => s == o,
             (EnumVariant(s), EnumVariant(o)) => s == o,
             (OptionVariant(s), OptionVariant(o)) => s.value == o.value,
-            (Regex(s), Regex(o)) => s.as_str() == o.as_str(),
+            (Regex(s), Regex(o)) => s == o,
             (File(s), File(o)) => s == o,
+            (Bytes(s), Bytes(o)) => s == o,
             _ => false,
         }
     }
@@ -943,6 +1055,7 @@ impl PartialOrd for Value {
             (Tuple(s), Tuple(o)) => s.partial_cmp(o),
             (EnumVariant(s), EnumVariant(o)) => s.value.partial_cmp(&o.value),
             (OptionVariant(s), OptionVariant(o)) => s.value.partial_cmp(&o.value),
+            (Bytes(s), Bytes(o)) => s.partial_cmp(o),
             _ => None,
         }
     }
@@ -958,6 +1071,9 @@ impl AsRef<Value> for Value {
 impl Display for Value {
 
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() && (self.is_array() || self.is_dictionary() || self.is_tuple()) {
+            return f.write_str(&self.to_pretty_string(PrettyOptions::default()));
+        }
         match self {
             Value::Null => f.write_str("null"),
             Value::Bool(b) => Display::fmt(b, f),
@@ -968,23 +1084,21 @@ impl Display for Value {
             Value::Decimal(d) => {
                 f.write_str("Decimal(\"")?;
                 Display::fmt(d, f)?;
-                f.write_str("\"")
+                f.write_str("\")")
             },
             Value::ObjectId(o) => {
                 f.write_str("ObjectId(\"")?;
                 Display::fmt(o, f)?;
-                f.write_str("\"")
+                f.write_str("\")")
             },
-            Value::String(s) => {
-                f.write_str(&format!("\"{}\"", s.replace("\"", "\\\"")))
-            }
+            Value::String(s) => write_string_literal(f, s),
             Value::Date(d) => f.write_str(&format!("Date(\"{}\")", d.to_string())),
             Value::DateTime(d) => f.write_str(&format!("DateTime(\"{}\")", d.to_rfc3339_opts(SecondsFormat::Millis, true))),
             Value::Array(a) => {
                 f.write_str(&("[".to_string() + a.iter().map(|v| format!("{v}")).join(", ").as_str() + "]"))
             }
             Value::Dictionary(m) => {
-                f.write_str(&("{".to_string() + m.iter().map(|(k, v)| format!("\"{k}\": {}", format!("{v}"))).join(", ").as_str() + "}"))
+                f.write_str(&("{".to_string() + m.iter().map(|(k, v)| format!("{}: {v}", string_literal(k))).join(", ").as_str() + "}"))
             }
             Value::Range(r) => Display::fmt(r, f),
             Value::Tuple(t) => {
@@ -1006,12 +1120,13 @@ impl Display for Value {
             Value::OptionVariant(o) => {
                 f.write_str(&o.display)
             }
-            Value::Regex(r) => {
-                f.write_str("/")?;
-                f.write_str(&format!("{}", r.as_str().replace("/", "\\/")))?;
-                f.write_str("/")
-            }
+            Value::Regex(r) => Display::fmt(r, f),
             Value::File(file) => Display::fmt(file, f),
+            Value::Bytes(b) => {
+                f.write_str("Bytes(\"")?;
+                f.write_str(&BASE64.encode(b))?;
+                f.write_str("\")")
+            }
         }
     }
 }
@@ -0,0 +1,437 @@
+//! Traversal of nested values.
+//!
+//! [`Visitor`] and [`VisitorMut`] have a hook for each variant, called with the [`Path`] of the
+//! value. Traversal is depth first and reaches the elements of arrays and tuples, the values of
+//! dictionaries, the bounds of ranges and the arguments of enum variants. For simpler cases,
+//! [`Value::fold`] accumulates over all values and [`Value::transform`] rebuilds a value from the
+//! leaves up.
+//!
+//! ```
+//! # use teo_teon::{teon, Value};
+//! # use teo_teon::visit::{Path, Visitor};
+//! #
+//! struct Strings(Vec<String>);
+//!
+//! impl Visitor for Strings {
+//!     fn visit_string(&mut self, path: &Path, value: &str) {
+//!         self.0.push(format!("{} = {}", path, value));
+//!     }
+//! }
+//!
+//! let mut strings = Strings(vec![]);
+//! teon!({"name": "Ann", "tags": ["a"]}).walk(&mut strings);
+//! assert_eq!(strings.0, vec!["$.name = Ann", "$.tags[0] = a"]);
+//!
+//! let value = teon!({"a": 1, "b": [2, 3]}).transform(|_, value| match value {
+//!     Value::Int(i) => Value::Int(i * 10),
+//!     value => value,
+//! });
+//! assert_eq!(value, teon!({"a": 10, "b": [20, 30]}));
+//! assert_eq!(value.fold(0, |count, _, _| count + 1), 5);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use indexmap::IndexMap;
+use crate::types::enum_variant::EnumVariant;
+use crate::types::file::File;
+use crate::types::option_variant::OptionVariant;
+use crate::types::range::Range;
+use crate::types::regex::TeonRegex;
+use crate::utils::literal::{is_identifier, string_literal};
+use crate::value::Value;
+
+/// A step from a value to one of its children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A dictionary key.
+    Key(String),
+    /// An index into an array or tuple.
+    Index(usize),
+    RangeStart,
+    RangeEnd,
+    /// The name of an enum variant argument.
+    Argument(String),
+}
+
+/// The location of a value within the value being traversed.
+///
+/// Paths print like `$.user.tags[0]`, with keys which aren't identifiers quoted as in
+/// `$["first name"]`, range bounds as `[start]` and `[end]`, and enum variant arguments as
+/// `(name)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+
+    /// The path of the traversed value itself.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The number of segments, which is the depth of the value.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    /// The dictionary key of the value, if it's a dictionary value.
+    pub fn key(&self) -> Option<&str> {
+        match self.segments.last() {
+            Some(PathSegment::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// This path extended by a segment.
+    pub fn child(&self, segment: PathSegment) -> Path {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+}
+
+impl From<Vec<PathSegment>> for Path {
+
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl Display for Path {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("$")?;
+        for segment in &self.segments {
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Segments print as they appear in a [`Path`], like `.name` or `[0]`.
+impl Display for PathSegment {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Key(key) if is_identifier(key) => write!(f, ".{}", key),
+            PathSegment::Key(key) => write!(f, "[{}]", string_literal(key)),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+            PathSegment::RangeStart => f.write_str("[start]"),
+            PathSegment::RangeEnd => f.write_str("[end]"),
+            PathSegment::Argument(name) => write!(f, "({})", name),
+        }
+    }
+}
+
+/// What a traversal does after [`Visitor::enter`] or [`VisitorMut::enter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Walk {
+    /// Visit the value and its children.
+    #[default]
+    Continue,
+    /// Skip the value and its children, and continue with its next sibling.
+    Skip,
+    /// End the traversal.
+    Stop,
+}
+
+/// Hooks for [`Value::walk`]. All of them do nothing by default.
+///
+/// For each value, `enter` is called first, then the hook of its variant, then the traversal
+/// descends into its children, and finally `leave` is called.
+#[allow(unused_variables)]
+pub trait Visitor {
+
+    fn enter(&mut self, path: &Path, value: &Value) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave(&mut self, path: &Path, value: &Value) { }
+
+    fn visit_null(&mut self, path: &Path) { }
+
+    fn visit_bool(&mut self, path: &Path, value: bool) { }
+
+    fn visit_int(&mut self, path: &Path, value: i32) { }
+
+    fn visit_int64(&mut self, path: &Path, value: i64) { }
+
+    fn visit_float32(&mut self, path: &Path, value: f32) { }
+
+    fn visit_float(&mut self, path: &Path, value: f64) { }
+
+    fn visit_decimal(&mut self, path: &Path, value: &BigDecimal) { }
+
+    fn visit_object_id(&mut self, path: &Path, value: &ObjectId) { }
+
+    fn visit_string(&mut self, path: &Path, value: &str) { }
+
+    fn visit_date(&mut self, path: &Path, value: &NaiveDate) { }
+
+    fn visit_datetime(&mut self, path: &Path, value: &DateTime<Utc>) { }
+
+    fn visit_array(&mut self, path: &Path, value: &[Value]) { }
+
+    fn visit_dictionary(&mut self, path: &Path, value: &IndexMap<String, Value>) { }
+
+    fn visit_range(&mut self, path: &Path, value: &Range) { }
+
+    fn visit_tuple(&mut self, path: &Path, value: &[Value]) { }
+
+    fn visit_enum_variant(&mut self, path: &Path, value: &EnumVariant) { }
+
+    fn visit_option_variant(&mut self, path: &Path, value: &OptionVariant) { }
+
+    fn visit_regex(&mut self, path: &Path, value: &TeonRegex) { }
+
+    fn visit_file(&mut self, path: &Path, value: &File) { }
+
+    fn visit_bytes(&mut self, path: &Path, value: &[u8]) { }
+}
+
+/// Hooks for [`Value::walk_mut`]. All of them do nothing by default.
+///
+/// The order is the same as for [`Visitor`]. Children are visited after the hook of their
+/// parent, so a value replaced in `enter` or changed in its variant hook is traversed as it is
+/// afterwards.
+#[allow(unused_variables)]
+pub trait VisitorMut {
+
+    fn enter(&mut self, path: &Path, value: &mut Value) -> Walk {
+        Walk::Continue
+    }
+
+    fn leave(&mut self, path: &Path, value: &mut Value) { }
+
+    fn visit_null(&mut self, path: &Path) { }
+
+    fn visit_bool(&mut self, path: &Path, value: &mut bool) { }
+
+    fn visit_int(&mut self, path: &Path, value: &mut i32) { }
+
+    fn visit_int64(&mut self, path: &Path, value: &mut i64) { }
+
+    fn visit_float32(&mut self, path: &Path, value: &mut f32) { }
+
+    fn visit_float(&mut self, path: &Path, value: &mut f64) { }
+
+    fn visit_decimal(&mut self, path: &Path, value: &mut BigDecimal) { }
+
+    fn visit_object_id(&mut self, path: &Path, value: &mut ObjectId) { }
+
+    fn visit_string(&mut self, path: &Path, value: &mut String) { }
+
+    fn visit_date(&mut self, path: &Path, value: &mut NaiveDate) { }
+
+    fn visit_datetime(&mut self, path: &Path, value: &mut DateTime<Utc>) { }
+
+    fn visit_array(&mut self, path: &Path, value: &mut Vec<Value>) { }
+
+    fn visit_dictionary(&mut self, path: &Path, value: &mut IndexMap<String, Value>) { }
+
+    fn visit_range(&mut self, path: &Path, value: &mut Range) { }
+
+    fn visit_tuple(&mut self, path: &Path, value: &mut Vec<Value>) { }
+
+    fn visit_enum_variant(&mut self, path: &Path, value: &mut EnumVariant) { }
+
+    fn visit_option_variant(&mut self, path: &Path, value: &mut OptionVariant) { }
+
+    fn visit_regex(&mut self, path: &Path, value: &mut TeonRegex) { }
+
+    fn visit_file(&mut self, path: &Path, value: &mut File) { }
+
+    fn visit_bytes(&mut self, path: &Path, value: &mut Vec<u8>) { }
+}
+
+impl Value {
+
+    /// Traverse this value and its descendants with a visitor.
+    pub fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        walk(visitor, &mut Path::root(), self);
+    }
+
+    /// Traverse this value and its descendants with a visitor which may change them.
+    pub fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        walk_mut(visitor, &mut Path::root(), self);
+    }
+
+    /// Accumulate over this value and its descendants, parents before their children.
+    pub fn fold<A>(&self, init: A, mut f: impl FnMut(A, &Path, &Value) -> A) -> A {
+        fold(self, &mut Path::root(), init, &mut f)
+    }
+
+    /// Rebuild this value from the leaves up. `f` receives each value after its children have
+    /// been transformed, and returns its replacement.
+    pub fn transform(self, mut f: impl FnMut(&Path, Value) -> Value) -> Value {
+        transform(self, &mut Path::root(), &mut f)
+    }
+}
+
+// Calls `f` with each child of `value` until it returns false. Returns false if it did.
+fn each_child<'v>(value: &'v Value, mut f: impl FnMut(PathSegment, &'v Value) -> bool) -> bool {
+    match value {
+        Value::Array(items) | Value::Tuple(items) => items.iter().enumerate().all(|(i, item)| f(PathSegment::Index(i), item)),
+        Value::Dictionary(map) => map.iter().all(|(k, item)| f(PathSegment::Key(k.clone()), item)),
+        Value::Range(range) => f(PathSegment::RangeStart, &range.start) && f(PathSegment::RangeEnd, &range.end),
+        Value::EnumVariant(EnumVariant { args: Some(args), .. }) => args.iter().all(|(k, item)| f(PathSegment::Argument(k.clone()), item)),
+        _ => true,
+    }
+}
+
+fn each_child_mut(value: &mut Value, mut f: impl FnMut(PathSegment, &mut Value) -> bool) -> bool {
+    match value {
+        Value::Array(items) | Value::Tuple(items) => items.iter_mut().enumerate().all(|(i, item)| f(PathSegment::Index(i), item)),
+        Value::Dictionary(map) => map.iter_mut().all(|(k, item)| f(PathSegment::Key(k.clone()), item)),
+        Value::Range(range) => f(PathSegment::RangeStart, &mut range.start) && f(PathSegment::RangeEnd, &mut range.end),
+        Value::EnumVariant(EnumVariant { args: Some(args), .. }) => args.iter_mut().all(|(k, item)| f(PathSegment::Argument(k.clone()), item)),
+        _ => true,
+    }
+}
+
+// Returns false when the traversal was stopped.
+fn walk<V: Visitor + ?Sized>(visitor: &mut V, path: &mut Path, value: &Value) -> bool {
+    match visitor.enter(path, value) {
+        Walk::Continue => (),
+        Walk::Skip => return true,
+        Walk::Stop => return false,
+    }
+    match value {
+        Value::Null => visitor.visit_null(path),
+        Value::Bool(b) => visitor.visit_bool(path, *b),
+        Value::Int(i) => visitor.visit_int(path, *i),
+        Value::Int64(i) => visitor.visit_int64(path, *i),
+        Value::Float32(f) => visitor.visit_float32(path, *f),
+        Value::Float(f) => visitor.visit_float(path, *f),
+        Value::Decimal(d) => visitor.visit_decimal(path, d),
+        Value::ObjectId(o) => visitor.visit_object_id(path, o),
+        Value::String(s) => visitor.visit_string(path, s),
+        Value::Date(d) => visitor.visit_date(path, d),
+        Value::DateTime(d) => visitor.visit_datetime(path, d),
+        Value::Array(items) => visitor.visit_array(path, items),
+        Value::Dictionary(map) => visitor.visit_dictionary(path, map),
+        Value::Range(r) => visitor.visit_range(path, r),
+        Value::Tuple(items) => visitor.visit_tuple(path, items),
+        Value::EnumVariant(e) => visitor.visit_enum_variant(path, e),
+        Value::OptionVariant(o) => visitor.visit_option_variant(path, o),
+        Value::Regex(r) => visitor.visit_regex(path, r),
+        Value::File(file) => visitor.visit_file(path, file),
+        Value::Bytes(bytes) => visitor.visit_bytes(path, bytes),
+    }
+    let completed = each_child(value, |segment, child| {
+        path.push(segment);
+        let completed = walk(visitor, path, child);
+        path.pop();
+        completed
+    });
+    if completed {
+        visitor.leave(path, value);
+    }
+    completed
+}
+
+fn walk_mut<V: VisitorMut + ?Sized>(visitor: &mut V, path: &mut Path, value: &mut Value) -> bool {
+    match visitor.enter(path, value) {
+        Walk::Continue => (),
+        Walk::Skip => return true,
+        Walk::Stop => return false,
+    }
+    match value {
+        Value::Null => visitor.visit_null(path),
+        Value::Bool(b) => visitor.visit_bool(path, b),
+        Value::Int(i) => visitor.visit_int(path, i),
+        Value::Int64(i) => visitor.visit_int64(path, i),
+        Value::Float32(f) => visitor.visit_float32(path, f),
+        Value::Float(f) => visitor.visit_float(path, f),
+        Value::Decimal(d) => visitor.visit_decimal(path, d),
+        Value::ObjectId(o) => visitor.visit_object_id(path, o),
+        Value::String(s) => visitor.visit_string(path, s),
+        Value::Date(d) => visitor.visit_date(path, d),
+        Value::DateTime(d) => visitor.visit_datetime(path, d),
+        Value::Array(items) => visitor.visit_array(path, items),
+        Value::Dictionary(map) => visitor.visit_dictionary(path, map),
+        Value::Range(r) => visitor.visit_range(path, r),
+        Value::Tuple(items) => visitor.visit_tuple(path, items),
+        Value::EnumVariant(e) => visitor.visit_enum_variant(path, e),
+        Value::OptionVariant(o) => visitor.visit_option_variant(path, o),
+        Value::Regex(r) => visitor.visit_regex(path, r),
+        Value::File(file) => visitor.visit_file(path, file),
+        Value::Bytes(bytes) => visitor.visit_bytes(path, bytes),
+    }
+    let completed = each_child_mut(value, |segment, child| {
+        path.push(segment);
+        let completed = walk_mut(visitor, path, child);
+        path.pop();
+        completed
+    });
+    if completed {
+        visitor.leave(path, value);
+    }
+    completed
+}
+
+fn fold<A>(value: &Value, path: &mut Path, init: A, f: &mut impl FnMut(A, &Path, &Value) -> A) -> A {
+    let mut acc = Some(f(init, path, value));
+    each_child(value, |segment, child| {
+        path.push(segment);
+        acc = Some(fold(child, path, acc.take().unwrap(), f));
+        path.pop();
+        true
+    });
+    acc.unwrap()
+}
+
+fn transform(value: Value, path: &mut Path, f: &mut impl FnMut(&Path, Value) -> Value) -> Value {
+    let mut child = |segment: PathSegment, value: Value| {
+        path.push(segment);
+        let value = transform(value, path, f);
+        path.pop();
+        value
+    };
+    let value = match value {
+        Value::Array(items) => Value::Array(items.into_iter().enumerate().map(|(i, item)| child(PathSegment::Index(i), item)).collect()),
+        Value::Tuple(items) => Value::Tuple(items.into_iter().enumerate().map(|(i, item)| child(PathSegment::Index(i), item)).collect()),
+        Value::Dictionary(map) => Value::Dictionary(map.into_iter().map(|(k, item)| {
+            let item = child(PathSegment::Key(k.clone()), item);
+            (k, item)
+        }).collect()),
+        Value::Range(Range { closed, start, end }) => {
+            let start = Box::new(child(PathSegment::RangeStart, *start));
+            let end = Box::new(child(PathSegment::RangeEnd, *end));
+            Value::Range(Range { closed, start, end })
+        }
+        Value::EnumVariant(EnumVariant { value, args: Some(args) }) => Value::EnumVariant(EnumVariant {
+            value,
+            args: Some(args.into_iter().map(|(k, item)| {
+                let item = child(PathSegment::Argument(k.clone()), item);
+                (k, item)
+            }).collect()),
+        }),
+        value => value,
+    };
+    f(path, value)
+}
@@ -0,0 +1,61 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use teo_teon::ops::aggregate::{AggregateOptions, NullHandling};
+    use teo_teon::{teon, Value};
+
+    fn decimal(s: &str) -> Value {
+        Value::Decimal(BigDecimal::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn aggregate_sum_mixed_numbers() {
+        let options = AggregateOptions::default();
+        assert_eq!(teon!([1, 2, 3]).sum(&options).unwrap(), teon!(6));
+        assert!(teon!([1, 2_i64]).sum(&options).unwrap().is_int64());
+        assert!(teon!([i32::MAX, 1]).sum(&options).unwrap().is_int64());
+        assert_eq!(teon!([1, 2.5]).sum(&options).unwrap(), teon!(3.5));
+        let sum = Value::Array(vec![teon!(1), decimal("0.1"), decimal("0.2")]).sum(&options).unwrap();
+        assert_eq!(sum, decimal("1.3"));
+        assert_eq!(teon!([]).sum(&options).unwrap(), teon!(0));
+        assert!(teon!(["a"]).sum(&options).is_err());
+    }
+
+    #[test]
+    fn aggregate_min_max_mixed_with_decimals() {
+        let options = AggregateOptions::default();
+        let values = Value::Array(vec![teon!(3), decimal("2.5"), Value::Int64(4), teon!(2.75)]);
+        assert_eq!(values.min(&options).unwrap(), decimal("2.5"));
+        assert!(values.min(&options).unwrap().is_decimal());
+        assert_eq!(values.max(&options).unwrap(), Value::Int64(4));
+        assert_eq!(Value::Array(vec![teon!(1), decimal("2")]).min(&options).unwrap(), teon!(1));
+        assert_eq!(teon!([1, "a"]).min(&options).unwrap_err().message, "cannot min Int with String");
+    }
+
+    #[test]
+    fn aggregate_through_path_with_nulls() {
+        let rows = teon!([{"n": 4}, {"n": null}, {"n": 2}, {}]);
+        let options = AggregateOptions::path("n");
+        assert_eq!(rows.sum(&options).unwrap(), teon!(6));
+        assert_eq!(rows.avg(&options).unwrap(), teon!(3.0));
+        assert_eq!(rows.count(&options).unwrap(), teon!(2_i64));
+        assert_eq!(rows.min(&options).unwrap(), teon!(2));
+        assert_eq!(rows.max(&options).unwrap(), teon!(4));
+        let options = AggregateOptions { path: Some("n".to_owned()), nulls: NullHandling::Error };
+        assert!(rows.sum(&options).is_err());
+    }
+
+    #[test]
+    fn aggregate_distinct_median_percentile() {
+        let options = AggregateOptions::default();
+        assert_eq!(teon!(["a", "b", "a"]).count_distinct(&options).unwrap(), teon!(2_i64));
+        assert_eq!(teon!(1).count_distinct(&options).unwrap_err().message, "cannot count_distinct Int");
+        assert_eq!(teon!([3, 1, 2]).median(&options).unwrap(), teon!(2.0));
+        assert_eq!(teon!([1, 2, 3, 4]).median(&options).unwrap(), teon!(2.5));
+        assert_eq!(teon!([1, 2, 3, 4, 5]).percentile(25.0, &options).unwrap(), teon!(2.0));
+        assert!(teon!([1]).percentile(101.0, &options).is_err());
+        assert_eq!(teon!([]).median(&options).unwrap(), teon!(null));
+        let median = Value::Array(vec![decimal("1"), decimal("2")]).median(&options).unwrap();
+        assert_eq!(median, decimal("1.5"));
+    }
+}
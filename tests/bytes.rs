@@ -0,0 +1,48 @@
+mod test {
+    use bson::Bson;
+    use serde_json::json;
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn bytes_from_and_into_vec() {
+        let value = Value::from(vec![1u8, 2, 3]);
+        assert!(value.is_bytes());
+        assert_eq!(value.as_bytes(), Some(&vec![1u8, 2, 3]));
+        let bytes: Vec<u8> = value.try_into().unwrap();
+        assert_eq!(bytes, vec![1u8, 2, 3]);
+        let bytes: Result<Vec<u8>, _> = Value::String("abc".to_owned()).try_into();
+        assert!(bytes.is_err());
+    }
+
+    #[test]
+    fn bytes_macro_and_display() {
+        let value = teon!({"hash": b"teon"});
+        assert_eq!(value["hash"], Value::Bytes(b"teon".to_vec()));
+        assert_eq!(format!("{}", value["hash"]), "Bytes(\"dGVvbg==\")");
+    }
+
+    #[test]
+    fn bytes_json() {
+        let value = Value::Bytes(b"teon".to_vec());
+        assert_eq!(serde_json::to_value(&value).unwrap(), json!({"$bytes": "dGVvbg=="}));
+        assert_eq!(serde_json::Value::try_from(&value).unwrap(), json!({"$bytes": "dGVvbg=="}));
+        assert_eq!(Value::from(json!({"$bytes": "dGVvbg=="})).type_hint(), "Bytes");
+        assert_eq!(Value::from(json!({"$bytes": "dGVvbg=="})), value);
+        assert_eq!(Value::from(json!({"$bytes": "not base64!"})), teon!({"$bytes": "not base64!"}));
+        assert_eq!(Value::from(json!({"$bytes": "dGVvbg==", "a": 1})).type_hint(), "Dictionary");
+    }
+
+    #[test]
+    fn bytes_bson() {
+        let value = teon!({"hash": b"teon", "count": 2_i32});
+        let bson = Bson::try_from(&value).unwrap();
+        assert!(matches!(bson.as_document().unwrap().get("hash"), Some(Bson::Binary(_))));
+        assert_eq!(Value::try_from(bson).unwrap(), value);
+    }
+
+    #[test]
+    fn bytes_ordering() {
+        assert!(Value::Bytes(vec![1, 2]) < Value::Bytes(vec![1, 3]));
+        assert_eq!(Value::Bytes(vec![]).normal_not(), Value::Bool(true));
+    }
+}
@@ -0,0 +1,46 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::{TimeZone, Utc};
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn canonical_form() {
+        let value = teon!({"b": [1, {"d": 2, "c": 3}], "a": -0.0});
+        assert_eq!(value.canonicalize().to_string(), r#"{"a": 0, "b": [1, {"c": 3, "d": 2}]}"#);
+        assert_eq!(Value::Int(1).canonicalize(), Value::Int64(1));
+        assert_eq!(Value::Float32(0.5).canonicalize(), Value::Float(0.5));
+        assert_eq!(Value::Decimal(BigDecimal::from_str("1.500").unwrap()).canonicalize().to_string(), "Decimal(\"1.5\")");
+        let datetime = Utc.timestamp_nanos(1_700_000_000_123_456_789);
+        assert_eq!(Value::DateTime(datetime).canonicalize(), Value::DateTime(Utc.timestamp_millis_opt(1_700_000_000_123).unwrap()));
+    }
+
+    #[test]
+    fn canonical_bytes_ignore_representation() {
+        let a = teon!({"x": 1, "y": [1.0, "s"]});
+        let b = Value::Dictionary(vec![
+            ("y".to_owned(), Value::Array(vec![Value::Float32(1.0), teon!("s")])),
+            ("x".to_owned(), Value::Int64(1)),
+        ].into_iter().collect());
+        assert_eq!(a.canonical_bytes(), b.canonical_bytes());
+        assert_eq!(a.digest(), b.digest());
+        assert_eq!(Value::Float(f64::NAN).digest(), Value::Float(-f64::NAN).digest());
+        let d1 = Value::Decimal(BigDecimal::from_str("10.0").unwrap());
+        let d2 = Value::Decimal(BigDecimal::from_str("10").unwrap());
+        assert_eq!(d1.digest(), d2.digest());
+        assert_ne!(teon!("1").digest(), teon!(1).digest());
+        assert_ne!(teon!([1, 2]).digest(), teon!([2, 1]).digest());
+        assert_ne!(teon!([[1], []]).digest(), teon!([[], [1]]).digest());
+    }
+
+    #[test]
+    fn canonical_encoding_is_stable() {
+        assert_eq!(Value::Null.canonical_bytes(), vec![0x00]);
+        assert_eq!(teon!(true).canonical_bytes(), vec![0x01, 0x01]);
+        assert_eq!(teon!(258).canonical_bytes(), vec![0x02, 0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(teon!("hi").canonical_bytes(), vec![0x06, 0, 0, 0, 0, 0, 0, 0, 2, b'h', b'i']);
+        assert_eq!(teon!([]).canonical_bytes(), vec![0x09, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Value::Null.digest(), "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d");
+        assert_eq!(teon!({"a": 1}).digest(), teon!({"a": 1}).digest());
+    }
+}
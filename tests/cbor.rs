@@ -0,0 +1,47 @@
+#![cfg(feature = "cbor")]
+
+mod common;
+
+mod test {
+    use teo_teon::Value;
+    use super::common::assert_round_trips;
+
+    fn round_trip(value: &Value) -> Value {
+        Value::from_cbor(&value.to_cbor().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn cbor_round_trip() {
+        assert_round_trips(round_trip);
+    }
+
+    #[test]
+    fn cbor_float32_decodes_as_float() {
+        assert!(matches!(round_trip(&Value::Float32(0.25)), Value::Float(f) if f == 0.25));
+    }
+
+    #[test]
+    fn cbor_int_decodes_as_int64() {
+        assert!(matches!(round_trip(&Value::Int(7)), Value::Int64(7)));
+    }
+
+    #[test]
+    fn cbor_rejects_trailing_bytes() {
+        let mut bytes = Value::Bool(true).to_cbor().unwrap();
+        bytes.push(0);
+        assert!(Value::from_cbor(&bytes).is_err());
+    }
+
+    #[test]
+    fn cbor_rejects_out_of_range_decimal_exponents() {
+        // 4([i64::MIN, 1])
+        let bytes = [0xc4, 0x82, 0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(Value::from_cbor(&bytes).unwrap_err().message, "Cannot convert cbor tag 4 into teon");
+        // 4([100000, 1])
+        let bytes = [0xc4, 0x82, 0x1a, 0x00, 0x01, 0x86, 0xa0, 0x01];
+        assert!(Value::from_cbor(&bytes).is_err());
+        // 4([-2, 125])
+        let bytes = [0xc4, 0x82, 0x21, 0x18, 0x7d];
+        assert_eq!(Value::from_cbor(&bytes).unwrap().to_string(), "Decimal(\"1.25\")");
+    }
+}
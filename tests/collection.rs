@@ -0,0 +1,72 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use teo_teon::{teon, Value};
+    use teo_teon::types::range::Range;
+
+    #[test]
+    fn collection_operators() {
+        assert_eq!((&teon!([1, 2]) + &teon!([2, 3])).unwrap(), teon!([1, 2, 2, 3]));
+        assert_eq!((&teon!([1, 2, 2]) | &teon!([2, 3])).unwrap(), teon!([1, 2, 3]));
+        assert_eq!((&teon!([1, 2, 3]) & &teon!([3, 2])).unwrap(), teon!([2, 3]));
+        assert_eq!((&teon!(["a", "b"]) - &teon!(["b"])).unwrap(), teon!(["a"]));
+        assert!((&teon!([1.5]) | &teon!([2])).is_err());
+        assert!((&teon!([{"a": 1}]) & &teon!([2])).is_err());
+        assert!((&teon!([1]) + &teon!(1)).is_err());
+        assert_eq!((&teon!([1, "1", null]) | &teon!([Value::Int64(1), "null"])).unwrap(), teon!([1, "1", null, "null"]));
+        assert_eq!((&teon!([1, "1", 2]) - &teon!([Value::Int64(2), "2"])).unwrap(), teon!([1, "1"]));
+        let large: Vec<Value> = (0..20000).map(Value::Int64).collect();
+        let result = (&Value::Array(large.clone()) & &Value::Array(large)).unwrap();
+        assert_eq!(result.as_array().unwrap().len(), 20000);
+    }
+
+    #[test]
+    fn collection_is_tuple() {
+        assert!(Value::Tuple(vec![teon!(1)]).is_tuple());
+        assert!(!Value::Range(Range { closed: false, start: Box::new(teon!(1)), end: Box::new(teon!(2)) }).is_tuple());
+        assert!(!teon!([1]).is_tuple());
+    }
+
+    #[test]
+    fn collection_dictionary_merge() {
+        let merged = (&teon!({"a": 1, "b": 2}) | &teon!({"b": 3, "c": 4})).unwrap();
+        assert_eq!(merged, teon!({"a": 1, "b": 3, "c": 4}));
+    }
+
+    #[test]
+    fn collection_methods() {
+        let array = teon!([3, 1, 3, [4, 5]]);
+        assert_eq!(array.contains(&teon!(1)).unwrap(), teon!(true));
+        assert_eq!(array.index_of(&teon!(3)).unwrap(), teon!(0_i64));
+        assert_eq!(array.index_of(&teon!(9)).unwrap(), teon!(null));
+        assert_eq!(array.unique().unwrap(), teon!([3, 1, [4, 5]]));
+        assert_eq!(array.flatten().unwrap(), teon!([3, 1, 3, 4, 5]));
+        assert_eq!(teon!([1, 2, 3]).chunk(2).unwrap(), teon!([[1, 2], [3]]));
+        assert_eq!(teon!([1, 2]).zip(&teon!(["a"])).unwrap().to_string(), "[(1, \"a\")]");
+    }
+
+    #[test]
+    fn collection_sort_and_group_by_path() {
+        let users = teon!([
+            {"name": "b", "profile": {"age": 30}},
+            {"name": "a", "profile": {"age": 20}},
+            {"name": "c"},
+        ]);
+        let sorted = users.sort_by_key("profile.age").unwrap();
+        assert_eq!(sorted.get_path("0.name").unwrap(), &teon!("c"));
+        assert_eq!(sorted.get_path("2.name").unwrap(), &teon!("b"));
+        let prices = teon!([{"p": Value::Decimal(BigDecimal::from_str("2.5").unwrap())}, {"p": 3}, {"p": Value::Int64(2)}]);
+        assert_eq!(prices.sort_by_key("p").unwrap(), teon!([{"p": Value::Int64(2)}, {"p": Value::Decimal(BigDecimal::from_str("2.5").unwrap())}, {"p": 3}]));
+        assert!(teon!([{"p": 1}, {"p": "1"}]).sort_by_key("p").is_err());
+        let grouped = teon!([{"k": "x", "v": 1}, {"k": "y", "v": 2}, {"k": "x", "v": 3}]).group_by("k").unwrap();
+        assert_eq!(grouped, Value::Array(vec![
+            Value::Tuple(vec![teon!("x"), teon!([{"k": "x", "v": 1}, {"k": "x", "v": 3}])]),
+            Value::Tuple(vec![teon!("y"), teon!([{"k": "y", "v": 2}])]),
+        ]));
+        let grouped = teon!([{"k": 1}, {"k": "1"}, {"k": null}, {"k": "null"}, {}, {"k": Value::Int64(1)}]).group_by("k").unwrap();
+        let keys: Vec<String> = grouped.as_array().unwrap().iter().map(|g| g.as_tuple().unwrap()[0].to_string()).collect();
+        assert_eq!(keys, vec!["1", "\"1\"", "null", "\"null\""]);
+        assert_eq!(grouped.as_array().unwrap()[0].as_tuple().unwrap()[1].as_array().unwrap().len(), 2);
+        assert_eq!(grouped.as_array().unwrap()[2].as_tuple().unwrap()[1].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,58 @@
+// Shared fixtures of the format tests. Each test file compiles this module separately and uses
+// only part of it.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{NaiveDate, TimeZone, Utc};
+use teo_teon::{teon, Value};
+use teo_teon::types::enum_variant::EnumVariant;
+use teo_teon::types::file::File;
+use teo_teon::types::option_variant::OptionVariant;
+use teo_teon::types::range::Range;
+use teo_teon::types::regex::TeonRegex;
+
+/// Values which every binary format reads back with the same type and value.
+pub fn round_trip_fixtures() -> Vec<Value> {
+    vec![
+        Value::Null,
+        Value::Bool(true),
+        Value::Int64(-42),
+        Value::Int64(i64::MAX),
+        Value::Float(1.5),
+        Value::String("teon".to_owned()),
+        Value::Bytes(vec![0, 1, 255]),
+        Value::Decimal(BigDecimal::from_str("-12.345").unwrap()),
+        Value::Decimal(BigDecimal::from_str("123456789012345678901234567890.5").unwrap()),
+        Value::ObjectId(ObjectId::parse_str("6541a1a8e3b5a1c2d3e4f506").unwrap()),
+        Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+        Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+        Value::DateTime(Utc.timestamp_millis_opt(-1_500).unwrap()),
+        Value::Range(Range { closed: true, start: Box::new(Value::Int64(1)), end: Box::new(Value::Int64(5)) }),
+        Value::Tuple(vec![Value::Int64(1), Value::String("a".to_owned())]),
+        Value::EnumVariant(EnumVariant { value: "admin".to_owned(), args: None }),
+        Value::EnumVariant(EnumVariant {
+            value: "limit".to_owned(),
+            args: Some(BTreeMap::from([("max".to_owned(), Value::Int64(3))])),
+        }),
+        Value::OptionVariant(OptionVariant { value: 6, display: ".Read | .Write".to_owned() }),
+        Value::Regex(TeonRegex::new("^a/b$", "i").unwrap()),
+        Value::File(File::from_bytes("avatar.png", vec![137, 80, 78, 71])),
+        teon!({
+            "name": "seed",
+            "list": [Value::Int64(1), {"nested": null}],
+            "range": Value::Range(Range { closed: false, start: Box::new(Value::Decimal(BigDecimal::from_str("0.5").unwrap())), end: Box::new(Value::Null) }),
+        }),
+    ]
+}
+
+/// Assert that `round_trip` reads back every fixture with the same type and value.
+pub fn assert_round_trips(round_trip: impl Fn(&Value) -> Value) {
+    for value in round_trip_fixtures() {
+        let read = round_trip(&value);
+        assert_eq!(read.type_hint(), value.type_hint(), "{value}");
+        assert_eq!(read, value);
+    }
+}
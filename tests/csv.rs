@@ -0,0 +1,102 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use indexmap::indexmap;
+    use teo_teon::{teon, Value};
+    use teo_teon::csv::{from_csv, to_csv, CsvReadOptions, CsvType, CsvWriteOptions, Quoting};
+
+    #[test]
+    fn csv_write_columns_and_quoting() {
+        let records = vec![
+            teon!({"name": "a,b", "note": "say \"hi\"", "tags": ["x"]}),
+            teon!({"name": "line\nbreak", "score": 1.5, "address": {"city": "Paris", "geo": {"lat": 1}}}),
+        ];
+        let text = to_csv(&records, &CsvWriteOptions::default()).unwrap();
+        assert_eq!(text, "name,note,tags,score,address.city,address.geo.lat\n\"a,b\",\"say \"\"hi\"\"\",\"[\"\"x\"\"]\",,,\n\"line\nbreak\",,,1.5,Paris,1\n");
+        let options = CsvWriteOptions { delimiter: b';', quoting: Quoting::NonNumeric, header: false, flatten: false };
+        let text = to_csv(&[teon!({"a": 1, "b": "x", "c": {"d": true}})], &options).unwrap();
+        assert_eq!(text, "1;\"x\";\"{\"\"d\"\":true}\"\n");
+        let options = CsvWriteOptions { quoting: Quoting::Always, ..Default::default() };
+        assert_eq!(to_csv(&[teon!({"a": 1})], &options).unwrap(), "\"a\"\n\"1\"\n");
+        assert!(to_csv(&[teon!(1)], &CsvWriteOptions::default()).is_err());
+    }
+
+    #[test]
+    fn csv_read_infers_types() {
+        let text = "id,price,ok,day,at,name,empty\r\n1,1.5,TRUE,2024-01-02,2024-01-02T03:04:05.000Z,x,\r\n2,2,false,2024-01-03,2024-01-02T03:04:05Z,\"\",\n";
+        let records = from_csv(text, &CsvReadOptions::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        let first = &records[0];
+        assert_eq!(first.get("id"), Some(&Value::Int64(1)));
+        assert_eq!(first.get("price"), Some(&Value::Float(1.5)));
+        assert_eq!(records[1].get("price"), Some(&Value::Float(2.0)));
+        assert_eq!(first.get("ok"), Some(&Value::Bool(true)));
+        assert_eq!(first.get("day"), Some(&Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())));
+        assert!(first.get("at").unwrap().is_datetime());
+        assert_eq!(first.get("name"), Some(&teon!("x")));
+        assert_eq!(records[1].get("name"), Some(&teon!("")));
+        assert_eq!(first.get("empty"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn csv_read_explicit_types_and_nesting() {
+        let options = CsvReadOptions {
+            types: indexmap! {"price".to_owned() => CsvType::Decimal, "id".to_owned() => CsvType::String},
+            ..Default::default()
+        };
+        let records = from_csv("id,price,a.b,a.c\n\n007,1.50,\"multi\nline\",3\n", &options).unwrap();
+        assert_eq!(records, vec![teon!({
+            "id": "007",
+            "price": Value::Decimal(BigDecimal::from_str("1.50").unwrap()),
+            "a": {"b": "multi\nline", "c": 3}
+        })]);
+        let options = CsvReadOptions { infer: false, unflatten: false, delimiter: b'\t', ..Default::default() };
+        assert_eq!(from_csv("a.b\tc\n1\t2", &options).unwrap(), vec![teon!({"a.b": "1", "c": "2"})]);
+    }
+
+    #[test]
+    fn csv_round_trip_and_errors() {
+        let records = vec![teon!({"id": 1, "user": {"name": "A \"B\""}}), teon!({"id": 2, "user": {"name": "C"}})];
+        let text = to_csv(&records, &CsvWriteOptions::default()).unwrap();
+        let read = from_csv(&text, &CsvReadOptions::default()).unwrap();
+        assert_eq!(read, vec![teon!({"id": 1, "user": {"name": "A \"B\""}}), teon!({"id": 2, "user": {"name": "C"}})]);
+        assert!(from_csv("a,b\n1\n", &CsvReadOptions::default()).is_err());
+        assert!(from_csv("a\n\"x", &CsvReadOptions::default()).is_err());
+        assert!(from_csv("a\n\"x\"y", &CsvReadOptions::default()).is_err());
+        let options = CsvReadOptions { types: indexmap! {"a".to_owned() => CsvType::Int64}, ..Default::default() };
+        assert!(from_csv("a\nx", &options).is_err());
+        assert!(from_csv("", &CsvReadOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn csv_read_conflicting_columns() {
+        let error = |text: &str| from_csv(text, &CsvReadOptions::default()).unwrap_err().message;
+        assert_eq!(error("a,a.b\n1,2\n"), "csv error at line 1: conflicting columns `a` and `a.b`");
+        assert_eq!(error("a.b.c,x,a.b\n1,2,3\n"), "csv error at line 1: conflicting columns `a.b.c` and `a.b`");
+        assert_eq!(error("a,a\n1,2\n"), "csv error at line 1: conflicting columns `a` and `a`");
+        assert_eq!(from_csv("a,ab.c,a_.b\n1,2,3\n", &CsvReadOptions::default()).unwrap(), vec![teon!({"a": 1_i64, "ab": {"c": 2_i64}, "a_": {"b": 3_i64}})]);
+        let flat = CsvReadOptions { unflatten: false, ..Default::default() };
+        assert_eq!(from_csv("a,a.b\n1,2\n", &flat).unwrap(), vec![teon!({"a": 1_i64, "a.b": 2_i64})]);
+    }
+
+    #[test]
+    fn csv_round_trip_empty_strings() {
+        let records = vec![teon!({"a": "", "b": null, "c": "x"})];
+        let text = to_csv(&records, &CsvWriteOptions::default()).unwrap();
+        assert_eq!(text, "a,b,c\n\"\",,x\n");
+        assert_eq!(from_csv(&text, &CsvReadOptions::default()).unwrap(), records);
+        let options = CsvWriteOptions { quoting: Quoting::NonNumeric, ..Default::default() };
+        assert_eq!(to_csv(&[teon!({"a": "", "b": 1})], &options).unwrap(), "\"a\",\"b\"\n\"\",1\n");
+    }
+
+    #[test]
+    fn csv_write_conflicting_columns() {
+        let error = |records: &[Value]| to_csv(records, &CsvWriteOptions::default()).unwrap_err().message;
+        assert_eq!(error(&[teon!({"a.b": 1, "a": {"b": 2}})]), "cannot write csv: more than one column named `a.b`");
+        assert_eq!(error(&[teon!({"a": 1}), teon!({"a": {"b": 2}})]), "cannot write csv: conflicting columns `a` and `a.b`");
+        assert_eq!(error(&[teon!({"a": 1, "a.b": 2})]), "cannot write csv: conflicting columns `a` and `a.b`");
+        let flat = CsvWriteOptions { flatten: false, ..Default::default() };
+        assert_eq!(to_csv(&[teon!({"a": 1, "a.b": 2})], &flat).unwrap(), "a,a.b\n1,2\n");
+    }
+}
@@ -0,0 +1,71 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use bson::oid::ObjectId;
+    use teo_teon::{teon, Value};
+    use teo_teon::expr::{evaluate, FunctionRegistry};
+    use teo_teon::types::file::File;
+    use teo_teon::types::regex::TeonRegex;
+
+    fn read_back(source: &str) -> Value {
+        evaluate(source, &Value::Null, &FunctionRegistry::new()).unwrap()
+    }
+
+    #[test]
+    fn display_string_escapes() {
+        assert_eq!(teon!("a\"b").to_string(), r#""a\"b""#);
+        assert_eq!(teon!("a\\b").to_string(), r#""a\\b""#);
+        assert_eq!(teon!("a\nb").to_string(), r#""a\nb""#);
+        assert_eq!(teon!("a\tb").to_string(), r#""a\tb""#);
+        assert_eq!(teon!("a\rb").to_string(), r#""a\rb""#);
+        assert_eq!(teon!("a\0b").to_string(), r#""a\0b""#);
+        assert_eq!(teon!("a\u{1b}b").to_string(), r#""a\u{1b}b""#);
+        assert_eq!(teon!("a\u{7f}b").to_string(), r#""a\u{7f}b""#);
+        assert_eq!(teon!("a\u{85}b").to_string(), r#""a\u{85}b""#);
+        assert_eq!(teon!("日本'/").to_string(), "\"日本'/\"");
+    }
+
+    #[test]
+    fn display_strings_read_back() {
+        let value = teon!("quote \" backslash \\ newline \n tab \t return \r nul \0 escape \u{1b} unicode é");
+        assert_eq!(read_back(&value.to_string()), value);
+    }
+
+    #[test]
+    fn display_dictionary_keys() {
+        let value = teon!({"a\"b\n": 1, "c\\": ["d\te"]});
+        assert_eq!(value.to_string(), r#"{"a\"b\n": 1, "c\\": ["d\te"]}"#);
+        assert_eq!(format!("{:#}", teon!({"\n": 1})), r#"{"\n": 1}"#);
+        assert_eq!(read_back(&value.to_string()), value);
+    }
+
+    #[test]
+    fn display_decimal_and_object_id() {
+        let decimal = Value::Decimal(BigDecimal::from_str("1.25").unwrap());
+        assert_eq!(decimal.to_string(), r#"Decimal("1.25")"#);
+        assert_eq!(read_back(&decimal.to_string()), decimal);
+        let object_id = Value::ObjectId(ObjectId::parse_str("507f1f77bcf86cd799439011").unwrap());
+        assert_eq!(object_id.to_string(), r#"ObjectId("507f1f77bcf86cd799439011")"#);
+        assert_eq!(read_back(&object_id.to_string()), object_id);
+        assert_eq!(teon!([decimal, object_id]).to_string(), r#"[Decimal("1.25"), ObjectId("507f1f77bcf86cd799439011")]"#);
+    }
+
+    #[test]
+    fn display_file_and_regex() {
+        let file = File::from_bytes("a\"b\\c.txt", vec![]);
+        assert_eq!(Value::File(file).to_string(), r#"File(memory "a\"b\\c.txt", 0 bytes)"#);
+        let file = File::from_bytes("a.txt", vec![1, 2, 3]).persist_to(std::env::temp_dir().join("teon-display-test")).unwrap();
+        assert_eq!(Value::File(file.clone()).to_string(), format!("File({:?})", file.filepath));
+        let regex = TeonRegex::new("a/b\\/c\\\\/d", "i").unwrap();
+        assert_eq!(regex.pattern(), r"a/b/c\\/d");
+        assert_eq!(regex.to_string(), r"/a\/b\/c\\\/d/i");
+        assert_eq!(regex.to_string().parse::<TeonRegex>().unwrap(), regex);
+        for pattern in [r"a/b\/c", r"\\\/", r"[/\/]+", r"\d\/\\"] {
+            let regex = TeonRegex::new(pattern, "m").unwrap();
+            let read = regex.to_string().parse::<TeonRegex>().unwrap();
+            assert_eq!(read, regex);
+            assert_eq!(read.to_string(), regex.to_string());
+            assert_eq!(read.is_match("a/b/c"), regex.is_match("a/b/c"));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use teo_teon::expr::{evaluate, parse, FunctionRegistry};
+    use teo_teon::{teon, Value};
+
+    fn eval(source: &str, context: &Value) -> Value {
+        evaluate(source, context, &FunctionRegistry::with_builtins()).unwrap()
+    }
+
+    #[test]
+    fn expr_literals() {
+        let context = teon!({});
+        assert_eq!(eval("[1, 2.5, \"a\\n\", null, true]", &context), teon!([1, 2.5, "a\n", null, true]));
+        assert_eq!(eval("{\"a\": 1, b: [2]}", &context), teon!({"a": 1, "b": [2]}));
+        assert_eq!(eval("(1,)", &context).to_string(), "(1,)");
+        assert_eq!(eval("Decimal(\"1.25\")", &context), Value::Decimal(BigDecimal::from_str("1.25").unwrap()));
+        assert_eq!(eval(".active", &context).to_string(), ".active");
+        assert_eq!(eval("3000000000", &context), teon!(3000000000_i64));
+    }
+
+    #[test]
+    fn expr_operators_and_precedence() {
+        let context = teon!({});
+        assert_eq!(eval("1 + 2 * 3", &context), teon!(7));
+        assert_eq!(eval("(1 + 2) * 3", &context), teon!(9));
+        assert_eq!(eval("10 - 4 - 3", &context), teon!(3));
+        assert_eq!(eval("-2 * 3 == -6 && !false", &context), teon!(true));
+        assert_eq!(eval("1 << 2 | 1", &context), teon!(5));
+        assert_eq!(eval("1 < 2 ? \"yes\" : \"no\"", &context), teon!("yes"));
+        assert_eq!(eval("false ? 1 : true ? 2 : 3", &context), teon!(2));
+        assert_eq!(eval("0 || \"default\"", &context), teon!("default"));
+    }
+
+    #[test]
+    fn expr_paths_and_functions() {
+        let context = teon!({"user": {"name": " teo ", "tags": ["a", "b"], "age": 20}});
+        assert_eq!(eval("user.tags[1]", &context), teon!("b"));
+        assert_eq!(eval("user[\"age\"] >= 18", &context), teon!(true));
+        assert_eq!(eval("user.missing", &context), teon!(null));
+        assert_eq!(eval("upper(trim(user.name))", &context), teon!("TEO"));
+        assert_eq!(eval("len(user.tags) + sum([1, 2])", &context), teon!(5_i64));
+        let mut functions = FunctionRegistry::new();
+        functions.register("double", |args| &args[0] * &Value::Int(2));
+        assert_eq!(evaluate("double(user.age)", &context, &functions).unwrap(), teon!(40));
+    }
+
+    #[test]
+    fn expr_errors() {
+        let context = teon!({});
+        assert!(parse("").is_err());
+        assert!(parse("a.").is_err());
+        assert!(parse("{1: 2}").is_err());
+        assert!(parse("1 +").is_err());
+        assert!(parse("(1").is_err());
+        assert!(parse("1 2").is_err());
+        assert!(evaluate("unknown", &context, &FunctionRegistry::new()).is_err());
+        assert!(evaluate("nothing()", &context, &FunctionRegistry::new()).is_err());
+        assert!(evaluate("1 < \"a\"", &context, &FunctionRegistry::new()).is_err());
+    }
+    #[test]
+    fn expr_integer_arithmetic() {
+        let context = teon!({});
+        let error = |source: &str| evaluate(source, &context, &FunctionRegistry::new()).unwrap_err().message;
+        assert_eq!(error("1 / 0"), "cannot div 1 by zero");
+        assert_eq!(error("1 % 0"), "cannot rem 1 by zero");
+        assert_eq!(error("Decimal(\"1\") / 0"), "cannot div Decimal(\"1\") by zero");
+        assert_eq!(error("Decimal(\"1\") / Decimal(\"0\")"), "cannot div Decimal(\"1\") by zero");
+        assert_eq!(error("Decimal(\"1\") % Decimal(\"0.00\")"), "cannot rem Decimal(\"1\") by zero");
+        assert_eq!(error("1 / Decimal(\"0\")"), "cannot div 1 by zero");
+        assert_eq!(error("1 % Decimal(\"0\")"), "cannot rem 1 by zero");
+        let (one, zero) = (Value::Decimal(BigDecimal::from_str("1").unwrap()), Value::Decimal(BigDecimal::from_str("0").unwrap()));
+        assert_eq!((&one / &zero).unwrap_err().message, "cannot div Decimal(\"1\") by zero");
+        assert_eq!((&one % &zero).unwrap_err().message, "cannot rem Decimal(\"1\") by zero");
+        assert!(error("1 << 40").starts_with("cannot shl by 40"));
+        assert!(error("1 << 3000000000").starts_with("cannot shl by 3000000000"));
+        assert!(error("1 >> -1").starts_with("cannot shr by -1"));
+        assert_eq!(error("9223372036854775807 + 1"), "integer overflow in add");
+        assert_eq!(error("-(-9223372036854775807 - 1)"), "integer overflow in neg");
+        assert_eq!(eval("1 << 31", &context), teon!(i32::MIN));
+        assert_eq!(eval("3000000000 << 1", &context), teon!(6000000000_i64));
+        assert_eq!(eval("2147483647 + 1", &context), teon!(2147483648_i64));
+        assert_eq!(eval("1 + 3000000000", &context), teon!(3000000001_i64));
+        assert_eq!(eval("3000000000 - 2999999999", &context), teon!(1_i64));
+        assert_eq!(eval("-(-2147483647 - 1)", &context), teon!(2147483648_i64));
+        assert_eq!(eval("1 & 3000000001", &context), teon!(1_i64));
+        assert_eq!(eval("7 / 2", &context), teon!(3));
+    }
+
+    #[test]
+    fn expr_depth_limit() {
+        assert!(parse(&format!("{}1{}", "(".repeat(64), ")".repeat(64))).is_err());
+        assert!(parse(&format!("{}1{}", "(".repeat(60), ")".repeat(60))).is_ok());
+        assert!(parse(&"(".repeat(200000)).is_err());
+        assert!(parse(&"[".repeat(200000)).is_err());
+        assert!(parse(&format!("{}1", "-".repeat(200000))).is_err());
+        assert!(parse(&format!("1{}", " + 1".repeat(200000))).is_err());
+        assert!(parse(&format!("a{}", ".b".repeat(200000))).is_err());
+        assert!(parse(&format!("1{}", " + 1".repeat(60))).is_ok());
+    }
+}
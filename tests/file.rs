@@ -0,0 +1,77 @@
+mod test {
+    use std::fs;
+    use std::path::PathBuf;
+    use serde_json::json;
+    use teo_teon::types::file::File;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("teo-teon-file-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn file_from_path_detects_content_type() {
+        let dir = temp_dir("from-path");
+        let path = dir.join("avatar.PNG");
+        fs::write(&path, b"\x89PNG\r\n\x1a\nrest").unwrap();
+        let file = File::from_path(&path).unwrap();
+        assert_eq!(file.filename, "avatar.PNG");
+        assert_eq!(file.filename_ext.as_deref(), Some("PNG"));
+        assert_eq!(file.content_type.as_deref(), Some("image/png"));
+        let path = dir.join("document");
+        fs::write(&path, b"%PDF-1.7").unwrap();
+        let file = File::from_path(&path).unwrap();
+        assert_eq!(file.filename_ext, None);
+        assert_eq!(file.content_type.as_deref(), Some("application/pdf"));
+        assert_eq!(file.size().unwrap(), 8);
+    }
+
+    #[test]
+    fn file_sha256() {
+        let dir = temp_dir("sha256");
+        let path = dir.join("abc.txt");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(File::from_path(&path).unwrap().sha256().unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let path = dir.join("large.bin");
+        fs::write(&path, vec![b'a'; 1_000_000]).unwrap();
+        assert_eq!(File::from_path(&path).unwrap().sha256().unwrap(), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    #[test]
+    fn file_persist_to_rejects_traversal() {
+        let dir = temp_dir("persist");
+        let path = dir.join("report.csv");
+        fs::write(&path, b"a,b").unwrap();
+        let file = File::from_path(&path).unwrap();
+        let persisted = file.persist_to(dir.join("uploads")).unwrap();
+        assert_eq!(fs::read(&persisted.filepath).unwrap(), b"a,b");
+        assert!(file.persist_to(dir.join("..").join("uploads")).is_err());
+        let mut malicious = file.clone();
+        malicious.filename = "../../etc/passwd".to_owned();
+        assert!(malicious.persist_to(dir.join("uploads")).is_err());
+    }
+
+    #[test]
+    fn file_in_memory_content() {
+        let file = File::from_bytes("hello.txt", b"hello".to_vec());
+        assert!(file.is_in_memory());
+        assert_eq!(file.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(file.size().unwrap(), 5);
+        assert!(file.open().is_err());
+        let persisted = file.persist_to(temp_dir("in-memory")).unwrap();
+        assert!(!persisted.is_in_memory());
+        let bytes: Vec<u8> = persisted.try_into().unwrap();
+        assert_eq!(bytes, b"hello");
+    }
+
+    #[test]
+    fn file_in_memory_json_round_trip() {
+        let file = File::from_bytes("hello.txt", b"hello".to_vec());
+        let json = serde_json::to_value(&file).unwrap();
+        assert_eq!(json.get("content").unwrap(), &json!("aGVsbG8="));
+        assert_eq!(File::try_from(&json).unwrap(), file);
+        assert!(File::from_json_with_limit(&json, 4).is_err());
+        assert!(File::from_json_with_limit(&json, 5).is_ok());
+    }
+}
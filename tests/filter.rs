@@ -0,0 +1,117 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use teo_teon::{teon, Value};
+    use teo_teon::filter::matches;
+    use teo_teon::types::range::Range;
+
+    fn user() -> Value {
+        teon!({
+            "name": "Alice",
+            "age": 20,
+            "email": null,
+            "tags": ["admin", "staff"],
+            "profile": {"city": "Paris", "score": 4.5},
+            "posts": [{"title": "Hello", "published": true}, {"title": "Draft", "published": false}]
+        })
+    }
+
+    #[test]
+    fn filter_scalar_operators() {
+        let user = user();
+        assert!(matches(&user, &Value::Null).unwrap());
+        assert!(matches(&user, &teon!({"name": "Alice"})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"equals": "Alice"}})).unwrap());
+        assert!(matches(&user, &teon!({"email": null})).unwrap());
+        assert!(matches(&user, &teon!({"missing": null})).unwrap());
+        assert!(matches(&user, &teon!({"age": {"gte": 18, "lt": 21}})).unwrap());
+        assert!(!matches(&user, &teon!({"age": {"gt": 20}})).unwrap());
+        assert!(!matches(&user, &teon!({"email": {"gt": 1}})).unwrap());
+        assert!(matches(&user, &teon!({"age": {"in": [18, 20]}})).unwrap());
+        assert!(matches(&user, &teon!({"age": {"notIn": [18, 19]}})).unwrap());
+        assert!(matches(&user, &teon!({"age": {"not": 21}})).unwrap());
+        assert!(matches(&user, &teon!({"age": {"not": {"lt": 18}}})).unwrap());
+        assert!(matches(&user, &teon!({"tags": {"contains": "admin"}})).unwrap());
+    }
+
+    #[test]
+    fn filter_string_modes() {
+        let user = user();
+        assert!(!matches(&user, &teon!({"name": {"startsWith": "al"}})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"startsWith": "al", "mode": "insensitive"}})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"endsWith": "CE", "mode": "insensitive"}})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"contains": "lic"}})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"equals": "alice", "mode": "insensitive"}})).unwrap());
+        assert!(!matches(&user, &teon!({"name": {"not": {"contains": "LI"}, "mode": "insensitive"}})).unwrap());
+        assert!(matches(&user, &teon!({"name": {"in": ["ALICE"], "mode": "insensitive"}})).unwrap());
+    }
+
+    #[test]
+    fn filter_logical_operators() {
+        let user = user();
+        assert!(matches(&user, &teon!({"OR": [{"age": 1}, {"name": "Alice"}]})).unwrap());
+        assert!(!matches(&user, &teon!({"AND": [{"age": 20}, {"name": "Bob"}]})).unwrap());
+        assert!(matches(&user, &teon!({"NOT": {"name": "Bob"}})).unwrap());
+        assert!(!matches(&user, &teon!({"NOT": [{"name": "Bob"}, {"age": 20}]})).unwrap());
+        assert!(!matches(&user, &teon!({"OR": []})).unwrap());
+    }
+
+    #[test]
+    fn filter_nested_paths() {
+        let user = user();
+        assert!(matches(&user, &teon!({"profile": {"city": "Paris"}})).unwrap());
+        assert!(matches(&user, &teon!({"profile": {"is": {"score": {"gt": 4}}}})).unwrap());
+        assert!(matches(&user, &teon!({"profile": {"isNot": null}})).unwrap());
+        assert!(matches(&user, &teon!({"posts": {"some": {"published": true}}})).unwrap());
+        assert!(!matches(&user, &teon!({"posts": {"every": {"published": true}}})).unwrap());
+        assert!(matches(&user, &teon!({"posts": {"none": {"title": {"startsWith": "X"}}}})).unwrap());
+        assert!(matches(&user, &teon!({"posts": {"some": {"title": "Hello"}}})).unwrap());
+    }
+
+    #[test]
+    fn filter_decimal_comparisons() {
+        let item = teon!({"price": Value::Decimal(BigDecimal::from_str("10.5").unwrap())});
+        assert!(matches(&item, &teon!({"price": {"gt": 10}})).unwrap());
+        assert!(matches(&item, &teon!({"price": {"lt": 10.75, "gte": Value::Int64(10)}})).unwrap());
+        assert!(!matches(&item, &teon!({"price": {"lte": 10}})).unwrap());
+        let range = Value::Range(Range { closed: false, start: Box::new(teon!(10)), end: Box::new(teon!(11)) });
+        assert!(matches(&item, &teon!({"price": range})).unwrap());
+    }
+
+    #[test]
+    fn filter_decimal_equality() {
+        let ten = Value::Decimal(BigDecimal::from_str("10.00").unwrap());
+        let item = teon!({"price": ten.clone(), "count": 10});
+        assert!(matches(&item, &teon!({"price": {"equals": 10}})).unwrap());
+        assert!(matches(&item, &teon!({"price": 10})).unwrap());
+        assert!(matches(&item, &teon!({"count": ten.clone()})).unwrap());
+        assert!(matches(&item, &teon!({"count": {"equals": ten.clone()}})).unwrap());
+        assert!(!matches(&item, &teon!({"price": {"not": 10}})).unwrap());
+        assert!(matches(&item, &teon!({"price": {"not": 11}})).unwrap());
+        assert!(matches(&item, &teon!({"price": {"in": [1, 10.0]}})).unwrap());
+        assert!(matches(&item, &teon!({"count": {"in": [ten.clone()]}})).unwrap());
+        assert!(!matches(&item, &teon!({"price": {"notIn": [Value::Int64(10)]}})).unwrap());
+        assert!(matches(&item, &teon!({"price": {"notIn": [9, 11]}})).unwrap());
+        assert!(!matches(&item, &teon!({"price": "10"})).unwrap());
+    }
+
+    #[test]
+    fn filter_range_shorthand() {
+        let user = user();
+        let open = Value::Range(Range { closed: false, start: Box::new(teon!(18)), end: Box::new(teon!(20)) });
+        let closed = Value::Range(Range { closed: true, start: Box::new(teon!(18)), end: Box::new(teon!(20)) });
+        assert!(!matches(&user, &teon!({"age": open})).unwrap());
+        assert!(matches(&user, &teon!({"age": closed})).unwrap());
+    }
+
+    #[test]
+    fn filter_errors() {
+        let user = user();
+        assert!(matches(&user, &teon!(1)).is_err());
+        assert!(matches(&user, &teon!({"age": {"gte": "x"}})).is_err());
+        assert!(matches(&user, &teon!({"age": {"gte": 1, "between": 2}})).is_err());
+        assert!(matches(&user, &teon!({"age": {"in": 20}})).is_err());
+        assert!(matches(&user, &teon!({"posts": {"title": "Hello"}})).is_err());
+        assert!(matches(&user, &teon!({"name": {"contains": "A", "mode": "loose"}})).is_err());
+    }
+}
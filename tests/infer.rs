@@ -0,0 +1,83 @@
+mod test {
+    use teo_teon::{teon, Value};
+    use teo_teon::infer::{infer_type, infer_type_with, InferOptions};
+    use teo_teon::schema::json_schema::validate;
+    use teo_teon::schema::Type;
+
+    #[test]
+    fn unify_observed_variants() {
+        let report = infer_type(&[
+            teon!({"id": 1, "score": 1.5}),
+            teon!({"id": Value::Int64(2), "score": null}),
+            teon!({"id": 3}),
+        ]);
+        assert_eq!(report.samples, 3);
+        assert_eq!(report.ty.to_string(), "{ id: Int64, score: Float? }");
+        let score = report.field("$.score").unwrap();
+        assert_eq!(score.count, 2);
+        assert_eq!(score.missing, 1);
+        assert_eq!(score.nulls, 1);
+        assert_eq!(report.field("$.id").unwrap().ty, Type::Int64);
+    }
+
+    #[test]
+    fn type_from_field_stats() {
+        let samples = [
+            teon!({"a": 1, "b": [1, 2], "c": null, "d": {"e": [{"f": 1}]}, "g": [[]]}),
+            teon!({"a": 2, "b": [], "d": {"e": []}, "g": "x"}),
+            teon!({"a": "x", "b": [Value::Int64(3), null], "d": null, "g": [["y"], null]}),
+            teon!([{"h": 1}, 2]),
+            teon!(null),
+        ];
+        let report = infer_type(&samples);
+        assert_eq!(report.ty, Type::from_values(&samples));
+        assert_eq!(report.field("$.c").unwrap().ty, Type::Optional(Box::new(Type::Null)));
+        assert_eq!(report.field("$.b[]").unwrap().ty.to_string(), "Int64?");
+        assert_eq!(infer_type(&samples[..2]).ty.to_string(), "{ a: Int, b: Int[], c: Null?, d: { e: { f: Int }[] }, g: Never[][] | String }");
+        assert_eq!(infer_type(&[]).ty, Type::Any);
+    }
+
+    #[test]
+    fn string_candidates() {
+        let values = [
+            teon!({"born": "1990-01-02", "age": "33", "name": "Ann", "tags": ["2024-01-01"]}),
+            teon!({"born": "1985-12-31", "age": "40", "name": "Bob", "tags": ["2024-02-29"]}),
+        ];
+        let report = infer_type(&values);
+        assert_eq!(report.field("$.born").unwrap().candidates, vec![Type::Date]);
+        assert_eq!(report.field("$.age").unwrap().candidates, vec![Type::Int64, Type::Decimal]);
+        assert!(report.field("$.name").unwrap().candidates.is_empty());
+        assert_eq!(report.field("$.tags[]").unwrap().candidates, vec![Type::Date]);
+        let promoted = infer_type_with(&values, &InferOptions { promote_candidates: true, ..Default::default() });
+        assert_eq!(promoted.ty.to_string(), "{ born: Date, age: Int64, name: String, tags: Date[] }");
+    }
+
+    #[test]
+    fn cardinality_and_examples() {
+        let values: Vec<Value> = ["a", "b", "a", "c", "d"].iter().map(|s| teon!({"k": *s})).collect();
+        let report = infer_type_with(&values, &InferOptions { max_examples: 2, max_distinct: 3, ..Default::default() });
+        let k = report.field("$.k").unwrap();
+        assert_eq!(k.distinct, 3);
+        assert!(k.distinct_capped);
+        assert_eq!(k.examples, vec![teon!("a"), teon!("b")]);
+    }
+
+    #[test]
+    fn printable_report() {
+        let report = infer_type(&[teon!({"name": "Ann", "odd key": 1}), teon!({"name": null})]);
+        assert_eq!(report.to_string(), "\
+2 samples
+$             { .. }   present 2/2
+$.name        String?  present 1/2, null 1, distinct 1, e.g. \"Ann\"
+$[\"odd key\"]  Int?     present 1/2, distinct 1, e.g. 1
+");
+    }
+
+    #[test]
+    fn schema_validates_later_values() {
+        let report = infer_type(&[teon!({"id": 1, "tags": ["a"]}), teon!({"id": 2, "tags": [], "note": "x"})]);
+        let schema = report.json_schema();
+        assert!(validate(&teon!({"id": 3, "tags": ["b", "c"]}), &schema).is_ok());
+        assert!(validate(&teon!({"id": "3", "tags": []}), &schema).is_err());
+    }
+}
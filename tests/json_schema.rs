@@ -0,0 +1,149 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use serde_json::json;
+    use teo_teon::{teon, Value};
+    use teo_teon::indexmap::indexmap;
+    use teo_teon::schema::Type;
+    use teo_teon::schema::json_schema::{from_json_schema, from_json_schema_document, to_json_schema, to_json_schema_document, validate, validation_errors};
+    use teo_teon::types::enum_variant::EnumVariant;
+    use teo_teon::types::range::Range;
+
+    fn boxed(t: Type) -> Box<Type> {
+        Box::new(t)
+    }
+
+    fn user() -> Type {
+        Type::Shape(indexmap! {
+            "id".to_owned() => Type::ObjectId,
+            "age".to_owned() => Type::Int,
+            "balance".to_owned() => Type::Decimal,
+            "birthday".to_owned() => Type::Optional(boxed(Type::Date)),
+            "joinedAt".to_owned() => Type::DateTime,
+            "role".to_owned() => Type::Enum(vec!["admin".to_owned(), "user".to_owned()]),
+            "pair".to_owned() => Type::Tuple(vec![Type::Int64, Type::String]),
+            "span".to_owned() => Type::Range(boxed(Type::Float)),
+            "tags".to_owned() => Type::Array(boxed(Type::String)),
+            "meta".to_owned() => Type::Dictionary(boxed(Type::Union(vec![Type::Bool, Type::Float32]))),
+            "avatar".to_owned() => Type::Optional(boxed(Type::File)),
+            "data".to_owned() => Type::Bytes,
+            "filter".to_owned() => Type::Regex,
+            "permissions".to_owned() => Type::OptionVariant,
+            "manager".to_owned() => Type::Optional(boxed(Type::Reference("User".to_owned()))),
+            "extra".to_owned() => Type::Any,
+        })
+    }
+
+    #[test]
+    fn json_schema_export() {
+        assert_eq!(to_json_schema(&Type::Decimal), json!({
+            "type": "object",
+            "properties": {"$decimal": {"type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$"}},
+            "required": ["$decimal"],
+            "additionalProperties": false,
+            "x-teon": "Decimal",
+        }));
+        assert_eq!(to_json_schema(&Type::Tuple(vec![Type::Int, Type::String])), json!({
+            "type": "array",
+            "prefixItems": [{"type": "integer", "format": "int32"}, {"type": "string"}],
+            "items": false,
+            "minItems": 2,
+        }));
+        assert_eq!(to_json_schema(&Type::Enum(vec!["a".to_owned()])), json!({"type": "string", "enum": ["a"]}));
+        assert_eq!(to_json_schema(&Type::Optional(boxed(Type::DateTime))), json!({"anyOf": [{"type": "string", "format": "date-time"}, {"type": "null"}]}));
+        assert_eq!(to_json_schema(&Type::Reference("a/b".to_owned())), json!({"$ref": "#/$defs/a~1b"}));
+        let document = to_json_schema_document(&indexmap! { "User".to_owned() => user() });
+        assert_eq!(document["$schema"], json!("https://json-schema.org/draft/2020-12/schema"));
+        assert_eq!(document["$defs"]["User"]["required"], json!(["id", "age", "balance", "joinedAt", "role", "pair", "span", "tags", "meta", "data", "filter", "permissions", "extra"]));
+    }
+
+    #[test]
+    fn json_schema_import_round_trip() {
+        let types = indexmap! { "User".to_owned() => user() };
+        assert_eq!(from_json_schema_document(&to_json_schema_document(&types)).unwrap(), types);
+    }
+
+    #[test]
+    fn json_schema_import_common_schemas() {
+        assert_eq!(from_json_schema(&json!(true)).unwrap(), Type::Any);
+        assert_eq!(from_json_schema(&json!({"type": ["string", "null"]})).unwrap(), Type::Optional(boxed(Type::String)));
+        assert_eq!(from_json_schema(&json!({"oneOf": [{"type": "integer"}, {"type": "string"}]})).unwrap(), Type::Union(vec![Type::Int64, Type::String]));
+        assert_eq!(from_json_schema(&json!({"const": "on"})).unwrap(), Type::Enum(vec!["on".to_owned()]));
+        assert_eq!(from_json_schema(&json!({"type": "object"})).unwrap(), Type::Dictionary(boxed(Type::Any)));
+        assert_eq!(from_json_schema(&json!({"$ref": "#/definitions/Post"})).unwrap(), Type::Reference("Post".to_owned()));
+        assert!(from_json_schema(&json!({"$ref": "https://example.com/schema"})).is_err());
+        assert!(from_json_schema(&json!({"type": "date"})).is_err());
+    }
+
+    #[test]
+    fn json_schema_validate_values() {
+        let document = to_json_schema_document(&indexmap! { "User".to_owned() => user() });
+        let schema = json!({"$defs": document["$defs"], "$ref": "#/$defs/User"});
+        let mut value = teon!({
+            "id": Value::ObjectId(bson::oid::ObjectId::parse_str("6541a1a8e3b5a1c2d3e4f506").unwrap()),
+            "age": 30,
+            "balance": Value::Decimal(BigDecimal::from_str("10.50").unwrap()),
+            "birthday": Value::Date(NaiveDate::from_ymd_opt(1990, 1, 2).unwrap()),
+            "joinedAt": Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+            "role": Value::EnumVariant(EnumVariant { value: "admin".to_owned(), args: None }),
+            "pair": Value::Tuple(vec![Value::Int64(1), teon!("a")]),
+            "span": Value::Range(Range { closed: true, start: Box::new(teon!(0.5)), end: Box::new(teon!(1.5)) }),
+            "tags": ["a"],
+            "meta": {"on": true, "ratio": 0.5},
+            "data": Value::Bytes(vec![1, 2]),
+            "filter": "^a",
+            "permissions": 3,
+            "manager": {"id": "6541a1a8e3b5a1c2d3e4f507"},
+            "extra": [1, "x"],
+        });
+        let errors = validation_errors(&value, &schema).unwrap();
+        assert_eq!(errors.iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["value at `/manager` must match at least one schema of `anyOf`"]);
+        value.as_dictionary_mut().unwrap().insert("manager".to_owned(), Value::Null);
+        assert!(validate(&value, &schema).is_ok());
+        value.as_dictionary_mut().unwrap().insert("role".to_owned(), teon!("owner"));
+        value.as_dictionary_mut().unwrap().insert("age".to_owned(), Value::Int64(1 << 40));
+        value.as_dictionary_mut().unwrap().insert("pair".to_owned(), Value::Tuple(vec![Value::Int64(1), teon!("a"), teon!(null)]));
+        value.as_dictionary_mut().unwrap().shift_remove("tags");
+        let messages: Vec<String> = validation_errors(&value, &schema).unwrap().iter().map(ToString::to_string).collect();
+        assert_eq!(messages, vec![
+            "value is missing required key `tags`",
+            "value at `/age` expect format int32",
+            "value at `/role` expect one of [\"admin\",\"user\"]",
+            "value at `/pair/2` is not allowed",
+        ]);
+        assert!(validate(&value, &schema).unwrap_err().message.contains("`/role`"));
+    }
+
+    #[test]
+    fn json_schema_validate_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 2, "pattern": "^[a-z]+$"},
+                "score": {"type": "number", "minimum": 0, "exclusiveMaximum": 10, "multipleOf": 0.5},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true, "maxItems": 2, "contains": {"const": "x"}},
+            },
+            "patternProperties": {"^x-": {"type": "integer"}},
+            "additionalProperties": false,
+            "not": {"required": ["forbidden"]},
+            "if": {"required": ["score"]},
+            "then": {"required": ["name"]},
+        });
+        assert!(validate(&teon!({"name": "ab", "score": 9.5, "tags": ["x", "y"], "x-count": 1}), &schema).is_ok());
+        let errors = validation_errors(&teon!({"score": 10, "tags": ["y", "y", "z"], "x-count": 1.5, "other": 1}), &schema).unwrap();
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, vec![
+            "value has unexpected key `other`",
+            "value at `/score` must be less than 10",
+            "value at `/tags` must have at most 2 items",
+            "value at `/tags` must have unique items",
+            "value at `/tags` must contain at least 1 matching items",
+            "value at `/x-count` expect integer, found number",
+            "value is missing required key `name`",
+        ]);
+        assert!(validate(&teon!({"name": "A"}), &schema).is_err());
+        assert!(validate(&teon!(1), &json!({"$ref": "#/$defs/missing"})).is_err());
+        assert!(validate(&teon!(1), &json!({"type": 1})).is_err());
+    }
+}
@@ -32,4 +32,30 @@ mod test {
         let vec_str: Vec<i32> = (&value).try_into().unwrap();
         assert_equal(vec_str, vec![1, 2])
     }
+
+    #[test]
+    fn value_into_tuple() {
+        let value = Value::Tuple(vec![Value::Int64(1), Value::String("a".to_owned())]);
+        let pair: (i64, String) = value.try_into().unwrap();
+        assert_eq!(pair, (1, "a".to_owned()));
+        let value = Value::Tuple(vec![Value::Int64(1), Value::String("a".to_owned()), Value::Bool(true)]);
+        let triple: (i64, String, bool) = value.try_into().unwrap();
+        assert_eq!(triple, (1, "a".to_owned(), true));
+    }
+
+    #[test]
+    fn value_into_tuple_of_wrong_length() {
+        let short = Value::Tuple(vec![Value::Int64(1), Value::Int64(2)]);
+        let long = Value::Tuple(vec![Value::Int64(1), Value::Int64(2), Value::Int64(3)]);
+        let triple: Result<(i64, i64, i64), _> = short.clone().try_into();
+        assert_eq!(triple.unwrap_err().message, "Cannot convert Tuple into Tuple");
+        let triple: Result<(i64, i64, i64), _> = Value::Tuple(vec![Value::Int64(1), Value::Int64(2), Value::Int64(3), Value::Int64(4)]).try_into();
+        assert!(triple.is_err());
+        let pair: Result<(i64, i64), _> = long.try_into();
+        assert!(pair.is_err());
+        let pair: Result<(i64, i64), _> = Value::Array(vec![Value::Int64(1), Value::Int64(2)]).try_into();
+        assert!(pair.is_err());
+        let pair: (i64, i64) = short.try_into().unwrap();
+        assert_eq!(pair, (1, 2));
+    }
 }
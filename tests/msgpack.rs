@@ -0,0 +1,34 @@
+#![cfg(feature = "msgpack")]
+
+mod common;
+
+mod test {
+    use teo_teon::Value;
+    use super::common::assert_round_trips;
+
+    fn round_trip(value: &Value) -> Value {
+        Value::from_msgpack(&value.to_msgpack().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn msgpack_round_trip() {
+        assert_round_trips(round_trip);
+    }
+
+    #[test]
+    fn msgpack_keeps_float32() {
+        assert!(matches!(round_trip(&Value::Float32(0.25)), Value::Float32(f) if f == 0.25));
+    }
+
+    #[test]
+    fn msgpack_int_decodes_as_int64() {
+        assert!(matches!(round_trip(&Value::Int(7)), Value::Int64(7)));
+    }
+
+    #[test]
+    fn msgpack_rejects_trailing_bytes() {
+        let mut bytes = Value::Bool(true).to_msgpack().unwrap();
+        bytes.push(0);
+        assert!(Value::from_msgpack(&bytes).is_err());
+    }
+}
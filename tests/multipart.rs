@@ -0,0 +1,109 @@
+mod test {
+    use std::fs;
+    use std::io::Read;
+    use std::path::PathBuf;
+    use teo_teon::{teon, Value};
+    use teo_teon::multipart::{boundary_from_content_type, from_multipart, MultipartOptions};
+
+    fn options(name: &str) -> MultipartOptions {
+        let dir = std::env::temp_dir().join(format!("teo-teon-multipart-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        MultipartOptions { temp_dir: dir, ..Default::default() }
+    }
+
+    fn files_in(dir: &PathBuf) -> usize {
+        fs::read_dir(dir).map(|entries| entries.count()).unwrap_or(0)
+    }
+
+    fn body(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut body = b"preamble\r\n".to_vec();
+        for (headers, content) in parts {
+            body.extend_from_slice(format!("--b0undary\r\n{}\r\n\r\n{}\r\n", headers, content).as_bytes());
+        }
+        body.extend_from_slice(b"--b0undary--\r\nepilogue");
+        body
+    }
+
+    // A reader returning at most three bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(3).min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn multipart_boundary() {
+        assert_eq!(boundary_from_content_type("multipart/form-data; boundary=abc"), Some("abc".to_owned()));
+        assert_eq!(boundary_from_content_type("Multipart/Form-Data; charset=utf-8; boundary=\"a;b c\""), Some("a;b c".to_owned()));
+        assert_eq!(boundary_from_content_type("application/json"), None);
+        assert_eq!(boundary_from_content_type("multipart/form-data"), None);
+    }
+
+    #[test]
+    fn multipart_fields_and_files() {
+        let options = options("fields");
+        let body = body(&[
+            ("Content-Disposition: form-data; name=\"user[name]\"", "Ann"),
+            ("Content-Disposition: form-data; name=\"user[tags][]\"", "a"),
+            ("Content-Disposition: form-data; name=\"user[tags][]\"", "b"),
+            ("content-disposition: form-data; name=\"note\"", "line 1\r\n--b0undar"),
+            ("Content-Disposition: form-data; name=\"photos[]\"; filename=\"C:\\pics\\a.png\"\r\nContent-Type: image/png", "\u{1}png data"),
+            ("Content-Disposition: form-data; name=\"photos[]\"; filename=\"x\"; filename*=UTF-8''na%C3%AFve.txt", "text"),
+            ("Content-Disposition: form-data; name=\"empty\"; filename=\"\"", ""),
+        ]);
+        let value = from_multipart(Trickle(&body), "b0undary", &options).unwrap();
+        assert_eq!(value.get("user"), Some(&teon!({"name": "Ann", "tags": ["a", "b"]})));
+        assert_eq!(value.get("note"), Some(&teon!("line 1\r\n--b0undar")));
+        assert_eq!(value.get("empty"), None);
+        let photos = value.get("photos").unwrap().as_array().unwrap();
+        assert_eq!(photos.len(), 2);
+        let png = photos[0].as_file().unwrap();
+        assert_eq!(png.filename, "a.png");
+        assert_eq!(png.filename_ext.as_deref(), Some("png"));
+        assert_eq!(png.content_type.as_deref(), Some("image/png"));
+        assert!(!png.is_in_memory());
+        assert!(png.filepath.starts_with(options.temp_dir.to_str().unwrap()));
+        assert_eq!(png.bytes().unwrap(), b"\x01png data");
+        let text = photos[1].as_file().unwrap();
+        assert_eq!(text.filename, "naïve.txt");
+        assert_eq!(text.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(text.bytes().unwrap(), b"text");
+        assert_eq!(files_in(&options.temp_dir), 2);
+        fs::remove_dir_all(&options.temp_dir).unwrap();
+    }
+
+    #[test]
+    fn multipart_limits() {
+        let file_part = ("Content-Disposition: form-data; name=\"f\"; filename=\"a.bin\"", "0123456789");
+        let field_part = ("Content-Disposition: form-data; name=\"t\"", "0123456789");
+        let options = MultipartOptions { max_file_size: 9, ..options("limits") };
+        let error = from_multipart(body(&[file_part]).as_slice(), "b0undary", &options).unwrap_err();
+        assert!(error.message.contains("file exceeds the size limit of 9 bytes"), "{}", error.message);
+        assert_eq!(files_in(&options.temp_dir), 0);
+        let options = MultipartOptions { max_field_size: 9, ..options };
+        assert!(from_multipart(body(&[field_part]).as_slice(), "b0undary", &options).is_err());
+        let options = MultipartOptions { max_parts: 1, ..options };
+        assert!(from_multipart(body(&[("Content-Disposition: form-data; name=\"a\"", ""); 2]).as_slice(), "b0undary", &options).is_err());
+        let options = MultipartOptions { max_body_size: 20, ..options };
+        assert!(from_multipart(body(&[("Content-Disposition: form-data; name=\"a\"", "")]).as_slice(), "b0undary", &options).is_err());
+        let _ = fs::remove_dir_all(&options.temp_dir);
+    }
+
+    #[test]
+    fn multipart_errors() {
+        let options = options("errors");
+        let truncated = b"--b0undary\r\nContent-Disposition: form-data; name=\"f\"; filename=\"a.txt\"\r\n\r\nabc";
+        assert!(from_multipart(truncated.as_slice(), "b0undary", &options).is_err());
+        assert_eq!(files_in(&options.temp_dir), 0);
+        assert!(from_multipart(body(&[("Content-Type: text/plain", "x")]).as_slice(), "b0undary", &options).is_err());
+        assert!(from_multipart(body(&[("Content-Disposition: form-data", "x")]).as_slice(), "b0undary", &options).is_err());
+        assert!(from_multipart(b"no delimiter".as_slice(), "b0undary", &options).is_err());
+        assert_eq!(from_multipart(b"--b0undary--".as_slice(), "b0undary", &options).unwrap(), Value::Dictionary(Default::default()));
+        let _ = fs::remove_dir_all(&options.temp_dir);
+    }
+}
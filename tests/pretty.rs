@@ -0,0 +1,47 @@
+mod test {
+    use teo_teon::teon;
+    use teo_teon::pretty::PrettyOptions;
+
+    #[test]
+    fn pretty_short_values_stay_on_one_line() {
+        let value = teon!({"a": [1, 2], "b": {}});
+        assert_eq!(value.to_pretty_string(PrettyOptions::default()), "{\"a\": [1, 2], \"b\": {}}");
+        assert_eq!(format!("{:#}", teon!(1)), "1");
+    }
+
+    #[test]
+    fn pretty_breaks_long_values() {
+        let value = teon!({"name": "Alice", "tags": ["a", "b"], "profile": {"city": "Paris"}});
+        let options = PrettyOptions { max_width: 40, ..Default::default() };
+        assert_eq!(value.to_pretty_string(options), concat!(
+            "{\n",
+            "    \"name\": \"Alice\",\n",
+            "    \"tags\": [\"a\", \"b\"],\n",
+            "    \"profile\": {\"city\": \"Paris\"}\n",
+            "}",
+        ));
+        let options = PrettyOptions { indent: 2, max_width: 0, trailing_commas: true, sort_keys: true };
+        assert_eq!(teon!({"b": [1], "a": []}).to_pretty_string(options), concat!(
+            "{\n",
+            "  \"a\": [],\n",
+            "  \"b\": [\n",
+            "    1,\n",
+            "  ],\n",
+            "}",
+        ));
+    }
+
+    #[test]
+    fn pretty_alternate_format() {
+        let long = "x".repeat(80);
+        let value = teon!([long.clone(), 1]);
+        assert_eq!(format!("{:#}", value), format!("[\n    \"{long}\",\n    1\n]"));
+        assert_eq!(format!("{}", value), format!("[\"{long}\", 1]"));
+    }
+
+    #[test]
+    fn pretty_sorted_keys_on_one_line() {
+        let options = PrettyOptions { sort_keys: true, ..Default::default() };
+        assert_eq!(teon!({"b": 1, "a": {"d": 2, "c": 3}}).to_pretty_string(options), "{\"a\": {\"c\": 3, \"d\": 2}, \"b\": 1}");
+    }
+}
@@ -0,0 +1,82 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use teo_teon::{teon, Value};
+    use teo_teon::query::{omit, order_by, paginate, query, select};
+
+    fn records() -> Vec<Value> {
+        vec![
+            teon!({"id": 1, "name": "Ann", "age": 30, "profile": {"score": 2}}),
+            teon!({"id": 2, "name": "Bob", "age": null, "profile": {"score": 5}}),
+            teon!({"id": 3, "name": "Cat", "age": 25, "profile": {"score": 5}}),
+            teon!({"id": 4, "name": "Dan", "age": 30, "profile": null}),
+        ]
+    }
+
+    fn ids(records: &[Value]) -> Vec<i32> {
+        records.iter().map(|r| r.get("id").unwrap().as_int().unwrap()).collect()
+    }
+
+    #[test]
+    fn query_order_by() {
+        let mut r = records();
+        order_by(&mut r, &teon!({"age": "asc"})).unwrap();
+        assert_eq!(ids(&r), vec![2, 3, 1, 4]);
+        order_by(&mut r, &teon!({"age": "desc"})).unwrap();
+        assert_eq!(ids(&r), vec![1, 4, 3, 2]);
+        order_by(&mut r, &teon!([{"age": {"sort": "asc", "nulls": "last"}}, {"id": "desc"}])).unwrap();
+        assert_eq!(ids(&r), vec![3, 4, 1, 2]);
+        order_by(&mut r, &teon!([{"profile": {"score": "desc"}}, {"name": "asc"}])).unwrap();
+        assert_eq!(ids(&r), vec![2, 3, 1, 4]);
+        assert!(order_by(&mut r, &teon!({"age": "up"})).is_err());
+        assert!(order_by(&mut records(), &teon!({"name": "asc", "id": "asc"})).is_ok());
+        let mut mixed = vec![teon!({"a": 1}), teon!({"a": "x"})];
+        assert!(order_by(&mut mixed, &teon!({"a": "asc"})).is_err());
+    }
+
+    #[test]
+    fn query_order_by_mixed_numbers() {
+        let decimal = |s: &str| Value::Decimal(BigDecimal::from_str(s).unwrap());
+        let mut r = vec![
+            teon!({"id": 1, "price": decimal("2.5")}),
+            teon!({"id": 2, "price": 3}),
+            teon!({"id": 3, "price": 1.5}),
+            teon!({"id": 4, "price": Value::Int64(2)}),
+        ];
+        order_by(&mut r, &teon!({"price": "asc"})).unwrap();
+        assert_eq!(ids(&r), vec![3, 4, 1, 2]);
+        order_by(&mut r, &teon!({"price": "desc"})).unwrap();
+        assert_eq!(ids(&r), vec![2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn query_paginate() {
+        assert_eq!(ids(&paginate(records(), None, Some(1), Some(2)).unwrap()), vec![2, 3]);
+        assert_eq!(ids(&paginate(records(), Some(&teon!({"id": 2})), None, Some(2)).unwrap()), vec![2, 3]);
+        assert_eq!(ids(&paginate(records(), Some(&teon!({"id": 2})), Some(1), None).unwrap()), vec![3, 4]);
+        assert_eq!(ids(&paginate(records(), Some(&teon!({"id": 2})), Some(i64::MAX), None).unwrap()), Vec::<i32>::new());
+        assert_eq!(ids(&paginate(records(), None, Some(0), Some(i64::MAX)).unwrap()), vec![1, 2, 3, 4]);
+        assert!(paginate(records(), Some(&teon!({"id": 9})), None, None).unwrap().is_empty());
+        assert!(paginate(records(), None, Some(-1), None).is_err());
+        assert!(paginate(records(), None, None, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn query_projection() {
+        let record = teon!({"id": 1, "name": "Ann", "posts": [{"title": "a", "body": "b"}], "profile": {"score": 2, "bio": "x"}});
+        assert_eq!(select(&record, &teon!({"id": true, "name": false})).unwrap(), teon!({"id": 1}));
+        assert_eq!(select(&record, &teon!({"posts": {"select": {"title": true}}, "profile": {"score": true}})).unwrap(), teon!({"posts": [{"title": "a"}], "profile": {"score": 2}}));
+        assert_eq!(omit(&record, &teon!({"posts": true, "profile": {"bio": true}})).unwrap(), teon!({"id": 1, "name": "Ann", "profile": {"score": 2}}));
+        assert!(select(&record, &teon!({"id": 1})).is_err());
+    }
+
+    #[test]
+    fn query_all_arguments() {
+        let args = teon!({"where": {"age": {"not": null}}, "orderBy": [{"age": "desc"}, {"id": "asc"}], "skip": 1, "take": 2, "select": {"id": true}});
+        assert_eq!(query(records(), &args).unwrap(), vec![teon!({"id": 4}), teon!({"id": 3})]);
+        assert_eq!(query(records(), &Value::Null).unwrap().len(), 4);
+        assert!(query(records(), &teon!({"limit": 1})).is_err());
+        assert!(query(records(), &teon!({"select": {"id": true}, "omit": {"age": true}})).is_err());
+        assert!(query(records(), &teon!({"take": "1"})).is_err());
+    }
+}
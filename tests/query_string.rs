@@ -0,0 +1,76 @@
+mod test {
+    use teo_teon::{teon, Value};
+    use teo_teon::query_string::{ArrayFormat, QueryStringOptions};
+
+    fn parse(text: &str) -> Value {
+        Value::from_query_string(text, &QueryStringOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn query_string_parse_nested() {
+        assert_eq!(parse(""), teon!({}));
+        assert_eq!(parse("?a=1&b=x+y&c"), teon!({"a": "1", "b": "x y", "c": ""}));
+        assert_eq!(parse("a[b][0]=1&a[b][1]=2&a[c]=x"), teon!({"a": {"b": ["1", "2"], "c": "x"}}));
+        assert_eq!(parse("a[]=1&a[]=2"), teon!({"a": ["1", "2"]}));
+        assert_eq!(parse("a=1&a=2&a=3"), teon!({"a": ["1", "2", "3"]}));
+        assert_eq!(parse("a[3]=c&a[1]=b"), teon!({"a": ["b", "c"]}));
+        assert_eq!(parse("a[0][id]=1&a[0][name]=x&a[1][id]=2"), teon!({"a": [{"id": "1", "name": "x"}, {"id": "2"}]}));
+        assert_eq!(parse("a%5Bb%5D=%E2%9C%93&k%26=v%3D"), teon!({"a": {"b": "✓"}, "k&": "v="}));
+        assert_eq!(parse("a[b=1&c]=2"), teon!({"a[b": "1", "c]": "2"}));
+        assert_eq!(parse("a=%zz%"), teon!({"a": "%zz%"}));
+    }
+
+    #[test]
+    fn query_string_parse_limits() {
+        assert_eq!(parse("a[b][c][d][e][f][g][h]=1"), teon!({"a": {"b": {"c": {"d": {"e": {"f": {"[g][h]": "1"}}}}}}}));
+        let options = QueryStringOptions { depth: 1, ..Default::default() };
+        assert_eq!(Value::from_query_string("a[b][c]=1", &options).unwrap(), teon!({"a": {"b": {"[c]": "1"}}}));
+        assert_eq!(parse("a[21]=x"), teon!({"a": {"21": "x"}}));
+        assert_eq!(parse("a[01]=x"), teon!({"a": {"01": "x"}}));
+        assert_eq!(parse("a[0]=x&a[b]=y"), teon!({"a": {"0": "x", "b": "y"}}));
+    }
+
+    #[test]
+    fn query_string_parse_comma() {
+        let options = QueryStringOptions { array_format: ArrayFormat::Comma, ..Default::default() };
+        assert_eq!(Value::from_query_string("a=1,2&b=x%2Cy", &options).unwrap(), teon!({"a": ["1", "2"], "b": "x,y"}));
+        assert_eq!(parse("a=1,2"), teon!({"a": "1,2"}));
+    }
+
+    #[test]
+    fn query_string_parse_errors() {
+        assert!(Value::from_query_string("a=1&a[b]=2", &QueryStringOptions::default()).is_err());
+        assert!(Value::from_query_string("a[b]=1&a=2", &QueryStringOptions::default()).is_err());
+        assert!(Value::from_query_string("a=%FF", &QueryStringOptions::default()).is_err());
+    }
+
+    #[test]
+    fn query_string_write_array_formats() {
+        let value = teon!({"a": [1, 2], "b": {"c": true, "d": null}, "e": [{"f": "x y"}]});
+        let write = |array_format| value.to_query_string(&QueryStringOptions { array_format, ..Default::default() }).unwrap();
+        assert_eq!(write(ArrayFormat::Indices), "a[0]=1&a[1]=2&b[c]=true&b[d]=&e[0][f]=x%20y");
+        assert_eq!(write(ArrayFormat::Brackets), "a[]=1&a[]=2&b[c]=true&b[d]=&e[0][f]=x%20y");
+        assert_eq!(write(ArrayFormat::Repeat), "a=1&a=2&b[c]=true&b[d]=&e[0][f]=x%20y");
+        assert_eq!(write(ArrayFormat::Comma), "a=1,2&b[c]=true&b[d]=&e[0][f]=x%20y");
+    }
+
+    #[test]
+    fn query_string_write_escapes() {
+        let value = teon!({"k&[x]": "a=b&c/✓", "sp": "+"});
+        let text = value.to_query_string(&QueryStringOptions::default()).unwrap();
+        assert_eq!(text, "k%26%5Bx%5D=a%3Db%26c%2F%E2%9C%93&sp=%2B");
+        assert_eq!(parse("sp=%2B").get("sp"), Some(&teon!("+")));
+        assert!(teon!([1]).to_query_string(&QueryStringOptions::default()).is_err());
+        assert!(teon!({"a": Value::Bytes(vec![1])}).to_query_string(&QueryStringOptions::default()).is_err());
+    }
+
+    #[test]
+    fn query_string_round_trip() {
+        let value = teon!({"user": {"name": "Ann Lee", "tags": ["a", "b&c"], "roles": [{"id": "1"}, {"id": "2"}]}, "q": "50%"});
+        for array_format in [ArrayFormat::Indices, ArrayFormat::Brackets, ArrayFormat::Repeat, ArrayFormat::Comma] {
+            let options = QueryStringOptions { array_format, ..Default::default() };
+            let text = value.to_query_string(&options).unwrap();
+            assert_eq!(Value::from_query_string(&text, &options).unwrap(), value, "{text}");
+        }
+    }
+}
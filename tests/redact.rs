@@ -0,0 +1,114 @@
+mod test {
+    use std::collections::BTreeMap;
+    use teo_teon::{teon, Value};
+    use teo_teon::redact::{HashKey, Redaction, RedactionRules};
+    use teo_teon::types::enum_variant::EnumVariant;
+    use teo_teon::types::file::File;
+    use teo_teon::types::regex::TeonRegex;
+
+    #[test]
+    fn redact_keys_by_name_and_glob() {
+        let value = teon!({
+            "user": {"name": "ann", "Password": "hunter2", "credentials": {"x": 1}},
+            "headers": {"Authorization": "Bearer abc", "accept": "*/*"},
+            "session_token": "xyz",
+        });
+        let rules = RedactionRules::common().key("credentials", Redaction::Mask).mask("***");
+        assert_eq!(value.redact(&rules), teon!({
+            "user": {"name": "ann", "Password": "***", "credentials": "***"},
+            "headers": {"Authorization": "***", "accept": "*/*"},
+            "session_token": "***",
+        }));
+    }
+
+    #[test]
+    fn redact_paths() {
+        let value = teon!({
+            "users": [{"ssn": "123-45-6789", "phone": 5551234567_i64}, {"ssn": "987-65-4321"}],
+            "ssn": "kept",
+            "deep": {"a": {"ssn": "000-00-0000"}},
+        });
+        let rules = RedactionRules::new()
+            .path("$.users[*].ssn", Redaction::Partial(4))
+            .path("$.users[*].phone", Redaction::Partial(4))
+            .path("$.deep.**.ssn", Redaction::Mask);
+        assert_eq!(value.redact(&rules), teon!({
+            "users": [{"ssn": "***-**-6789", "phone": "******4567"}, {"ssn": "***-**-4321"}],
+            "ssn": "kept",
+            "deep": {"a": {"ssn": "[REDACTED]"}},
+        }));
+        assert_eq!(teon!({"pin": "12"}).redact(&RedactionRules::new().key("pin", Redaction::Partial(4))), teon!({"pin": "**"}));
+    }
+
+    #[test]
+    fn redact_patterns_and_hash() {
+        let card = TeonRegex::new(r"\b\d{4}(?:[ -]?\d{4}){3}\b", "").unwrap();
+        let rules = RedactionRules::new()
+            .key("email", Redaction::Hash(HashKey::new("pepper").unwrap()))
+            .pattern(card, Redaction::Partial(4));
+        let value = teon!({"email": "ann@example.com", "notes": ["paid with 4111 1111 1111 1234", "no card"]});
+        let redacted = value.redact(&rules);
+        assert_eq!(redacted.get_path("notes.0"), Some(&teon!("paid with **** **** **** 1234")));
+        assert_eq!(redacted.get_path("notes.1"), Some(&teon!("no card")));
+        let hash = redacted.get_path("email").unwrap().as_str().unwrap();
+        assert!(hash.starts_with("hmac-sha256:"));
+        assert_eq!(hash.len(), 12 + 64);
+        assert_eq!(teon!({"email": "ann@example.com"}).redact(&rules).get_path("email").unwrap().as_str(), Some(hash));
+        let other = RedactionRules::new().key("email", Redaction::Hash(HashKey::new("salt").unwrap()));
+        assert_ne!(value.redact(&other).get_path("email").unwrap().as_str(), Some(hash));
+        assert_eq!(HashKey::new("").unwrap_err().message, "cannot hash redacted values with an empty key");
+        assert_eq!(format!("{:?}", Redaction::Hash(HashKey::new("pepper").unwrap())), "Hash(HashKey(..))");
+    }
+
+    #[test]
+    fn redact_hash_is_hmac_sha256() {
+        // RFC 4231, test case 2
+        let rules = RedactionRules::new().key("q", Redaction::Hash(HashKey::new("Jefe").unwrap()));
+        assert_eq!(
+            teon!({"q": "what do ya want for nothing?"}).redact(&rules),
+            teon!({"q": "hmac-sha256:5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"}),
+        );
+        // RFC 4231, test case 6, with a key longer than the block size
+        let rules = RedactionRules::new().key("q", Redaction::Hash(HashKey::new([0xaa; 131]).unwrap()));
+        assert_eq!(
+            teon!({"q": "Test Using Larger Than Block-Size Key - Hash Key First"}).redact(&rules),
+            teon!({"q": "hmac-sha256:60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"}),
+        );
+    }
+
+    #[test]
+    fn redact_patterns_in_keys_and_file_names() {
+        let email = TeonRegex::new(r"[\w.]+@[\w.]+", "").unwrap();
+        let rules = RedactionRules::new().pattern(email, Redaction::Mask);
+        let value = teon!({
+            "ann@example.com": {"bob@example.com": 1, "[REDACTED]": 2},
+            "role": Value::EnumVariant(EnumVariant {
+                value: "invite".to_owned(),
+                args: Some(BTreeMap::from([("cid@example.com".to_owned(), teon!(true))])),
+            }),
+            "upload": Value::File(File::from_bytes("dan@example.com avatar.png", vec![])),
+        });
+        let redacted = value.redact(&rules);
+        assert_eq!(redacted.get_path("[REDACTED]"), Some(&teon!({"[REDACTED] (2)": 1, "[REDACTED]": 2})));
+        assert_eq!(redacted.get_path("role"), Some(&Value::EnumVariant(EnumVariant {
+            value: "invite".to_owned(),
+            args: Some(BTreeMap::from([("[REDACTED]".to_owned(), teon!(true))])),
+        })));
+        match redacted.get_path("upload") {
+            Some(Value::File(file)) => assert_eq!(file.filename, "[REDACTED] avatar.png"),
+            other => panic!("expected a file, got {:?}", other),
+        }
+        assert!(!value.redacted(&rules).to_string().contains('@'));
+    }
+
+    #[test]
+    fn redacted_display() {
+        let value = teon!({"password": "hunter2", "tags": Value::Tuple(vec![teon!({"token": "t"})])});
+        let rules = RedactionRules::common();
+        let redacted = value.redacted(&rules);
+        assert_eq!(redacted.to_string(), value.redact(&rules).to_string());
+        assert!(!format!("{}", redacted).contains("hunter2"));
+        assert!(!format!("{:?}", redacted).contains("hunter2"));
+        assert!(!format!("{:?}", redacted).contains("\"t\""));
+    }
+}
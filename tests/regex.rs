@@ -0,0 +1,58 @@
+mod test {
+    use std::str::FromStr;
+    use bson::Bson;
+    use serde_json::json;
+    use teo_teon::types::regex::TeonRegex;
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn regex_flags() {
+        let regex = TeonRegex::from_str("/^a.c$/mis").unwrap();
+        assert_eq!(regex.pattern(), "^a.c$");
+        assert_eq!(regex.flags(), "ims");
+        assert!(regex.is_match("x\nA\nC"));
+        assert!(TeonRegex::new("abc", "g").is_err());
+        assert_eq!(TeonRegex::new("abc", "mi").unwrap(), TeonRegex::new("abc", "im").unwrap());
+        assert_ne!(TeonRegex::new("abc", "i").unwrap(), TeonRegex::new("abc", "").unwrap());
+    }
+
+    #[test]
+    fn regex_text_round_trip() {
+        let regex = TeonRegex::from_str("/a\\/b/i").unwrap();
+        assert_eq!(regex.pattern(), "a/b");
+        assert_eq!(regex.to_string(), "/a\\/b/i");
+        assert_eq!(TeonRegex::from_str(&regex.to_string()).unwrap(), regex);
+        assert_eq!(Value::Regex(regex).to_string(), "/a\\/b/i");
+    }
+
+    #[test]
+    fn regex_captures_and_replace() {
+        let regex = TeonRegex::new("(?P<year>\\d{4})-(\\d{2})(x)?", "").unwrap();
+        assert_eq!(regex.captures("on 2023-11"), teon!(["2023-11", "2023", "11", null]));
+        assert_eq!(regex.named_captures("on 2023-11"), teon!({"year": "2023"}));
+        assert_eq!(regex.captures("none"), Value::Null);
+        assert_eq!(regex.replace("2023-11 2024-01", "$2/$year"), teon!("11/2023 01/2024"));
+    }
+
+    #[test]
+    fn regex_json_round_trip() {
+        let value = Value::Regex(TeonRegex::new("abc", "im").unwrap());
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, json!({"$regex": "abc", "$options": "im"}));
+        assert_eq!(serde_json::Value::try_from(&value).unwrap(), json);
+        assert_eq!(Value::Regex(TeonRegex::try_from(&json).unwrap()), value);
+        let value = Value::Regex(TeonRegex::new("abc", "").unwrap());
+        assert_eq!(serde_json::to_value(&value).unwrap(), json!({"$regex": "abc"}));
+    }
+
+    #[test]
+    fn regex_bson_round_trip() {
+        let value = Value::Regex(TeonRegex::new("abc", "xi").unwrap());
+        let bson = Bson::try_from(&value).unwrap();
+        match &bson {
+            Bson::RegularExpression(regex) => assert_eq!(regex.options, "ix"),
+            _ => panic!("expect regular expression"),
+        }
+        assert_eq!(Value::try_from(bson).unwrap(), value);
+    }
+}
@@ -0,0 +1,149 @@
+mod test {
+    use teo_teon::{teon, Value};
+    use teo_teon::indexmap::indexmap;
+    use teo_teon::schema::{typescript, Type};
+    use teo_teon::types::enum_variant::EnumVariant;
+
+    fn boxed(t: Type) -> Box<Type> {
+        Box::new(t)
+    }
+
+    #[test]
+    fn type_of_value() {
+        assert_eq!(Type::of(&teon!(null)), Type::Null);
+        assert_eq!(Type::of(&Value::Int(1)), Type::Int);
+        assert_eq!(Type::of(&teon!([])), Type::Array(boxed(Type::Union(vec![]))));
+        assert_eq!(Type::of(&teon!([1, null, 2])), Type::Array(boxed(Type::Optional(boxed(Type::Int)))));
+        assert_eq!(Type::of(&Value::EnumVariant(EnumVariant { value: "a".to_owned(), args: None })), Type::Enum(vec!["a".to_owned()]));
+        assert_eq!(Type::of(&teon!({"a": 1, "b": [true]})), Type::Shape(indexmap! {
+            "a".to_owned() => Type::Int,
+            "b".to_owned() => Type::Array(boxed(Type::Bool)),
+        }));
+    }
+
+    #[test]
+    fn type_unify() {
+        assert_eq!(Type::Int.unify(Type::Int64), Type::Int64);
+        assert_eq!(Type::Float32.unify(Type::Float), Type::Float);
+        assert_eq!(Type::Null.unify(Type::String), Type::Optional(boxed(Type::String)));
+        assert_eq!(Type::String.unify(Type::Int).unify(Type::Int64), Type::Union(vec![Type::String, Type::Int64]));
+        assert_eq!(Type::String.unify(Type::Int).unify(Type::Null), Type::Optional(boxed(Type::Union(vec![Type::String, Type::Int]))));
+        assert_eq!(Type::Enum(vec!["a".to_owned()]).unify(Type::Enum(vec!["b".to_owned(), "a".to_owned()])), Type::Enum(vec!["a".to_owned(), "b".to_owned()]));
+        assert_eq!(Type::Tuple(vec![Type::Int]).unify(Type::Tuple(vec![Type::Int, Type::Int])), Type::Union(vec![Type::Tuple(vec![Type::Int]), Type::Tuple(vec![Type::Int, Type::Int])]));
+        assert_eq!(Type::Any.unify(Type::Bool), Type::Any);
+        assert_eq!(Type::Union(vec![]).unify(Type::Bool), Type::Bool);
+        assert_eq!(Type::of(&teon!([[], [1]])), Type::Array(boxed(Type::Array(boxed(Type::Int)))));
+    }
+
+    #[test]
+    fn type_missing_fields() {
+        let t = Type::from_values(&[teon!({"a": 1, "b": [1, 2], "c": null, "d": 1}), teon!({"a": 2, "b": []})]);
+        assert_eq!(t, Type::Shape(indexmap! {
+            "a".to_owned() => Type::Int,
+            "b".to_owned() => Type::Array(boxed(Type::Int)),
+            "c".to_owned() => Type::Optional(boxed(Type::Null)),
+            "d".to_owned() => Type::Optional(boxed(Type::Int)),
+        }));
+        let any = Type::Shape(indexmap! { "a".to_owned() => Type::Any });
+        let missing = any.clone().unify(Type::Shape(indexmap! {}));
+        assert_eq!(missing, Type::Shape(indexmap! { "a".to_owned() => Type::Optional(boxed(Type::Any)) }));
+        assert_eq!(missing.clone().unify(any), missing);
+    }
+
+    #[test]
+    fn type_from_values() {
+        let t = Type::from_values(&[teon!({"id": 1, "name": "a"}), teon!({"id": 2, "tags": ["x"]}), teon!({"id": 3, "name": null})]);
+        assert_eq!(t, Type::Shape(indexmap! {
+            "id".to_owned() => Type::Int,
+            "name".to_owned() => Type::Optional(boxed(Type::String)),
+            "tags".to_owned() => Type::Optional(boxed(Type::Array(boxed(Type::String)))),
+        }));
+        assert_eq!(Type::from_values(&[]), Type::Any);
+    }
+
+    #[test]
+    fn typescript_declarations() {
+        let types = indexmap! {
+            "Role".to_owned() => Type::Enum(vec!["admin".to_owned(), "user".to_owned()]),
+            "User".to_owned() => Type::Shape(indexmap! {
+                "id".to_owned() => Type::ObjectId,
+                "score".to_owned() => Type::Optional(boxed(Type::Float)),
+                "price".to_owned() => Type::Decimal,
+                "joined-at".to_owned() => Type::DateTime,
+                "roles".to_owned() => Type::Array(boxed(Type::Reference("Role".to_owned()))),
+                "pair".to_owned() => Type::Tuple(vec![Type::Int64, Type::Union(vec![Type::String, Type::Null])]),
+                "span".to_owned() => Type::Range(boxed(Type::Date)),
+                "meta".to_owned() => Type::Dictionary(boxed(Type::Any)),
+                "tags".to_owned() => Type::Array(boxed(Type::Enum(vec!["a".to_owned()]))),
+            }),
+        };
+        let generated = typescript::generate(&types).unwrap();
+        assert!(generated.declarations.contains("export type Role = \"admin\" | \"user\";\nexport declare function decodeRole(json: unknown): Role;\nexport declare function encodeRole(value: Role): unknown;\n"));
+        assert!(generated.declarations.contains(r#"export interface User {
+    id: string;
+    score?: number | null;
+    price: Decimal;
+    "joined-at": Date;
+    roles: Role[];
+    pair: [number, string | null];
+    span: Range<Date>;
+    meta: Record<string, unknown>;
+    tags: ("a")[];
+}
+"#));
+        assert!(generated.declarations.contains("export declare class Decimal {"));
+        assert!(generated.runtime.contains("\"Role\": {\n    \"enum\": [\n      \"admin\",\n      \"user\"\n    ]\n  }"));
+        assert!(generated.runtime.contains("export function decodeUser(json) {\n    return decode(DECLARATIONS.User, json);\n}"));
+        assert!(generated.runtime.contains("export function encodeUser(value) {\n    return encode(DECLARATIONS.User, value);\n}"));
+    }
+
+    #[test]
+    fn typescript_errors() {
+        assert!(typescript::generate(&indexmap! { "my-type".to_owned() => Type::Bool }).is_err());
+        assert!(typescript::generate(&indexmap! { "Decimal".to_owned() => Type::Bool }).is_err());
+        for name in ["Tag", "Bytes", "DateTime", "Regex", "Teon", "Date", "RegExp", "Uint8Array", "Record", "string"] {
+            assert!(typescript::generate(&indexmap! { name.to_owned() => Type::Bool }).is_err(), "{name}");
+        }
+        assert_eq!(
+            typescript::generate(&indexmap! { "Tag".to_owned() => Type::Bool }).unwrap_err().message,
+            "Cannot generate typescript for `Tag`, `decodeTag` is declared by the runtime",
+        );
+        assert!(typescript::generate(&indexmap! { "Tags".to_owned() => Type::Bool, "Bytes2".to_owned() => Type::Bytes }).is_ok());
+        for name in ["class", "default", "function", "enum", "interface", "await", "symbol"] {
+            assert_eq!(
+                typescript::generate(&indexmap! { name.to_owned() => Type::Bool }).unwrap_err().message,
+                format!("Cannot generate typescript for `{name}`, expect an identifier which is not reserved"),
+            );
+        }
+        let generated = typescript::generate(&indexmap! { "Klass".to_owned() => Type::Shape(indexmap! { "class".to_owned() => Type::Bool }) }).unwrap();
+        assert!(generated.declarations.contains("export interface Klass {\n    class: boolean;\n}\n"));
+        assert!(typescript::generate(&indexmap! { "A".to_owned() => Type::Array(boxed(Type::Reference("B".to_owned()))) }).is_err());
+    }
+    // Runs the generated runtime with node, when it's installed, and decodes every sample the
+    // type was inferred from.
+    #[test]
+    fn typescript_decodes_inferred_samples() {
+        let samples = [
+            teon!({"a": 1, "b": [1, 2], "c": null, "d": {"e": "x"}, "f": [], "g": Value::Decimal("1.5".parse().unwrap())}),
+            teon!({"a": 2, "b": [], "f": [null], "g": null}),
+            teon!({"a": Value::Int64(3), "b": [3], "c": null, "d": {"e": "y", "h": true}, "f": []}),
+        ];
+        let types = indexmap! { "Sample".to_owned() => Type::from_values(&samples), "Tags".to_owned() => Type::Bool };
+        let generated = typescript::generate(&types).unwrap();
+        let directory = std::env::temp_dir().join(format!("teon-typescript-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("models.mjs"), &generated.runtime).unwrap();
+        let samples: Vec<String> = samples.iter().map(|sample| serde_json::to_string(sample).unwrap()).collect();
+        std::fs::write(directory.join("test.mjs"), format!(
+            "import {{ decodeSample, encodeSample }} from \"./models.mjs\";\nfor (const json of [{}]) {{\n    decodeSample(encodeSample(decodeSample(json)));\n}}\n",
+            samples.join(", "),
+        )).unwrap();
+        let output = match std::process::Command::new("node").arg(directory.join("test.mjs")).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => panic!("{e}"),
+        };
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,79 @@
+mod test {
+    use std::sync::Arc;
+    use std::thread;
+    use teo_teon::{teon, Value};
+    use teo_teon::shared::SharedValue;
+    use teo_teon::types::range::Range;
+
+    fn sample() -> Value {
+        teon!({
+            "name": "Ann",
+            "age": 30,
+            "tags": ["a", "b"],
+            "range": Value::Range(Range { closed: true, start: Box::new(teon!(1)), end: Box::new(teon!(3)) }),
+            "pair": Value::Tuple(vec![teon!(1), teon!("x")]),
+            "bytes": Value::Bytes(vec![1, 2, 3]),
+        })
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = sample();
+        let shared = SharedValue::from(value.clone());
+        assert_eq!(Value::from(&shared), value);
+        assert_eq!(Value::from(shared.clone()), value);
+        assert_eq!(shared.to_value().get("age").unwrap().type_hint(), "Int");
+        assert_eq!(SharedValue::from(&value), shared);
+    }
+
+    #[test]
+    fn borrowing_accessors() {
+        let shared = SharedValue::from(sample());
+        assert_eq!(shared.get("name").and_then(SharedValue::as_str), Some("Ann"));
+        assert_eq!(shared["age"].as_int(), Some(30));
+        assert_eq!(shared.get_path("tags.1").and_then(SharedValue::as_str), Some("b"));
+        assert_eq!(shared["range"].as_range().unwrap().end.as_ref(), &teon!(3));
+        assert_eq!(shared.get_path("pair.1").and_then(SharedValue::as_str), Some("x"));
+        assert_eq!(shared["bytes"].as_bytes(), Some(&[1u8, 2, 3][..]));
+        assert!(shared["missing"].is_null());
+        assert_eq!(shared["tags"].type_hint(), "Array");
+        assert_eq!(SharedValue::Int(1), SharedValue::Int64(1));
+        assert_eq!(SharedValue::Int(1), SharedValue::Float(1.0));
+        assert_ne!(SharedValue::Int(1), SharedValue::from(teon!("1")));
+    }
+
+    #[test]
+    fn clones_share_and_copy_on_write() {
+        let shared = SharedValue::from(sample());
+        let clone = shared.clone();
+        assert!(clone.ptr_eq(&shared));
+        assert!(clone["tags"].ptr_eq(&shared["tags"]));
+        let mut changed = shared.clone();
+        changed.as_dictionary_mut().unwrap()["tags"].as_array_mut().unwrap().push(SharedValue::from(teon!("c")));
+        assert!(!changed.ptr_eq(&shared));
+        assert!(changed["name"].ptr_eq(&shared["name"]));
+        assert_eq!(Value::from(&changed["tags"]), teon!(["a", "b", "c"]));
+        assert_eq!(Value::from(&shared["tags"]), teon!(["a", "b"]));
+    }
+
+    #[test]
+    fn display() {
+        let value = sample();
+        let shared = SharedValue::from(value.clone());
+        assert_eq!(shared.to_string(), value.to_string());
+        assert_eq!(format!("{:#}", shared), format!("{:#}", value));
+        assert_eq!(shared["pair"].to_string(), "(1, \"x\")");
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let shared = Arc::new(SharedValue::from(sample()));
+        let handles: Vec<_> = (0..4).map(|_| {
+            let shared = SharedValue::clone(&shared);
+            thread::spawn(move || shared["tags"].as_array().unwrap().len())
+        }).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+    }
+}
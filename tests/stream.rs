@@ -0,0 +1,126 @@
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use teo_teon::{teon, Value};
+    use teo_teon::stream::{JsonEvent, JsonReader};
+    use teo_teon::types::regex::TeonRegex;
+
+    fn read(input: &str) -> teo_result::Result<Value> {
+        let mut reader = JsonReader::tagged(input.as_bytes());
+        let value = reader.read_value()?.unwrap();
+        assert!(reader.next_event()?.is_none());
+        Ok(value)
+    }
+
+    #[test]
+    fn stream_events() {
+        let events: Vec<JsonEvent> = JsonReader::new(r#"{"a": [1, 2.5, "s"], "b": {}, "c": null}"#.as_bytes()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(events, vec![
+            JsonEvent::StartDictionary,
+            JsonEvent::Key("a".to_owned()),
+            JsonEvent::StartArray,
+            JsonEvent::Scalar(Value::Int64(1)),
+            JsonEvent::Scalar(Value::Float(2.5)),
+            JsonEvent::Scalar(teon!("s")),
+            JsonEvent::EndArray,
+            JsonEvent::Key("b".to_owned()),
+            JsonEvent::StartDictionary,
+            JsonEvent::EndDictionary,
+            JsonEvent::Key("c".to_owned()),
+            JsonEvent::Scalar(Value::Null),
+            JsonEvent::EndDictionary,
+        ]);
+    }
+
+    #[test]
+    fn stream_matches_serde_json() {
+        let input = r#" {"n": -12e-1, "big": 18446744073709551615, "s": "\"\\\/\b\f\n\r\t\u00e9\ud83d\ude00", "t": [true, false, [], {"x": 0}]} "#;
+        let json: serde_json::Value = serde_json::from_str(input).unwrap();
+        let mut reader = JsonReader::new(input.as_bytes());
+        let value = reader.read_value().unwrap().unwrap();
+        assert_eq!(value.get("s"), Some(&Value::from(json.get("s").unwrap())));
+        assert_eq!(value.get("t"), Some(&Value::from(json.get("t").unwrap())));
+        assert_eq!(value.get("n"), Some(&Value::Float(-1.2)));
+        assert_eq!(value.get("big"), Some(&Value::Float(18446744073709551615.0)));
+    }
+
+    #[test]
+    fn stream_tagged_values() {
+        let value = teon!({
+            "int": Value::Int(1),
+            "decimal": Value::Decimal(BigDecimal::from_str("1.5").unwrap()),
+            "date": Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            "tuple": Value::Tuple(vec![teon!(1), teon!("a")]),
+            "regex": Value::Regex(TeonRegex::new("a+", "i").unwrap()),
+            "bytes": Value::Bytes(vec![1, 2, 3]),
+            "nested": [{"$float32": 0.5}],
+            "$other": 1
+        });
+        let text = serde_json::to_string(&value).unwrap();
+        let read_back = read(&text).unwrap();
+        assert_eq!(read_back.get("int"), Some(&Value::Int(1)));
+        assert_eq!(read_back.get("decimal"), value.get("decimal"));
+        assert_eq!(read_back.get("date"), value.get("date"));
+        assert_eq!(read_back.get("tuple"), Some(&Value::Tuple(vec![Value::Int64(1), teon!("a")])));
+        assert_eq!(read_back.get("regex"), value.get("regex"));
+        assert_eq!(read_back.get("bytes"), value.get("bytes"));
+        assert_eq!(read_back.get("$other"), Some(&Value::Int64(1)));
+        let plain = JsonReader::new(r#"{"$date": "2024-01-02"}"#.as_bytes()).read_value().unwrap().unwrap();
+        assert!(plain.is_dictionary());
+    }
+
+    #[test]
+    fn stream_tagged_key_order() {
+        let regex = Value::Regex(TeonRegex::new("a+", "im").unwrap());
+        assert_eq!(read(r#"{"$options": "mi", "$regex": "a+"}"#).unwrap(), regex);
+        assert_eq!(read(r#"[{"$options": "mi", "$regex": "a+"}]"#).unwrap(), teon!([regex]));
+        assert_eq!(read(r#"{"$options": {"a": [1]}}"#).unwrap(), teon!({"$options": {"a": [Value::Int64(1)]}}));
+        assert_eq!(read(r#"{"$options": "i", "b": 2}"#).unwrap(), teon!({"$options": "i", "b": Value::Int64(2)}));
+        let events: Vec<JsonEvent> = JsonReader::tagged(r#"{"$options": [1], "b": 2}"#.as_bytes()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(events, vec![
+            JsonEvent::StartDictionary,
+            JsonEvent::Key("$options".to_owned()),
+            JsonEvent::StartArray,
+            JsonEvent::Scalar(Value::Int64(1)),
+            JsonEvent::EndArray,
+            JsonEvent::Key("b".to_owned()),
+            JsonEvent::Scalar(Value::Int64(2)),
+            JsonEvent::EndDictionary,
+        ]);
+        assert!(read(r#"{"$options": 1, "$regex": "a"}"#).is_err());
+    }
+
+    #[test]
+    fn stream_max_depth() {
+        assert!(read(&"[".repeat(200000)).is_err());
+        assert!(read(&"{\"a\": ".repeat(200000)).is_err());
+        assert!(read(&format!("{}{}", "[".repeat(128), "]".repeat(128))).is_ok());
+        assert!(read(&format!("{}{}", "[".repeat(129), "]".repeat(129))).is_err());
+        let nested = format!("{}{}", "[".repeat(3), "]".repeat(3));
+        assert!(JsonReader::new(nested.as_bytes()).max_depth(2).read_value().is_err());
+        assert!(JsonReader::new(nested.as_bytes()).max_depth(3).read_value().is_ok());
+    }
+
+    #[test]
+    fn stream_array_elements() {
+        let input = r#"[{"id": 1}, {"id": 2}, 3]"#;
+        let mut reader = JsonReader::new(input.as_bytes());
+        let mut elements = reader.elements().unwrap();
+        assert_eq!(elements.next().unwrap().unwrap(), teon!({"id": 1}));
+        assert_eq!(elements.next().unwrap().unwrap(), teon!({"id": 2}));
+        assert_eq!(elements.next().unwrap().unwrap(), Value::Int64(3));
+        assert!(elements.next().is_none());
+        assert!(JsonReader::new("{}".as_bytes()).elements().is_err());
+    }
+
+    #[test]
+    fn stream_errors() {
+        for input in ["", "[1,]", "{\"a\" 1}", "[1 2]", "01", "1.", "\"\n\"", "tru", "[1]]", "{\"a\": 1", "\"\\x\""] {
+            assert!(read(input).is_err(), "{input}");
+        }
+        assert!(read(r#"{"$date": "2024-13-01"}"#).is_err());
+        assert!(read(r#"{"$int": 1, "other": 2}"#).is_err());
+        assert!(read(r#"{"$int": 3000000000}"#).is_err());
+    }
+}
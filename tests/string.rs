@@ -0,0 +1,51 @@
+mod test {
+    use teo_teon::types::regex::TeonRegex;
+    use teo_teon::ops::string::MAX_STRING_SIZE;
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn string_ops() {
+        let value = teon!("  Héllo World  ");
+        assert_eq!(value.str_ops().len().unwrap(), teon!(15_i64));
+        assert_eq!(value.str_ops().trim().unwrap(), teon!("Héllo World"));
+        assert_eq!(teon!("Héllo").str_ops().upper().unwrap(), teon!("HÉLLO"));
+        assert_eq!(teon!("Héllo").str_ops().lower().unwrap(), teon!("héllo"));
+        assert_eq!(teon!("abc").str_ops().starts_with(&teon!("ab")).unwrap(), teon!(true));
+        assert_eq!(teon!("abc").str_ops().ends_with(&teon!("ab")).unwrap(), teon!(false));
+        assert_eq!(teon!("abc").str_ops().contains(&teon!("b")).unwrap(), teon!(true));
+    }
+
+    #[test]
+    fn string_split_join_replace() {
+        let regex = Value::Regex(TeonRegex::new("\\s*,\\s*", "").unwrap());
+        let parts = teon!("a , b,c").str_ops().split(&regex).unwrap();
+        assert_eq!(parts, teon!(["a", "b", "c"]));
+        assert_eq!(parts.str_ops().join(&teon!("-")).unwrap(), teon!("a-b-c"));
+        assert_eq!(teon!("a.b.c").str_ops().split(&teon!(".")).unwrap(), teon!(["a", "b", "c"]));
+        let regex = Value::Regex(TeonRegex::new("(\\d+)", "").unwrap());
+        assert_eq!(teon!("a1b22").str_ops().replace(&regex, &teon!("<$1>")).unwrap(), teon!("a<1>b<22>"));
+        assert_eq!(teon!("aXbX").str_ops().replace(&teon!("X"), &teon!("-")).unwrap(), teon!("a-b-"));
+    }
+
+    #[test]
+    fn string_pad_and_repeat() {
+        assert_eq!(teon!("7").str_ops().pad_start(&teon!(3), &teon!("0")).unwrap(), teon!("007"));
+        assert_eq!(teon!("7").str_ops().pad_end(&teon!(3), &teon!("ab")).unwrap(), teon!("7ab"));
+        assert_eq!(teon!("x").str_ops().pad(&teon!(4), &teon!("*")).unwrap(), teon!("*x**"));
+        assert_eq!((&teon!("ab") * &teon!(3)).unwrap(), teon!("ababab"));
+        assert!((&teon!("ab") * &teon!(-1)).is_err());
+        let error = (&teon!("ab") * &Value::Int64(300000000000000)).unwrap_err();
+        assert_eq!(error.message, format!("cannot mul String, the result exceeds {} bytes", MAX_STRING_SIZE));
+        assert!((&teon!("ab") * &Value::Int64(i64::MAX)).is_err());
+        assert_eq!((&teon!("") * &Value::Int64(i64::MAX)).unwrap(), teon!(""));
+        assert!(teon!("x").str_ops().pad_start(&Value::Int64(i64::MAX), &teon!(" ")).is_err());
+    }
+
+    #[test]
+    fn string_ops_type_errors() {
+        let error = teon!(1).str_ops().upper().unwrap_err();
+        assert_eq!(error.message, "cannot upper Int");
+        let error = teon!("a").str_ops().split(&teon!(1)).unwrap_err();
+        assert_eq!(error.message, "cannot split String with Int");
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "toml")]
+
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn toml_parse_dates() {
+        let value = Value::from_toml_str(r#"
+title = "seed"
+count = 3
+
+[dates]
+offset = 2024-01-02T03:04:05+02:00
+local = 2024-01-02T03:04:05.5
+day = 2024-01-02
+"#).unwrap();
+        assert_eq!(value.get("title"), Some(&teon!("seed")));
+        assert_eq!(value.get("count"), Some(&Value::Int64(3)));
+        let dates = value.get("dates").unwrap();
+        assert_eq!(dates.get("offset"), Some(&Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 1, 4, 5).unwrap())));
+        assert_eq!(dates.get("local"), Some(&Value::DateTime(Utc.timestamp_millis_opt(1704164645500).unwrap())));
+        assert_eq!(dates.get("day"), Some(&Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())));
+        assert!(Value::from_toml_str("at = 07:32:00").is_err());
+        assert!(Value::from_toml_str("a = ").is_err());
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let value = teon!({
+            "name": "app",
+            "port": 8080,
+            "day": Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            "server": {"hosts": ["a", "b"], "at": Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())}
+        });
+        let text = value.to_toml_string().unwrap();
+        let read = Value::from_toml_str(&text).unwrap();
+        assert_eq!(read, teon!({
+            "name": "app",
+            "port": Value::Int64(8080),
+            "day": Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            "server": {"hosts": ["a", "b"], "at": Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())}
+        }));
+    }
+
+    #[test]
+    fn toml_rejects_unrepresentable_values() {
+        let error = teon!({"a": {"b": null}}).to_toml_string().unwrap_err();
+        assert_eq!(error.message, "Cannot convert Null at `a.b` into toml");
+        let error = teon!({"a": [1, Value::Decimal(BigDecimal::from_str("1").unwrap())]}).to_toml_string().unwrap_err();
+        assert_eq!(error.message, "Cannot convert Decimal at `a[1]` into toml");
+        assert!(teon!([1]).to_toml_string().is_err());
+        let error = teon!({"a": Value::Date(NaiveDate::from_ymd_opt(10000, 1, 1).unwrap())}).to_toml_string().unwrap_err();
+        assert_eq!(error.message, "Cannot convert Date at `a` into toml");
+        let error = teon!({"a": Value::Date(NaiveDate::from_ymd_opt(-1, 1, 1).unwrap())}).to_toml_string().unwrap_err();
+        assert_eq!(error.message, "Cannot convert Date at `a` into toml");
+        let error = teon!({"a": [Value::DateTime(Utc.with_ymd_and_hms(10000, 1, 1, 0, 0, 0).unwrap())]}).to_toml_string().unwrap_err();
+        assert_eq!(error.message, "Cannot convert DateTime at `a[0]` into toml");
+        assert!(teon!({"a": Value::Date(NaiveDate::from_ymd_opt(5, 1, 1).unwrap())}).to_toml_string().is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+mod test {
+    use std::collections::BTreeMap;
+    use teo_teon::{teon, Value};
+    use teo_teon::types::enum_variant::EnumVariant;
+    use teo_teon::types::range::Range;
+    use teo_teon::visit::{Path, PathSegment, Visitor, VisitorMut, Walk};
+
+    fn nested() -> Value {
+        let mut args = BTreeMap::new();
+        args.insert("reason".to_owned(), teon!("late"));
+        teon!({
+            "items": [1, Value::Tuple(vec![teon!("a"), teon!(2)])],
+            "period": Value::Range(Range { closed: false, start: Box::new(teon!(1)), end: Box::new(teon!(5)) }),
+            "status": Value::EnumVariant(EnumVariant { value: "cancelled".to_owned(), args: Some(args) }),
+            "odd key": null,
+        })
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        stop_at: Option<String>,
+    }
+
+    impl Visitor for Recorder {
+
+        fn enter(&mut self, path: &Path, value: &Value) -> Walk {
+            if self.stop_at.as_deref() == Some(path.to_string().as_str()) {
+                return Walk::Stop;
+            }
+            if value.is_tuple() {
+                return Walk::Skip;
+            }
+            Walk::Continue
+        }
+
+        fn leave(&mut self, path: &Path, _value: &Value) {
+            self.events.push(format!("leave {}", path));
+        }
+
+        fn visit_int(&mut self, path: &Path, value: i32) {
+            self.events.push(format!("{} {}", path, value));
+        }
+
+        fn visit_string(&mut self, path: &Path, value: &str) {
+            self.events.push(format!("{} {}", path, value));
+        }
+
+        fn visit_null(&mut self, path: &Path) {
+            self.events.push(format!("{} null", path));
+        }
+    }
+
+    #[test]
+    fn walk_with_paths() {
+        let mut recorder = Recorder::default();
+        nested().walk(&mut recorder);
+        assert_eq!(recorder.events, vec![
+            "$.items[0] 1",
+            "leave $.items[0]",
+            "leave $.items",
+            "$.period[start] 1",
+            "leave $.period[start]",
+            "$.period[end] 5",
+            "leave $.period[end]",
+            "leave $.period",
+            "$.status(reason) late",
+            "leave $.status(reason)",
+            "leave $.status",
+            "$[\"odd key\"] null",
+            "leave $[\"odd key\"]",
+            "leave $",
+        ]);
+        let mut recorder = Recorder { stop_at: Some("$.period[end]".to_owned()), ..Default::default() };
+        nested().walk(&mut recorder);
+        assert_eq!(recorder.events.last().unwrap(), "leave $.period[start]");
+    }
+
+    struct Upper;
+
+    impl VisitorMut for Upper {
+
+        fn enter(&mut self, path: &Path, value: &mut Value) -> Walk {
+            if path.key() == Some("odd key") {
+                *value = teon!(["x"]);
+            }
+            Walk::Continue
+        }
+
+        fn visit_string(&mut self, _path: &Path, value: &mut String) {
+            *value = value.to_uppercase();
+        }
+
+        fn visit_int(&mut self, _path: &Path, value: &mut i32) {
+            *value += 1;
+        }
+    }
+
+    #[test]
+    fn walk_mut_changes_values() {
+        let mut value = nested();
+        value.walk_mut(&mut Upper);
+        assert_eq!(value.get_path("items.1.0"), Some(&teon!("A")));
+        assert_eq!(value.get_path("items.1.1"), Some(&teon!(3)));
+        assert_eq!(value.as_dictionary().unwrap()["period"].as_range().unwrap().end.as_ref(), &teon!(6));
+        assert_eq!(value.as_dictionary().unwrap()["status"].as_enum_variant().unwrap().args.as_ref().unwrap()["reason"], teon!("LATE"));
+        assert_eq!(value.as_dictionary().unwrap()["odd key"], teon!(["X"]));
+    }
+
+    #[test]
+    fn fold_and_transform() {
+        let value = nested();
+        let ints = value.fold(0, |sum, _, value| sum + value.as_int().unwrap_or(0));
+        assert_eq!(ints, 9);
+        let deepest = value.fold(Path::root(), |deepest, path, _| if path.len() > deepest.len() { path.clone() } else { deepest });
+        assert_eq!(deepest, Path::from(vec![PathSegment::Key("items".to_owned()), PathSegment::Index(1), PathSegment::Index(0)]));
+        assert_eq!(deepest.to_string(), "$.items[1][0]");
+        assert_eq!(PathSegment::Key("first name".to_owned()).to_string(), "[\"first name\"]");
+        assert_eq!(PathSegment::Argument("max".to_owned()).to_string(), "(max)");
+        let mut order = vec![];
+        let transformed = value.transform(|path, value| {
+            order.push(path.to_string());
+            match value {
+                Value::Int(i) => Value::Int(-i),
+                Value::Null => teon!("none"),
+                value => value,
+            }
+        });
+        assert_eq!(order[..3], ["$.items[0]", "$.items[1][0]", "$.items[1][1]"]);
+        assert_eq!(order.last().unwrap(), "$");
+        assert_eq!(transformed.get_path("items.1.1"), Some(&teon!(-2)));
+        assert_eq!(transformed.as_dictionary().unwrap()["period"].as_range().unwrap().start.as_ref(), &teon!(-1));
+        assert_eq!(transformed.as_dictionary().unwrap()["odd key"], teon!("none"));
+    }
+}
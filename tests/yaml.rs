@@ -0,0 +1,65 @@
+#![cfg(feature = "yaml")]
+
+mod test {
+    use std::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use bson::oid::ObjectId;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use teo_teon::{teon, Value};
+
+    #[test]
+    fn yaml_parse_plain_and_tagged() {
+        let value = Value::from_yaml_str(r#"
+name: seed
+count: 3
+ratio: 0.5
+enabled: true
+missing: ~
+tags: [a, b]
+1: numeric key
+price: !decimal 12.50
+owner: !objectId 6541a1a8e3b5a1c2d3e4f506
+day: !date 2024-01-02
+at: !datetime 2024-01-02T03:04:05.000Z
+"#).unwrap();
+        assert_eq!(value.get("name"), Some(&teon!("seed")));
+        assert_eq!(value.get("count"), Some(&Value::Int64(3)));
+        assert_eq!(value.get("ratio"), Some(&Value::Float(0.5)));
+        assert_eq!(value.get("missing"), Some(&Value::Null));
+        assert_eq!(value.get("tags"), Some(&teon!(["a", "b"])));
+        assert_eq!(value.get("1"), Some(&teon!("numeric key")));
+        assert_eq!(value.get("price"), Some(&Value::Decimal(BigDecimal::from_str("12.50").unwrap())));
+        assert_eq!(value.get("owner"), Some(&Value::ObjectId(ObjectId::parse_str("6541a1a8e3b5a1c2d3e4f506").unwrap())));
+        assert_eq!(value.get("day"), Some(&Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())));
+        assert_eq!(value.get("at"), Some(&Value::DateTime(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())));
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        let value = teon!({
+            "decimal": Value::Decimal(BigDecimal::from_str("1.5").unwrap()),
+            "date": Value::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            "tuple": Value::Tuple(vec![teon!(1), teon!("a")]),
+            "bytes": Value::Bytes(vec![0, 255]),
+            "float32": Value::Float32(0.25),
+            "list": [1, {"nested": null}]
+        });
+        let text = value.to_yaml_string().unwrap();
+        assert!(text.contains("decimal: !decimal '1.5'") || text.contains("decimal: !decimal 1.5"), "{text}");
+        let read = Value::from_yaml_str(&text).unwrap();
+        assert_eq!(read.get("decimal"), value.get("decimal"));
+        assert_eq!(read.get("date"), value.get("date"));
+        assert_eq!(read.get("tuple"), Some(&Value::Tuple(vec![Value::Int64(1), teon!("a")])));
+        assert_eq!(read.get("bytes"), value.get("bytes"));
+        assert_eq!(read.get("float32"), value.get("float32"));
+        assert_eq!(read.get("list"), Some(&teon!([1, {"nested": null}])));
+    }
+
+    #[test]
+    fn yaml_errors() {
+        assert!(Value::from_yaml_str("a: !unknown 1").is_err());
+        assert!(Value::from_yaml_str("a: !date tomorrow").is_err());
+        assert!(Value::from_yaml_str("a: [").is_err());
+        assert!(Value::from_yaml_str("? [1]\n: 2").is_err());
+    }
+}